@@ -3,7 +3,7 @@ use locustdb_serialization::api::{
     AnyVal, Column, ColumnNameRequest, ColumnNameResponse, EncodingOpts, MultiQueryRequest,
     MultiQueryResponse,
 };
-use locustdb_serialization::event_buffer::{ColumnBuffer, ColumnData, EventBuffer, TableBuffer};
+use locustdb_serialization::event_buffer::{ColumnBuffer, EventBuffer, TableBuffer};
 use reqwest::header::CONTENT_TYPE;
 use std::collections::HashMap;
 use std::sync::Once;
@@ -82,6 +82,7 @@ impl Client {
             } else {
                 None
             },
+            timeout_ms: None,
         };
         let request_start_ms = performance.now();
         let response = self
@@ -163,9 +164,7 @@ impl Client {
         let mut _columns = HashMap::new();
         for (name, value) in columns.into_iter().zip(values.iter()) {
             let val = js_value_to_any_val(value.clone())?;
-            let mut buffer = ColumnBuffer {
-                data: ColumnData::default(),
-            };
+            let mut buffer = ColumnBuffer::default();
             buffer.push(val, 0);
             _columns.insert(name, buffer);
         }
@@ -180,10 +180,20 @@ fn create_buffer(field_ident: &Ident, field_type: &Type) -> Stmt {
         parse_quote!(let #field_ident = self.buffer_provider.buffer_val(#field_name);)
     } else if *field_type == parse_quote!(BufferRef<ValRows<'static>>) {
         parse_quote!(let #field_ident = self.buffer_provider.buffer_val_rows(#field_name);)
+    } else if *field_type == parse_quote!(BufferRef<ValSets<'static>>) {
+        parse_quote!(let #field_ident = self.buffer_provider.buffer_val_sets(#field_name);)
+    } else if *field_type == parse_quote!(BufferRef<Sketches>) {
+        parse_quote!(let #field_ident = self.buffer_provider.buffer_sketches(#field_name);)
+    } else if *field_type == parse_quote!(BufferRef<FloatLists>) {
+        parse_quote!(let #field_ident = self.buffer_provider.buffer_float_lists(#field_name);)
+    } else if *field_type == parse_quote!(BufferRef<WelfordStates>) {
+        parse_quote!(let #field_ident = self.buffer_provider.buffer_welford_states(#field_name);)
     } else if *field_type == parse_quote!(BufferRef<usize>) {
         parse_quote!(let #field_ident = self.buffer_provider.buffer_usize(#field_name);)
     } else if *field_type == parse_quote!(BufferRef<i64>) {
         parse_quote!(let #field_ident = self.buffer_provider.buffer_i64(#field_name);)
+    } else if *field_type == parse_quote!(BufferRef<u64>) {
+        parse_quote!(let #field_ident = self.buffer_provider.buffer_u64(#field_name);)
     } else if *field_type == parse_quote!(BufferRef<u32>) {
         parse_quote!(let #field_ident = self.buffer_provider.buffer_u32(#field_name);)
     } else if *field_type == parse_quote!(BufferRef<Nullable<i64>>) {
@@ -222,10 +232,20 @@ fn convert(expr: Expr, field_type: &Type) -> Expr {
         parse_quote!(#expr.val().unwrap())
     } else if *field_type == parse_quote!(BufferRef<ValRows<'static>>) {
         parse_quote!(#expr.val_rows().unwrap())
+    } else if *field_type == parse_quote!(BufferRef<ValSets<'static>>) {
+        parse_quote!(#expr.val_sets().unwrap())
+    } else if *field_type == parse_quote!(BufferRef<Sketches>) {
+        parse_quote!(#expr.sketches().unwrap())
+    } else if *field_type == parse_quote!(BufferRef<FloatLists>) {
+        parse_quote!(#expr.float_lists().unwrap())
+    } else if *field_type == parse_quote!(BufferRef<WelfordStates>) {
+        parse_quote!(#expr.welford_states().unwrap())
     } else if *field_type == parse_quote!(BufferRef<usize>) {
         parse_quote!(#expr.usize().unwrap())
     } else if *field_type == parse_quote!(BufferRef<i64>) {
         parse_quote!(#expr.i64().unwrap())
+    } else if *field_type == parse_quote!(BufferRef<u64>) {
+        parse_quote!(#expr.u64().unwrap())
     } else if *field_type == parse_quote!(BufferRef<u32>) {
         parse_quote!(#expr.u32().unwrap())
     } else if *field_type == parse_quote!(BufferRef<Nullable<i64>>) {
@@ -257,6 +277,16 @@ fn hash(field_ident: &Ident, field_type: &Type) -> Stmt {
     } else if *field_type == parse_quote!(bool) {
         parse_quote!(hasher.update(&[#field_ident as u8]);)
     } else if *field_type == parse_quote!(Aggregator) {
+        parse_quote!(hasher.update(&#field_ident.signature());)
+    } else if *field_type == parse_quote!(StringFunction) {
+        parse_quote!(hasher.update(&#field_ident.signature());)
+    } else if *field_type == parse_quote!(MathFunction) {
+        parse_quote!(hasher.update(&#field_ident.signature());)
+    } else if *field_type == parse_quote!(Vec<String>) {
+        parse_quote!(for s in &#field_ident { hasher.update(&s.len().to_ne_bytes()); hasher.update(s.as_bytes()); })
+    } else if *field_type == parse_quote!(Vec<i64>) {
+        parse_quote!(for x in &#field_ident { hasher.update(&x.to_ne_bytes()); })
+    } else if *field_type == parse_quote!(TimeGranularity) {
         parse_quote!(hasher.update(&[#field_ident as u8]);)
     } else if *field_type == parse_quote!(TypedBufferRef) {
         parse_quote!(hasher.update(&#field_ident.buffer.i.to_ne_bytes());)
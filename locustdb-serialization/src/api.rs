@@ -23,12 +23,16 @@ pub struct ColumnNameResponse {
 #[derive(Serialize, Deserialize, Debug)]
 pub struct QueryRequest {
     pub query: String,
+    /// Overrides the default query timeout of the server
+    pub timeout_ms: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct MultiQueryRequest {
     pub queries: Vec<String>,
     pub encoding_opts: Option<EncodingOpts>,
+    /// Overrides the default query timeout of the server for every query
+    pub timeout_ms: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
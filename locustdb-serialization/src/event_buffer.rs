@@ -3,6 +3,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::api::AnyVal;
 use crate::default_reader_options;
+use crate::logical_type::LogicalType;
 use crate::wal_segment_capnp::{self, table_segment_list};
 
 #[derive(Default, Clone, Debug)]
@@ -86,6 +87,7 @@ impl TableBuffer {
 #[derive(Default, Clone, Debug)]
 pub struct ColumnBuffer {
     pub data: ColumnData,
+    pub logical_type: LogicalType,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -234,6 +236,7 @@ impl EventBuffer {
             for (j, (colname, column)) in table.columns.iter().enumerate() {
                 let mut column_builder = columns.reborrow().get(j as u32);
                 column_builder.set_name(colname);
+                column_builder.set_logical_type(column.logical_type.into());
                 match &column.data {
                     ColumnData::Dense(f64s) => {
                         column_builder.get_data().set_f64(&f64s[..]).unwrap();
@@ -347,7 +350,8 @@ impl EventBuffer {
                         ColumnData::Mixed(values)
                     }
                 };
-                columns.insert(colname, ColumnBuffer { data });
+                let logical_type = column.get_logical_type()?.into();
+                columns.insert(colname, ColumnBuffer { data, logical_type });
             }
             tables.insert(name, TableBuffer { len, columns });
         }
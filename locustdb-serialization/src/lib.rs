@@ -4,6 +4,7 @@ pub mod wal_segment_capnp;
 pub mod api_capnp;
 pub mod api;
 pub mod event_buffer;
+pub mod logical_type;
 
 
 pub fn default_reader_options() -> capnp::message::ReaderOptions {
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{partition_segment_capnp, wal_segment_capnp};
+
+/// Interpretation of the physical values stored in a column.
+/// Booleans and timestamps are stored as integers, the logical type determines how they are compared and displayed.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+pub enum LogicalType {
+    #[default]
+    Plain,
+    Bool,
+    Timestamp(TimeUnit),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum TimeUnit {
+    Second,
+    Millisecond,
+    Microsecond,
+    Nanosecond,
+}
+
+impl TimeUnit {
+    pub fn per_second(self) -> i64 {
+        match self {
+            TimeUnit::Second => 1,
+            TimeUnit::Millisecond => 1_000,
+            TimeUnit::Microsecond => 1_000_000,
+            TimeUnit::Nanosecond => 1_000_000_000,
+        }
+    }
+}
+
+impl From<LogicalType> for partition_segment_capnp::LogicalType {
+    fn from(logical_type: LogicalType) -> Self {
+        use partition_segment_capnp::LogicalType as L;
+        match logical_type {
+            LogicalType::Plain => L::Plain,
+            LogicalType::Bool => L::Bool,
+            LogicalType::Timestamp(TimeUnit::Second) => L::TimestampSecond,
+            LogicalType::Timestamp(TimeUnit::Millisecond) => L::TimestampMillisecond,
+            LogicalType::Timestamp(TimeUnit::Microsecond) => L::TimestampMicrosecond,
+            LogicalType::Timestamp(TimeUnit::Nanosecond) => L::TimestampNanosecond,
+        }
+    }
+}
+
+impl From<partition_segment_capnp::LogicalType> for LogicalType {
+    fn from(logical_type: partition_segment_capnp::LogicalType) -> Self {
+        use partition_segment_capnp::LogicalType as L;
+        match logical_type {
+            L::Plain => LogicalType::Plain,
+            L::Bool => LogicalType::Bool,
+            L::TimestampSecond => LogicalType::Timestamp(TimeUnit::Second),
+            L::TimestampMillisecond => LogicalType::Timestamp(TimeUnit::Millisecond),
+            L::TimestampMicrosecond => LogicalType::Timestamp(TimeUnit::Microsecond),
+            L::TimestampNanosecond => LogicalType::Timestamp(TimeUnit::Nanosecond),
+        }
+    }
+}
+
+impl From<LogicalType> for wal_segment_capnp::LogicalType {
+    fn from(logical_type: LogicalType) -> Self {
+        use wal_segment_capnp::LogicalType as L;
+        match logical_type {
+            LogicalType::Plain => L::Plain,
+            LogicalType::Bool => L::Bool,
+            LogicalType::Timestamp(TimeUnit::Second) => L::TimestampSecond,
+            LogicalType::Timestamp(TimeUnit::Millisecond) => L::TimestampMillisecond,
+            LogicalType::Timestamp(TimeUnit::Microsecond) => L::TimestampMicrosecond,
+            LogicalType::Timestamp(TimeUnit::Nanosecond) => L::TimestampNanosecond,
+        }
+    }
+}
+
+impl From<wal_segment_capnp::LogicalType> for LogicalType {
+    fn from(logical_type: wal_segment_capnp::LogicalType) -> Self {
+        use wal_segment_capnp::LogicalType as L;
+        match logical_type {
+            L::Plain => LogicalType::Plain,
+            L::Bool => LogicalType::Bool,
+            L::TimestampSecond => LogicalType::Timestamp(TimeUnit::Second),
+            L::TimestampMillisecond => LogicalType::Timestamp(TimeUnit::Millisecond),
+            L::TimestampMicrosecond => LogicalType::Timestamp(TimeUnit::Microsecond),
+            L::TimestampNanosecond => LogicalType::Timestamp(TimeUnit::Nanosecond),
+        }
+    }
+}
@@ -309,6 +309,10 @@ pub mod column {
     pub fn has_data(&self) -> bool {
       !self.reader.get_pointer_field(3).is_null()
     }
+    #[inline]
+    pub fn get_logical_type(self) -> ::core::result::Result<crate::partition_segment_capnp::LogicalType,::capnp::NotInSchema> {
+      ::core::convert::TryInto::try_into(self.reader.get_data_field::<u16>(5))
+    }
   }
 
   pub struct Builder<'a> { builder: ::capnp::private::layout::StructBuilder<'a> }
@@ -429,6 +433,14 @@ pub mod column {
     pub fn has_data(&self) -> bool {
       !self.builder.is_pointer_field_null(3)
     }
+    #[inline]
+    pub fn get_logical_type(self) -> ::core::result::Result<crate::partition_segment_capnp::LogicalType,::capnp::NotInSchema> {
+      ::core::convert::TryInto::try_into(self.builder.get_data_field::<u16>(5))
+    }
+    #[inline]
+    pub fn set_logical_type(&mut self, value: crate::partition_segment_capnp::LogicalType)  {
+      self.builder.set_data_field::<u16>(5, value as u16);
+    }
   }
 
   pub struct Pipeline { _typeless: ::capnp::any_pointer::Pipeline }
@@ -443,7 +455,7 @@ pub mod column {
     }
   }
   mod _private {
-    pub static ENCODED_NODE: [::capnp::Word; 94] = [
+    pub static ENCODED_NODE: [::capnp::Word; 111] = [
       ::capnp::word(0, 0, 0, 0, 5, 0, 6, 0),
       ::capnp::word(81, 61, 173, 43, 66, 66, 97, 171),
       ::capnp::word(24, 0, 0, 0, 1, 0, 2, 0),
@@ -453,7 +465,7 @@ pub mod column {
       ::capnp::word(21, 0, 0, 0, 250, 0, 0, 0),
       ::capnp::word(33, 0, 0, 0, 7, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
-      ::capnp::word(29, 0, 0, 0, 31, 1, 0, 0),
+      ::capnp::word(29, 0, 0, 0, 87, 1, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(112, 97, 114, 116, 105, 116, 105, 111),
@@ -461,42 +473,49 @@ pub mod column {
       ::capnp::word(116, 46, 99, 97, 112, 110, 112, 58),
       ::capnp::word(67, 111, 108, 117, 109, 110, 0, 0),
       ::capnp::word(0, 0, 0, 0, 1, 0, 1, 0),
-      ::capnp::word(20, 0, 0, 0, 3, 0, 4, 0),
+      ::capnp::word(24, 0, 0, 0, 3, 0, 4, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(0, 0, 1, 0, 0, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
-      ::capnp::word(125, 0, 0, 0, 42, 0, 0, 0),
+      ::capnp::word(153, 0, 0, 0, 42, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
-      ::capnp::word(120, 0, 0, 0, 3, 0, 1, 0),
-      ::capnp::word(132, 0, 0, 0, 2, 0, 1, 0),
+      ::capnp::word(148, 0, 0, 0, 3, 0, 1, 0),
+      ::capnp::word(160, 0, 0, 0, 2, 0, 1, 0),
       ::capnp::word(1, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(0, 0, 1, 0, 1, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
-      ::capnp::word(129, 0, 0, 0, 34, 0, 0, 0),
+      ::capnp::word(157, 0, 0, 0, 34, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
-      ::capnp::word(124, 0, 0, 0, 3, 0, 1, 0),
-      ::capnp::word(136, 0, 0, 0, 2, 0, 1, 0),
+      ::capnp::word(152, 0, 0, 0, 3, 0, 1, 0),
+      ::capnp::word(164, 0, 0, 0, 2, 0, 1, 0),
       ::capnp::word(2, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(1, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(76, 17, 222, 249, 222, 51, 184, 235),
-      ::capnp::word(133, 0, 0, 0, 50, 0, 0, 0),
+      ::capnp::word(161, 0, 0, 0, 50, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(3, 0, 0, 0, 2, 0, 0, 0),
       ::capnp::word(0, 0, 1, 0, 4, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
-      ::capnp::word(109, 0, 0, 0, 50, 0, 0, 0),
+      ::capnp::word(137, 0, 0, 0, 50, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
-      ::capnp::word(104, 0, 0, 0, 3, 0, 1, 0),
-      ::capnp::word(132, 0, 0, 0, 2, 0, 1, 0),
+      ::capnp::word(132, 0, 0, 0, 3, 0, 1, 0),
+      ::capnp::word(160, 0, 0, 0, 2, 0, 1, 0),
       ::capnp::word(4, 0, 0, 0, 3, 0, 0, 0),
       ::capnp::word(0, 0, 1, 0, 5, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
-      ::capnp::word(129, 0, 0, 0, 42, 0, 0, 0),
+      ::capnp::word(157, 0, 0, 0, 42, 0, 0, 0),
+      ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
+      ::capnp::word(152, 0, 0, 0, 3, 0, 1, 0),
+      ::capnp::word(180, 0, 0, 0, 2, 0, 1, 0),
+      ::capnp::word(5, 0, 0, 0, 5, 0, 0, 0),
+      ::capnp::word(0, 0, 1, 0, 6, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
-      ::capnp::word(124, 0, 0, 0, 3, 0, 1, 0),
-      ::capnp::word(152, 0, 0, 0, 2, 0, 1, 0),
+      ::capnp::word(177, 0, 0, 0, 98, 0, 0, 0),
+      ::capnp::word(181, 0, 0, 0, 7, 0, 0, 0),
+      ::capnp::word(180, 0, 0, 0, 3, 0, 1, 0),
+      ::capnp::word(192, 0, 0, 0, 2, 0, 1, 0),
       ::capnp::word(110, 97, 109, 101, 0, 0, 0, 0),
       ::capnp::word(12, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
@@ -538,6 +557,16 @@ pub mod column {
       ::capnp::word(14, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
+      ::capnp::word(108, 111, 103, 105, 99, 97, 108, 84),
+      ::capnp::word(121, 112, 101, 0, 0, 0, 0, 0),
+      ::capnp::word(0, 0, 0, 0, 1, 0, 2, 0),
+      ::capnp::word(15, 0, 0, 0, 0, 0, 0, 0),
+      ::capnp::word(232, 218, 129, 78, 7, 39, 202, 249),
+      ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
+      ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
+      ::capnp::word(15, 0, 0, 0, 0, 0, 0, 0),
+      ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
+      ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
     ];
     pub fn get_field_types(index: u16) -> ::capnp::introspect::Type {
       match index {
@@ -546,6 +575,7 @@ pub mod column {
         2 => <crate::partition_segment_capnp::column::range::Owned as ::capnp::introspect::Introspect>::introspect(),
         3 => <::capnp::struct_list::Owned<crate::partition_segment_capnp::codec_op::Owned> as ::capnp::introspect::Introspect>::introspect(),
         4 => <::capnp::struct_list::Owned<crate::partition_segment_capnp::data_section::Owned> as ::capnp::introspect::Introspect>::introspect(),
+        5 => <crate::partition_segment_capnp::LogicalType as ::capnp::introspect::Introspect>::introspect(),
         _ => panic!("invalid field index {}", index),
       }
     }
@@ -558,9 +588,9 @@ pub mod column {
       members_by_discriminant: MEMBERS_BY_DISCRIMINANT,
       members_by_name: MEMBERS_BY_NAME,
     };
-    pub static NONUNION_MEMBERS : &[u16] = &[0,1,2,3,4];
+    pub static NONUNION_MEMBERS : &[u16] = &[0,1,2,3,4,5];
     pub static MEMBERS_BY_DISCRIMINANT : &[u16] = &[];
-    pub static MEMBERS_BY_NAME : &[u16] = &[3,4,1,0,2];
+    pub static MEMBERS_BY_NAME : &[u16] = &[3,4,1,5,0,2];
     pub const TYPE_ID: u64 = 0xab61_4242_2bad_3d51;
   }
 
@@ -3844,3 +3874,106 @@ pub fn get_annotation_types(child_index: Option<u16>, index: u32) -> ::capnp::in
   panic!("invalid annotation indices ({:?}, {}) ", child_index, index)
 }
 }
+
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogicalType {
+  Plain = 0,
+  Bool = 1,
+  TimestampSecond = 2,
+  TimestampMillisecond = 3,
+  TimestampMicrosecond = 4,
+  TimestampNanosecond = 5,
+}
+
+impl ::capnp::introspect::Introspect for LogicalType {
+  fn introspect() -> ::capnp::introspect::Type { ::capnp::introspect::TypeVariant::Enum(::capnp::introspect::RawEnumSchema { encoded_node: &logical_type::ENCODED_NODE, annotation_types: logical_type::get_annotation_types }).into() }
+}
+impl <'a> ::core::convert::From<LogicalType> for ::capnp::dynamic_value::Reader<'a> {
+  fn from(e: LogicalType) -> Self { ::capnp::dynamic_value::Enum::new(e.into(), ::capnp::introspect::RawEnumSchema { encoded_node: &logical_type::ENCODED_NODE, annotation_types: logical_type::get_annotation_types }.into()).into() }
+}
+impl ::core::convert::TryFrom<u16> for LogicalType {
+  type Error = ::capnp::NotInSchema;
+  fn try_from(value: u16) -> ::core::result::Result<Self, <LogicalType as ::core::convert::TryFrom<u16>>::Error> {
+    match value {
+      0 => ::core::result::Result::Ok(Self::Plain),
+      1 => ::core::result::Result::Ok(Self::Bool),
+      2 => ::core::result::Result::Ok(Self::TimestampSecond),
+      3 => ::core::result::Result::Ok(Self::TimestampMillisecond),
+      4 => ::core::result::Result::Ok(Self::TimestampMicrosecond),
+      5 => ::core::result::Result::Ok(Self::TimestampNanosecond),
+      n => ::core::result::Result::Err(::capnp::NotInSchema(n)),
+    }
+  }
+}
+impl From<LogicalType> for u16 {
+  #[inline]
+  fn from(x: LogicalType) -> u16 { x as u16 }
+}
+impl ::capnp::traits::HasTypeId for LogicalType {
+  const TYPE_ID: u64 = 0xf9ca_2707_4e81_dae8u64;
+}
+mod logical_type {
+pub static ENCODED_NODE: [::capnp::Word; 57] = [
+  ::capnp::word(0, 0, 0, 0, 5, 0, 6, 0),
+  ::capnp::word(232, 218, 129, 78, 7, 39, 202, 249),
+  ::capnp::word(24, 0, 0, 0, 2, 0, 0, 0),
+  ::capnp::word(50, 232, 230, 38, 86, 104, 227, 194),
+  ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
+  ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
+  ::capnp::word(21, 0, 0, 0, 34, 1, 0, 0),
+  ::capnp::word(37, 0, 0, 0, 7, 0, 0, 0),
+  ::capnp::word(37, 0, 0, 0, 7, 0, 0, 0),
+  ::capnp::word(37, 0, 0, 0, 151, 0, 0, 0),
+  ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
+  ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
+  ::capnp::word(112, 97, 114, 116, 105, 116, 105, 111),
+  ::capnp::word(110, 95, 115, 101, 103, 109, 101, 110),
+  ::capnp::word(116, 46, 99, 97, 112, 110, 112, 58),
+  ::capnp::word(76, 111, 103, 105, 99, 97, 108, 84),
+  ::capnp::word(121, 112, 101, 0, 0, 0, 0, 0),
+  ::capnp::word(0, 0, 0, 0, 1, 0, 1, 0),
+  ::capnp::word(0, 0, 0, 0, 1, 0, 2, 0),
+  ::capnp::word(24, 0, 0, 0, 1, 0, 2, 0),
+  ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
+  ::capnp::word(65, 0, 0, 0, 50, 0, 0, 0),
+  ::capnp::word(65, 0, 0, 0, 7, 0, 0, 0),
+  ::capnp::word(1, 0, 0, 0, 0, 0, 0, 0),
+  ::capnp::word(61, 0, 0, 0, 42, 0, 0, 0),
+  ::capnp::word(61, 0, 0, 0, 7, 0, 0, 0),
+  ::capnp::word(2, 0, 0, 0, 0, 0, 0, 0),
+  ::capnp::word(57, 0, 0, 0, 130, 0, 0, 0),
+  ::capnp::word(61, 0, 0, 0, 7, 0, 0, 0),
+  ::capnp::word(3, 0, 0, 0, 0, 0, 0, 0),
+  ::capnp::word(57, 0, 0, 0, 170, 0, 0, 0),
+  ::capnp::word(65, 0, 0, 0, 7, 0, 0, 0),
+  ::capnp::word(4, 0, 0, 0, 0, 0, 0, 0),
+  ::capnp::word(61, 0, 0, 0, 170, 0, 0, 0),
+  ::capnp::word(69, 0, 0, 0, 7, 0, 0, 0),
+  ::capnp::word(5, 0, 0, 0, 0, 0, 0, 0),
+  ::capnp::word(65, 0, 0, 0, 162, 0, 0, 0),
+  ::capnp::word(73, 0, 0, 0, 7, 0, 0, 0),
+  ::capnp::word(112, 108, 97, 105, 110, 0, 0, 0),
+  ::capnp::word(0, 0, 0, 0, 1, 0, 2, 0),
+  ::capnp::word(98, 111, 111, 108, 0, 0, 0, 0),
+  ::capnp::word(0, 0, 0, 0, 1, 0, 2, 0),
+  ::capnp::word(116, 105, 109, 101, 115, 116, 97, 109),
+  ::capnp::word(112, 83, 101, 99, 111, 110, 100, 0),
+  ::capnp::word(0, 0, 0, 0, 1, 0, 2, 0),
+  ::capnp::word(116, 105, 109, 101, 115, 116, 97, 109),
+  ::capnp::word(112, 77, 105, 108, 108, 105, 115, 101),
+  ::capnp::word(99, 111, 110, 100, 0, 0, 0, 0),
+  ::capnp::word(0, 0, 0, 0, 1, 0, 2, 0),
+  ::capnp::word(116, 105, 109, 101, 115, 116, 97, 109),
+  ::capnp::word(112, 77, 105, 99, 114, 111, 115, 101),
+  ::capnp::word(99, 111, 110, 100, 0, 0, 0, 0),
+  ::capnp::word(0, 0, 0, 0, 1, 0, 2, 0),
+  ::capnp::word(116, 105, 109, 101, 115, 116, 97, 109),
+  ::capnp::word(112, 78, 97, 110, 111, 115, 101, 99),
+  ::capnp::word(111, 110, 100, 0, 0, 0, 0, 0),
+  ::capnp::word(0, 0, 0, 0, 1, 0, 2, 0),
+];
+pub fn get_annotation_types(child_index: Option<u16>, index: u32) -> ::capnp::introspect::Type {
+  panic!("invalid annotation indices ({:?}, {}) ", child_index, index)
+}
+}
@@ -805,6 +805,10 @@ pub mod column {
     pub fn get_data(self) -> crate::wal_segment_capnp::column::data::Reader<'a> {
       self.reader.into()
     }
+    #[inline]
+    pub fn get_logical_type(self) -> ::core::result::Result<crate::wal_segment_capnp::LogicalType,::capnp::NotInSchema> {
+      ::core::convert::TryInto::try_into(self.reader.get_data_field::<u16>(1))
+    }
   }
 
   pub struct Builder<'a> { builder: ::capnp::private::layout::StructBuilder<'a> }
@@ -889,6 +893,14 @@ pub mod column {
       self.builder.reborrow().get_pointer_field(2).clear();
       self.builder.into()
     }
+    #[inline]
+    pub fn get_logical_type(self) -> ::core::result::Result<crate::wal_segment_capnp::LogicalType,::capnp::NotInSchema> {
+      ::core::convert::TryInto::try_into(self.builder.get_data_field::<u16>(1))
+    }
+    #[inline]
+    pub fn set_logical_type(&mut self, value: crate::wal_segment_capnp::LogicalType)  {
+      self.builder.set_data_field::<u16>(1, value as u16);
+    }
   }
 
   pub struct Pipeline { _typeless: ::capnp::any_pointer::Pipeline }
@@ -903,7 +915,7 @@ pub mod column {
     }
   }
   mod _private {
-    pub static ENCODED_NODE: [::capnp::Word; 41] = [
+    pub static ENCODED_NODE: [::capnp::Word; 58] = [
       ::capnp::word(0, 0, 0, 0, 5, 0, 6, 0),
       ::capnp::word(55, 153, 166, 99, 106, 69, 152, 148),
       ::capnp::word(18, 0, 0, 0, 1, 0, 1, 0),
@@ -913,7 +925,7 @@ pub mod column {
       ::capnp::word(21, 0, 0, 0, 202, 0, 0, 0),
       ::capnp::word(33, 0, 0, 0, 7, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
-      ::capnp::word(29, 0, 0, 0, 119, 0, 0, 0),
+      ::capnp::word(29, 0, 0, 0, 175, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(119, 97, 108, 95, 115, 101, 103, 109),
@@ -921,21 +933,28 @@ pub mod column {
       ::capnp::word(112, 58, 67, 111, 108, 117, 109, 110),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 1, 0, 1, 0),
-      ::capnp::word(8, 0, 0, 0, 3, 0, 4, 0),
+      ::capnp::word(12, 0, 0, 0, 3, 0, 4, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(0, 0, 1, 0, 0, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
-      ::capnp::word(41, 0, 0, 0, 42, 0, 0, 0),
+      ::capnp::word(69, 0, 0, 0, 42, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
-      ::capnp::word(36, 0, 0, 0, 3, 0, 1, 0),
-      ::capnp::word(48, 0, 0, 0, 2, 0, 1, 0),
+      ::capnp::word(64, 0, 0, 0, 3, 0, 1, 0),
+      ::capnp::word(76, 0, 0, 0, 2, 0, 1, 0),
       ::capnp::word(1, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(1, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(69, 244, 243, 59, 189, 92, 161, 222),
-      ::capnp::word(45, 0, 0, 0, 42, 0, 0, 0),
+      ::capnp::word(73, 0, 0, 0, 42, 0, 0, 0),
+      ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
+      ::capnp::word(2, 0, 0, 0, 1, 0, 0, 0),
+      ::capnp::word(0, 0, 1, 0, 10, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
+      ::capnp::word(49, 0, 0, 0, 98, 0, 0, 0),
+      ::capnp::word(53, 0, 0, 0, 7, 0, 0, 0),
+      ::capnp::word(52, 0, 0, 0, 3, 0, 1, 0),
+      ::capnp::word(64, 0, 0, 0, 2, 0, 1, 0),
       ::capnp::word(110, 97, 109, 101, 0, 0, 0, 0),
       ::capnp::word(12, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
@@ -945,11 +964,22 @@ pub mod column {
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
       ::capnp::word(100, 97, 116, 97, 0, 0, 0, 0),
+      ::capnp::word(108, 111, 103, 105, 99, 97, 108, 84),
+      ::capnp::word(121, 112, 101, 0, 0, 0, 0, 0),
+      ::capnp::word(0, 0, 0, 0, 1, 0, 2, 0),
+      ::capnp::word(15, 0, 0, 0, 0, 0, 0, 0),
+      ::capnp::word(6, 178, 30, 55, 199, 65, 200, 249),
+      ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
+      ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
+      ::capnp::word(15, 0, 0, 0, 0, 0, 0, 0),
+      ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
+      ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
     ];
     pub fn get_field_types(index: u16) -> ::capnp::introspect::Type {
       match index {
         0 => <::capnp::text::Owned as ::capnp::introspect::Introspect>::introspect(),
         1 => <crate::wal_segment_capnp::column::data::Owned as ::capnp::introspect::Introspect>::introspect(),
+        2 => <crate::wal_segment_capnp::LogicalType as ::capnp::introspect::Introspect>::introspect(),
         _ => panic!("invalid field index {}", index),
       }
     }
@@ -962,9 +992,9 @@ pub mod column {
       members_by_discriminant: MEMBERS_BY_DISCRIMINANT,
       members_by_name: MEMBERS_BY_NAME,
     };
-    pub static NONUNION_MEMBERS : &[u16] = &[0,1];
+    pub static NONUNION_MEMBERS : &[u16] = &[0,1,2];
     pub static MEMBERS_BY_DISCRIMINANT : &[u16] = &[];
-    pub static MEMBERS_BY_NAME : &[u16] = &[1,0];
+    pub static MEMBERS_BY_NAME : &[u16] = &[1,2,0];
     pub const TYPE_ID: u64 = 0x9498_456a_63a6_9937;
   }
 
@@ -2474,3 +2504,105 @@ pub mod any_val {
     pub type WhichBuilder<'a,> = Which<::capnp::Result<::capnp::text::Builder<'a>>>;
   }
 }
+
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogicalType {
+  Plain = 0,
+  Bool = 1,
+  TimestampSecond = 2,
+  TimestampMillisecond = 3,
+  TimestampMicrosecond = 4,
+  TimestampNanosecond = 5,
+}
+
+impl ::capnp::introspect::Introspect for LogicalType {
+  fn introspect() -> ::capnp::introspect::Type { ::capnp::introspect::TypeVariant::Enum(::capnp::introspect::RawEnumSchema { encoded_node: &logical_type::ENCODED_NODE, annotation_types: logical_type::get_annotation_types }).into() }
+}
+impl <'a> ::core::convert::From<LogicalType> for ::capnp::dynamic_value::Reader<'a> {
+  fn from(e: LogicalType) -> Self { ::capnp::dynamic_value::Enum::new(e.into(), ::capnp::introspect::RawEnumSchema { encoded_node: &logical_type::ENCODED_NODE, annotation_types: logical_type::get_annotation_types }.into()).into() }
+}
+impl ::core::convert::TryFrom<u16> for LogicalType {
+  type Error = ::capnp::NotInSchema;
+  fn try_from(value: u16) -> ::core::result::Result<Self, <LogicalType as ::core::convert::TryFrom<u16>>::Error> {
+    match value {
+      0 => ::core::result::Result::Ok(Self::Plain),
+      1 => ::core::result::Result::Ok(Self::Bool),
+      2 => ::core::result::Result::Ok(Self::TimestampSecond),
+      3 => ::core::result::Result::Ok(Self::TimestampMillisecond),
+      4 => ::core::result::Result::Ok(Self::TimestampMicrosecond),
+      5 => ::core::result::Result::Ok(Self::TimestampNanosecond),
+      n => ::core::result::Result::Err(::capnp::NotInSchema(n)),
+    }
+  }
+}
+impl From<LogicalType> for u16 {
+  #[inline]
+  fn from(x: LogicalType) -> u16 { x as u16 }
+}
+impl ::capnp::traits::HasTypeId for LogicalType {
+  const TYPE_ID: u64 = 0xf9c8_41c7_371e_b206u64;
+}
+mod logical_type {
+pub static ENCODED_NODE: [::capnp::Word; 56] = [
+  ::capnp::word(0, 0, 0, 0, 5, 0, 6, 0),
+  ::capnp::word(6, 178, 30, 55, 199, 65, 200, 249),
+  ::capnp::word(18, 0, 0, 0, 2, 0, 0, 0),
+  ::capnp::word(202, 69, 242, 113, 180, 214, 43, 219),
+  ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
+  ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
+  ::capnp::word(21, 0, 0, 0, 242, 0, 0, 0),
+  ::capnp::word(33, 0, 0, 0, 7, 0, 0, 0),
+  ::capnp::word(33, 0, 0, 0, 7, 0, 0, 0),
+  ::capnp::word(33, 0, 0, 0, 151, 0, 0, 0),
+  ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
+  ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
+  ::capnp::word(119, 97, 108, 95, 115, 101, 103, 109),
+  ::capnp::word(101, 110, 116, 46, 99, 97, 112, 110),
+  ::capnp::word(112, 58, 76, 111, 103, 105, 99, 97),
+  ::capnp::word(108, 84, 121, 112, 101, 0, 0, 0),
+  ::capnp::word(0, 0, 0, 0, 1, 0, 1, 0),
+  ::capnp::word(0, 0, 0, 0, 1, 0, 2, 0),
+  ::capnp::word(24, 0, 0, 0, 1, 0, 2, 0),
+  ::capnp::word(0, 0, 0, 0, 0, 0, 0, 0),
+  ::capnp::word(65, 0, 0, 0, 50, 0, 0, 0),
+  ::capnp::word(65, 0, 0, 0, 7, 0, 0, 0),
+  ::capnp::word(1, 0, 0, 0, 0, 0, 0, 0),
+  ::capnp::word(61, 0, 0, 0, 42, 0, 0, 0),
+  ::capnp::word(61, 0, 0, 0, 7, 0, 0, 0),
+  ::capnp::word(2, 0, 0, 0, 0, 0, 0, 0),
+  ::capnp::word(57, 0, 0, 0, 130, 0, 0, 0),
+  ::capnp::word(61, 0, 0, 0, 7, 0, 0, 0),
+  ::capnp::word(3, 0, 0, 0, 0, 0, 0, 0),
+  ::capnp::word(57, 0, 0, 0, 170, 0, 0, 0),
+  ::capnp::word(65, 0, 0, 0, 7, 0, 0, 0),
+  ::capnp::word(4, 0, 0, 0, 0, 0, 0, 0),
+  ::capnp::word(61, 0, 0, 0, 170, 0, 0, 0),
+  ::capnp::word(69, 0, 0, 0, 7, 0, 0, 0),
+  ::capnp::word(5, 0, 0, 0, 0, 0, 0, 0),
+  ::capnp::word(65, 0, 0, 0, 162, 0, 0, 0),
+  ::capnp::word(73, 0, 0, 0, 7, 0, 0, 0),
+  ::capnp::word(112, 108, 97, 105, 110, 0, 0, 0),
+  ::capnp::word(0, 0, 0, 0, 1, 0, 2, 0),
+  ::capnp::word(98, 111, 111, 108, 0, 0, 0, 0),
+  ::capnp::word(0, 0, 0, 0, 1, 0, 2, 0),
+  ::capnp::word(116, 105, 109, 101, 115, 116, 97, 109),
+  ::capnp::word(112, 83, 101, 99, 111, 110, 100, 0),
+  ::capnp::word(0, 0, 0, 0, 1, 0, 2, 0),
+  ::capnp::word(116, 105, 109, 101, 115, 116, 97, 109),
+  ::capnp::word(112, 77, 105, 108, 108, 105, 115, 101),
+  ::capnp::word(99, 111, 110, 100, 0, 0, 0, 0),
+  ::capnp::word(0, 0, 0, 0, 1, 0, 2, 0),
+  ::capnp::word(116, 105, 109, 101, 115, 116, 97, 109),
+  ::capnp::word(112, 77, 105, 99, 114, 111, 115, 101),
+  ::capnp::word(99, 111, 110, 100, 0, 0, 0, 0),
+  ::capnp::word(0, 0, 0, 0, 1, 0, 2, 0),
+  ::capnp::word(116, 105, 109, 101, 115, 116, 97, 109),
+  ::capnp::word(112, 78, 97, 110, 111, 115, 101, 99),
+  ::capnp::word(111, 110, 100, 0, 0, 0, 0, 0),
+  ::capnp::word(0, 0, 0, 0, 1, 0, 2, 0),
+];
+pub fn get_annotation_types(child_index: Option<u16>, index: u32) -> ::capnp::introspect::Type {
+  panic!("invalid annotation indices ({:?}, {}) ", child_index, index)
+}
+}
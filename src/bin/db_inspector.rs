@@ -2,6 +2,7 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use itertools::Itertools;
 use locustdb::disk_store::meta_store::PartitionMetadata;
 use locustdb::disk_store::storage::Storage;
 use locustdb::observability::PerfCounter;
@@ -41,6 +42,9 @@ fn main() {
         println!("### META STORE ###");
         println!("Next WAL ID: {}", meta.next_wal_id());
         println!("Number of partitions: {:?}", meta.partitions().count());
+        for (table, policy) in meta.retention_policies().iter().sorted_by_key(|(t, _)| *t) {
+            println!("Retention policy for table {}: {:?}", table, policy);
+        }
         if opts.meta > 0 {
             let partitions = match &opts.table {
                 Some(table) => Box::new(meta.partitions_for_table(table))
@@ -80,6 +84,9 @@ fn main() {
                             );
                         }
                     }
+                    for (column, range) in partition.column_ranges.iter().sorted_by_key(|(c, _)| *c) {
+                        println!("  Column {} ranges from {} to {}", column, range.min, range.max);
+                    }
                 }
             }
         }
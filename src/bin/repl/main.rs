@@ -1,6 +1,7 @@
 use std::error::Error;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures::executor::block_on;
 use rustyline::DefaultEditor;
@@ -21,7 +22,8 @@ mod unicode;
     author = "Clemens Winter <clemenswinter1@gmail.com>"
 )]
 struct Opt {
-    /// Database path. Supports both local file paths and Google cloud storage paths (gs://bucket/folder)
+    /// Database path. Supports local file paths, Google cloud storage paths (gs://bucket/folder), Azure paths
+    /// (az://account/container/folder) and S3 paths (s3://bucket/folder)
     #[structopt(long, name = "PATH", parse(from_os_str))]
     db_path: Option<PathBuf>,
 
@@ -124,6 +126,22 @@ struct Opt {
     /// Internal metrics table name
     #[structopt(long)]
     metrics_table_name: Option<String>,
+
+    /// Internal query log table name
+    #[structopt(long)]
+    query_log_table_name: Option<String>,
+
+    /// Abort queries that run for longer than this number of seconds
+    #[structopt(long, name = "SECONDS")]
+    query_timeout: Option<u64>,
+
+    /// Interval in seconds at which table retention policies are enforced
+    #[structopt(long, default_value = "60")]
+    retention_interval: u64,
+
+    /// Endpoint of an S3-compatible object store (e.g. http://localhost:9000 for MinIO) used for S3 paths
+    #[structopt(long, name = "URL")]
+    s3_endpoint: Option<String>,
 }
 
 fn main() {
@@ -155,6 +173,10 @@ fn main() {
         metrics_table_name,
         io_threads,
         max_wal_files,
+        query_timeout,
+        query_log_table_name,
+        retention_interval,
+        s3_endpoint,
     } = Opt::from_args();
 
     let options = locustdb::Options {
@@ -174,6 +196,11 @@ fn main() {
         io_threads,
         metrics_interval,
         metrics_table_name,
+        query_timeout: query_timeout.map(Duration::from_secs),
+        query_log_table_name,
+        retention_interval,
+        blob_writer: None,
+        s3_endpoint,
     };
 
     if options.readahead > options.mem_size_limit_tables {
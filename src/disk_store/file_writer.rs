@@ -2,24 +2,34 @@ use std::error::Error;
 use std::fs::{create_dir_all, File};
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
-
+use std::sync::Arc;
+
+/// Backend that persists the WAL, metastore and partitions of a database.
+/// Paths are prefixed with `Options::db_path`, e.g. `<db_path>/meta`, `<db_path>/wal/<id>.wal` and
+/// `<db_path>/tables/<table>/<partition>`, and can be used as object keys by object stores.
+/// For backends selected by a gs://, az:// or s3:// prefix, the scheme and bucket (or account and container) are
+/// stripped from `db_path` first.
+/// A custom backend can be supplied with `Options::blob_writer`.
 pub trait BlobWriter: Send + Sync {
+    /// Replaces the blob at `path`, the blob must either be fully written or not at all
     fn store(&self, path: &Path, data: &[u8])
         -> Result<(), Box<dyn Error + Send + Sync + 'static>>;
     fn load(&self, path: &Path) -> Result<Vec<u8>, Box<dyn Error + Send + Sync + 'static>>;
     fn delete(&self, path: &Path) -> Result<(), Box<dyn Error + Send + Sync + 'static>>;
-    /// Returns absolute paths of files in the directory
+    /// Returns the full paths (including the `path` prefix) of the files directly inside the directory `path`
     fn list(&self, path: &Path) -> Result<Vec<PathBuf>, Box<dyn Error + Send + Sync + 'static>>;
     fn exists(&self, path: &Path) -> Result<bool, Box<dyn Error + Send + Sync + 'static>>;
 }
 
 pub struct VersionedChecksummedBlobWriter {
-    writer: Box<dyn BlobWriter>,
+    writer: Arc<dyn BlobWriter>,
 }
 
 impl VersionedChecksummedBlobWriter {
-    pub fn new(writer: Box<dyn BlobWriter>) -> VersionedChecksummedBlobWriter {
-        VersionedChecksummedBlobWriter { writer }
+    pub fn new(writer: impl Into<Arc<dyn BlobWriter>>) -> VersionedChecksummedBlobWriter {
+        VersionedChecksummedBlobWriter {
+            writer: writer.into(),
+        }
     }
 }
 
@@ -97,6 +107,7 @@ impl BlobWriter for VersionedChecksummedBlobWriter {
     }
 }
 
+#[derive(Default)]
 pub struct FileBlobWriter;
 
 impl FileBlobWriter {
@@ -2,13 +2,17 @@ use capnp::serialize_packed;
 use datasize::DataSize;
 use locustdb_serialization::{dbmeta_capnp, default_reader_options};
 use lz4_flex::block::decompress_size_prepended;
+use ordered_float::OrderedFloat;
 use pco::standalone::simple_decompress;
 use std::collections::{BTreeMap, HashMap};
 use std::ops::Range;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
+use std::time::Duration;
 
+use crate::ingest::raw_val::RawVal;
 use crate::observability::SimpleTracer;
+use crate::TimeUnit;
 
 type TableName = String;
 type PartitionID = u64;
@@ -22,6 +26,8 @@ pub struct MetaStore {
     /// Maps each table to it's set of partitions.
     /// Each partition is a contigous subset of rows in the table.
     partitions: HashMap<TableName, HashMap<PartitionID, PartitionMetadata>>,
+    /// Retention policies of tables that have one
+    retention_policies: HashMap<TableName, RetentionPolicy>,
 }
 
 #[derive(Clone, DataSize, Debug)]
@@ -46,6 +52,37 @@ pub struct PartitionMetadata {
     pub subpartitions: Vec<SubpartitionMetadata>,
     // Maps the last column name in each subpartition to the corresponding index in `subpartitions`
     pub subpartitions_by_last_column: BTreeMap<String, usize>,
+    /// Range of values of each column, used to skip partitions that cannot match a query filter
+    pub column_ranges: HashMap<String, ColumnRange>,
+}
+
+/// Smallest and largest non-null value of a column in a partition.
+/// Bounds are either both `RawVal::Int`, both `RawVal::Float` or both `RawVal::Str`.
+#[derive(Clone, Debug, DataSize, PartialEq)]
+pub struct ColumnRange {
+    pub min: RawVal,
+    pub max: RawVal,
+    /// Unit of the integer bounds if the column is a timestamp column
+    #[data_size(skip)]
+    pub timestamp_unit: Option<TimeUnit>,
+}
+
+/// Limits on the data retained for a table, the oldest partitions are deleted once a limit is exceeded.
+#[derive(Clone, Debug, DataSize, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Partitions where all values of the timestamp column are older than the maximum age are deleted
+    pub max_age: Option<MaxAge>,
+    /// The oldest partitions are deleted while the total size of the table exceeds this number of bytes
+    pub max_size_bytes: Option<u64>,
+}
+
+#[derive(Clone, Debug, DataSize, PartialEq, Eq)]
+pub struct MaxAge {
+    pub timestamp_column: String,
+    #[data_size(skip)]
+    pub unit: TimeUnit,
+    #[data_size(skip)]
+    pub max_age: Duration,
 }
 
 #[derive(Clone, Debug, DataSize)]
@@ -127,6 +164,17 @@ impl MetaStore {
         self.partitions.values().flat_map(|x| x.values())
     }
 
+    pub fn retention_policies(&self) -> &HashMap<TableName, RetentionPolicy> {
+        &self.retention_policies
+    }
+
+    pub fn set_retention_policy(&mut self, table_name: &str, policy: Option<RetentionPolicy>) {
+        match policy {
+            Some(policy) => self.retention_policies.insert(table_name.to_string(), policy),
+            None => self.retention_policies.remove(table_name),
+        };
+    }
+
     pub fn partitions_for_table(
         &self,
         table_name: &str,
@@ -217,19 +265,55 @@ impl MetaStore {
                 partition_builder.set_len(partition.len as u64);
 
                 assert!(partition.subpartitions.len() < u32::MAX as usize);
-                let mut subpartitions_builder =
-                    partition_builder.init_subpartitions(partition.subpartitions.len() as u32);
+                let mut subpartitions_builder = partition_builder
+                    .reborrow()
+                    .init_subpartitions(partition.subpartitions.len() as u32);
                 for (i, subpartition) in partition.subpartitions.iter().enumerate() {
                     let mut subpartition_builder = subpartitions_builder.reborrow().get(i as u32);
                     subpartition_builder.set_size_bytes(subpartition.size_bytes);
                     subpartition_builder.set_subpartition_key(&subpartition.subpartition_key);
                     subpartition_builder.set_last_column(&subpartition.last_column);
                 }
+
+                // Ranges with bounds that cannot be represented are dropped, which only disables pruning on the column
+                let column_ranges = partition
+                    .column_ranges
+                    .iter()
+                    .filter_map(|(column, range)| Some((column, bound(&range.min)?, bound(&range.max)?, range)))
+                    .collect::<Vec<_>>();
+                assert!(column_ranges.len() < u32::MAX as usize);
+                let mut column_ranges_builder = partition_builder
+                    .reborrow()
+                    .init_column_ranges(column_ranges.len() as u32);
+                for (i, (column, min, max, range)) in column_ranges.into_iter().enumerate() {
+                    let mut range_builder = column_ranges_builder.reborrow().get(i as u32);
+                    range_builder.set_column(column);
+                    set_bound(range_builder.reborrow().init_min(), min);
+                    set_bound(range_builder.reborrow().init_max(), max);
+                    range_builder.set_timestamp_units_per_second(
+                        range.timestamp_unit.map_or(0, |unit| unit.per_second()),
+                    );
+                }
                 i += 1;
             }
         }
         tracer.end_span(span_partition_serialization);
 
+        assert!(self.retention_policies.len() < u32::MAX as usize);
+        let mut policies_builder = dbmeta
+            .reborrow()
+            .init_retention_policies(self.retention_policies.len() as u32);
+        for (i, (table, policy)) in self.retention_policies.iter().enumerate() {
+            let mut policy_builder = policies_builder.reborrow().get(i as u32);
+            policy_builder.set_tablename(table);
+            if let Some(max_age) = &policy.max_age {
+                policy_builder.set_timestamp_column(&max_age.timestamp_column);
+                policy_builder.set_timestamp_units_per_second(max_age.unit.per_second());
+                policy_builder.set_max_age_seconds(max_age.max_age.as_secs());
+            }
+            policy_builder.set_max_size_bytes(policy.max_size_bytes.unwrap_or(0));
+        }
+
         // Write out the capnproto message
         let span_message_serialization = tracer.start_span("message_serialization");
         let mut buf = Vec::new();
@@ -325,6 +409,20 @@ impl MetaStore {
                     loaded: Arc::new(AtomicBool::new(false)),
                 });
             }
+            // v4
+            let mut column_ranges = HashMap::new();
+            for range in partition.get_column_ranges()? {
+                let column = range.get_column()?.to_string()?;
+                let min = get_bound(range.get_min()?)?;
+                let max = get_bound(range.get_max()?)?;
+                // v6
+                let timestamp_unit = match range.get_timestamp_units_per_second() {
+                    0 => None,
+                    units_per_second => Some(time_unit(units_per_second)?),
+                };
+                column_ranges.insert(column, ColumnRange { min, max, timestamp_unit });
+            }
+
             let partition = PartitionMetadata {
                 id,
                 tablename: tablename.clone(),
@@ -332,6 +430,7 @@ impl MetaStore {
                 len,
                 subpartitions,
                 subpartitions_by_last_column,
+                column_ranges,
             };
             partitions
                 .entry(tablename)
@@ -339,10 +438,108 @@ impl MetaStore {
                 .insert(id, partition);
         }
 
+        // v5
+        let mut retention_policies = HashMap::new();
+        for policy in dbmeta.get_retention_policies()? {
+            let tablename = policy.get_tablename()?.to_string().unwrap();
+            let timestamp_column = policy.get_timestamp_column()?.to_string().unwrap();
+            let max_age = if timestamp_column.is_empty() {
+                None
+            } else {
+                Some(MaxAge {
+                    timestamp_column,
+                    unit: time_unit(policy.get_timestamp_units_per_second())?,
+                    max_age: Duration::from_secs(policy.get_max_age_seconds()),
+                })
+            };
+            let max_size_bytes = match policy.get_max_size_bytes() {
+                0 => None,
+                max_size_bytes => Some(max_size_bytes),
+            };
+            retention_policies.insert(
+                tablename,
+                RetentionPolicy {
+                    max_age,
+                    max_size_bytes,
+                },
+            );
+        }
+
         Ok(MetaStore {
             next_wal_id,
             earliest_unflushed_wal_id: next_wal_id,
             partitions,
+            retention_policies,
         })
     }
 }
+
+fn time_unit(units_per_second: i64) -> capnp::Result<TimeUnit> {
+    match units_per_second {
+        1 => Ok(TimeUnit::Second),
+        1_000 => Ok(TimeUnit::Millisecond),
+        1_000_000 => Ok(TimeUnit::Microsecond),
+        1_000_000_000 => Ok(TimeUnit::Nanosecond),
+        _ => Err(capnp::Error::failed(format!(
+            "Unsupported timestamp unit with {} units per second",
+            units_per_second
+        ))),
+    }
+}
+
+/// Column range bound that can be stored in the metastore.
+enum Bound<'a> {
+    Int(i64),
+    Float(f64),
+    Str(&'a str),
+}
+
+fn bound(value: &RawVal) -> Option<Bound> {
+    match value {
+        RawVal::Int(i) => Some(Bound::Int(*i)),
+        RawVal::Float(f) => Some(Bound::Float(f.0)),
+        RawVal::Str(s) => Some(Bound::Str(s)),
+        _ => None,
+    }
+}
+
+fn set_bound(mut builder: dbmeta_capnp::bound::Builder, value: Bound) {
+    match value {
+        Bound::Int(i) => builder.set_int(i),
+        Bound::Float(f) => builder.set_float(f),
+        Bound::Str(s) => builder.set_string(s),
+    }
+}
+
+fn get_bound(reader: dbmeta_capnp::bound::Reader) -> capnp::Result<RawVal> {
+    Ok(match reader.which()? {
+        dbmeta_capnp::bound::Int(i) => RawVal::Int(i),
+        dbmeta_capnp::bound::Float(f) => RawVal::Float(OrderedFloat(f)),
+        dbmeta_capnp::bound::String(s) => RawVal::Str(s?.to_string()?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_unit() {
+        for unit in [TimeUnit::Second, TimeUnit::Millisecond, TimeUnit::Microsecond, TimeUnit::Nanosecond] {
+            assert_eq!(time_unit(unit.per_second()).unwrap(), unit);
+        }
+        assert!(time_unit(60).is_err());
+        assert!(time_unit(0).is_err());
+    }
+
+    #[test]
+    fn test_bound() {
+        for value in [RawVal::Int(-1), RawVal::Float(OrderedFloat(0.5)), RawVal::Str("a".to_string())] {
+            let mut message = capnp::message::Builder::new_default();
+            set_bound(message.init_root(), bound(&value).unwrap());
+            let reader = message.get_root_as_reader().unwrap();
+            assert_eq!(get_bound(reader).unwrap(), value);
+        }
+        assert!(bound(&RawVal::Null).is_none());
+    }
+}
@@ -1,9 +1,10 @@
 mod azure_writer;
-mod file_writer;
+pub mod file_writer;
 mod gcs_writer;
 pub mod meta_store;
 pub mod noop_storage;
 mod partition_segment;
+mod s3_writer;
 pub mod storage;
 pub mod wal_segment;
 
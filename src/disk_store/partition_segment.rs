@@ -19,6 +19,7 @@ impl PartitionSegment {
             let mut column = columns.reborrow().get(i as u32);
             column.set_name(col.name());
             column.set_len(col.len() as u64);
+            column.set_logical_type(col.logical_type().into());
             {
                 let mut range = column.reborrow().init_range();
                 match col.range() {
@@ -130,6 +131,7 @@ impl PartitionSegment {
         for column in partition_segment.get_columns()?.iter() {
             let name = column.get_name()?.to_string().unwrap();
             let len = column.get_len();
+            let logical_type = column.get_logical_type()?.into();
             use partition_segment_capnp::column::range::Which;
             let range = match column.get_range().which()? {
                 Which::Empty(()) => None,
@@ -262,13 +264,9 @@ impl PartitionSegment {
                 })
                 .collect::<Vec<_>>();
 
-            columns.push(Column::new(
-                &name,
-                len as usize,
-                range,
-                codec,
-                data_sections,
-            ));
+            let mut col = Column::new(&name, len as usize, range, codec, data_sections);
+            col.set_logical_type(logical_type);
+            columns.push(col);
         }
 
         Ok(PartitionSegment { columns })
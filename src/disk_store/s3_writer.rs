@@ -0,0 +1,338 @@
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use aws_config::{BehaviorVersion, Region, SdkConfig};
+use aws_sdk_s3::config::retry::RetryConfig;
+use aws_sdk_s3::config::{RequestChecksumCalculation, ResponseChecksumValidation};
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::Client;
+
+use super::file_writer::BlobWriter;
+use super::RT;
+
+/// Number of attempts for each request before a transient error (throttling, 5xx, timeouts) is returned.
+const MAX_ATTEMPTS: u32 = 8;
+
+/// Stores blobs in an S3 bucket or any S3-compatible object store (MinIO, Ceph).
+pub struct S3BlobWriter {
+    client: Client,
+    bucket: String,
+}
+
+impl S3BlobWriter {
+    /// Credentials and region are loaded from the standard AWS environment variables and config files.
+    /// If `endpoint` is set, requests are sent to that endpoint with path-style addressing instead of AWS.
+    pub fn new(bucket: String, endpoint: Option<String>) -> Result<S3BlobWriter, Box<dyn Error>> {
+        let config = RT.block_on(aws_config::defaults(BehaviorVersion::latest()).load());
+        Ok(S3BlobWriter::from_config(bucket, endpoint, &config))
+    }
+
+    fn from_config(bucket: String, endpoint: Option<String>, config: &SdkConfig) -> S3BlobWriter {
+        let mut builder = aws_sdk_s3::config::Builder::from(config)
+            .retry_config(RetryConfig::standard().with_max_attempts(MAX_ATTEMPTS))
+            // Checksum trailers are not supported by all S3-compatible stores
+            .request_checksum_calculation(RequestChecksumCalculation::WhenRequired)
+            .response_checksum_validation(ResponseChecksumValidation::WhenRequired);
+        if config.region().is_none() {
+            builder = builder.region(Region::from_static("us-east-1"));
+        }
+        if let Some(endpoint) = endpoint {
+            builder = builder.endpoint_url(endpoint).force_path_style(true);
+        }
+        S3BlobWriter {
+            client: Client::from_conf(builder.build()),
+            bucket,
+        }
+    }
+
+    fn key(path: &Path) -> String {
+        path.to_string_lossy().to_string()
+    }
+}
+
+impl BlobWriter for S3BlobWriter {
+    fn store(
+        &self,
+        path: &Path,
+        data: &[u8],
+    ) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+        log::debug!("Storing blob in S3: {:?} {} B", path, data.len());
+        RT.block_on(
+            self.client
+                .put_object()
+                .bucket(&self.bucket)
+                .key(S3BlobWriter::key(path))
+                .content_type("application/octet-stream")
+                .body(ByteStream::from(data.to_vec()))
+                .send(),
+        )?;
+        Ok(())
+    }
+
+    fn load(&self, path: &Path) -> Result<Vec<u8>, Box<dyn Error + Send + Sync + 'static>> {
+        log::debug!("Loading blob from S3: {:?}", path);
+        RT.block_on(async {
+            let object = self
+                .client
+                .get_object()
+                .bucket(&self.bucket)
+                .key(S3BlobWriter::key(path))
+                .send()
+                .await?;
+            let data = object.body.collect().await?;
+            Ok(data.into_bytes().to_vec())
+        })
+    }
+
+    fn delete(&self, path: &Path) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+        log::debug!("Deleting blob from S3: {:?}", path);
+        RT.block_on(
+            self.client
+                .delete_object()
+                .bucket(&self.bucket)
+                .key(S3BlobWriter::key(path))
+                .send(),
+        )?;
+        Ok(())
+    }
+
+    fn list(&self, path: &Path) -> Result<Vec<PathBuf>, Box<dyn Error + Send + Sync + 'static>> {
+        log::debug!("Listing blobs from S3: {:?}", path);
+        let mut prefix = S3BlobWriter::key(path);
+        if !prefix.ends_with('/') {
+            prefix.push('/');
+        }
+        // Each page contains at most 1000 keys, the paginator follows continuation tokens until all are listed
+        let mut pages = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(prefix)
+            .delimiter("/")
+            .into_paginator()
+            .send();
+        let mut paths = Vec::new();
+        while let Some(page) = RT.block_on(pages.next()) {
+            for object in page?.contents() {
+                if let Some(key) = object.key() {
+                    paths.push(PathBuf::from(key));
+                }
+            }
+        }
+        Ok(paths)
+    }
+
+    fn exists(&self, path: &Path) -> Result<bool, Box<dyn Error + Send + Sync + 'static>> {
+        log::debug!("Checking if blob exists in S3: {:?}", path);
+        match RT.block_on(
+            self.client
+                .head_object()
+                .bucket(&self.bucket)
+                .key(S3BlobWriter::key(path))
+                .send(),
+        ) {
+            Ok(_) => Ok(true),
+            Err(err) => match err.as_service_error() {
+                Some(service_err) if service_err.is_not_found() => Ok(false),
+                _ => Err(Box::new(err)),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    use aws_sdk_s3::config::Credentials;
+
+    use super::*;
+
+    /// Maximum number of keys returned per list request, small to exercise pagination.
+    const PAGE_SIZE: usize = 2;
+
+    /// Minimal stand-in for an S3-compatible object store like MinIO that serves path-style requests.
+    #[derive(Default)]
+    struct FakeS3 {
+        objects: Mutex<BTreeMap<String, Vec<u8>>>,
+        /// Number of upcoming requests that fail with 503 Slow Down.
+        fail_requests: AtomicUsize,
+        requests: AtomicUsize,
+    }
+
+    impl FakeS3 {
+        fn start() -> (Arc<FakeS3>, String) {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let endpoint = format!("http://{}", listener.local_addr().unwrap());
+            let s3 = Arc::new(FakeS3::default());
+            let server = s3.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    server.handle(stream.unwrap());
+                }
+            });
+            (s3, endpoint)
+        }
+
+        fn handle(&self, mut stream: TcpStream) {
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut content_length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = header.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            self.requests.fetch_add(1, Ordering::SeqCst);
+
+            let mut parts = request_line.split_whitespace();
+            let method = parts.next().unwrap();
+            let target = parts.next().unwrap();
+            let (path, query) = target.split_once('?').unwrap_or((target, ""));
+            let key = decode(path.trim_start_matches("/bucket").trim_start_matches('/'));
+            let params = query
+                .split('&')
+                .filter_map(|param| param.split_once('='))
+                .map(|(name, value)| (name, decode(value)))
+                .collect::<BTreeMap<_, _>>();
+
+            let (status, response) = if self.fail_requests.load(Ordering::SeqCst) > 0 {
+                self.fail_requests.fetch_sub(1, Ordering::SeqCst);
+                ("503 Slow Down", error_xml("SlowDown"))
+            } else {
+                let mut objects = self.objects.lock().unwrap();
+                match method {
+                    "PUT" => {
+                        objects.insert(key, body);
+                        ("200 OK", vec![])
+                    }
+                    "GET" if key.is_empty() => ("200 OK", list_xml(&objects, &params)),
+                    "GET" | "HEAD" => match objects.get(&key) {
+                        Some(data) if method == "GET" => ("200 OK", data.clone()),
+                        Some(_) => ("200 OK", vec![]),
+                        None => ("404 Not Found", error_xml("NoSuchKey")),
+                    },
+                    "DELETE" => {
+                        objects.remove(&key);
+                        ("204 No Content", vec![])
+                    }
+                    _ => ("405 Method Not Allowed", error_xml("MethodNotAllowed")),
+                }
+            };
+            let response = if method == "HEAD" { vec![] } else { response };
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                status,
+                response.len()
+            )
+            .unwrap();
+            stream.write_all(&response).unwrap();
+        }
+    }
+
+    fn decode(value: &str) -> String {
+        let bytes = value.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == b'%' {
+                decoded.push(u8::from_str_radix(&value[i + 1..i + 3], 16).unwrap());
+                i += 3;
+            } else {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+        String::from_utf8(decoded).unwrap()
+    }
+
+    fn error_xml(code: &str) -> Vec<u8> {
+        format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?><Error><Code>{}</Code></Error>", code).into_bytes()
+    }
+
+    fn list_xml(objects: &BTreeMap<String, Vec<u8>>, params: &BTreeMap<&str, String>) -> Vec<u8> {
+        let prefix = params.get("prefix").cloned().unwrap_or_default();
+        let start = params.get("continuation-token").map_or(0, |token| token.parse().unwrap());
+        let keys = objects
+            .keys()
+            .filter(|key| key.starts_with(&prefix) && !key[prefix.len()..].contains('/'))
+            .collect::<Vec<_>>();
+        let page = &keys[start..keys.len().min(start + PAGE_SIZE)];
+        let mut xml = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><ListBucketResult><Name>bucket</Name><Prefix>{}</Prefix><KeyCount>{}</KeyCount><IsTruncated>{}</IsTruncated>",
+            prefix,
+            page.len(),
+            start + PAGE_SIZE < keys.len(),
+        );
+        for key in page {
+            xml.push_str(&format!("<Contents><Key>{}</Key><Size>{}</Size></Contents>", key, objects[*key].len()));
+        }
+        if start + PAGE_SIZE < keys.len() {
+            xml.push_str(&format!("<NextContinuationToken>{}</NextContinuationToken>", start + PAGE_SIZE));
+        }
+        xml.push_str("</ListBucketResult>");
+        xml.into_bytes()
+    }
+
+    fn writer(endpoint: String) -> S3BlobWriter {
+        let config = SdkConfig::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .credentials_provider(aws_sdk_s3::config::SharedCredentialsProvider::new(Credentials::new(
+                "minioadmin",
+                "minioadmin",
+                None,
+                None,
+                "test",
+            )))
+            .build();
+        S3BlobWriter::from_config("bucket".to_string(), Some(endpoint), &config)
+    }
+
+    #[test]
+    fn test_s3_blob_writer() {
+        let (s3, endpoint) = FakeS3::start();
+        let writer = writer(endpoint);
+
+        for i in 0..5 {
+            writer.store(&PathBuf::from(format!("db/wal/{}.wal", i)), &[i; 3]).unwrap();
+        }
+        writer.store(Path::new("db/meta"), b"meta").unwrap();
+        assert_eq!(writer.load(Path::new("db/wal/3.wal")).unwrap(), vec![3; 3]);
+        assert!(writer.load(Path::new("db/wal/5.wal")).is_err());
+        assert!(writer.exists(Path::new("db/meta")).unwrap());
+        assert!(!writer.exists(Path::new("db/wal/5.wal")).unwrap());
+
+        // Listing spans three pages and excludes keys in subdirectories
+        let requests = s3.requests.load(Ordering::SeqCst);
+        let mut listed = writer.list(Path::new("db/wal")).unwrap();
+        listed.sort();
+        assert_eq!(listed, (0..5).map(|i| PathBuf::from(format!("db/wal/{}.wal", i))).collect::<Vec<_>>());
+        assert_eq!(s3.requests.load(Ordering::SeqCst) - requests, 3);
+        assert_eq!(writer.list(Path::new("db")).unwrap(), vec![PathBuf::from("db/meta")]);
+
+        writer.delete(Path::new("db/wal/0.wal")).unwrap();
+        assert_eq!(writer.list(Path::new("db/wal/")).unwrap().len(), 4);
+
+        // Transient errors are retried
+        s3.fail_requests.store(2, Ordering::SeqCst);
+        writer.store(Path::new("db/meta"), b"retried").unwrap();
+        assert_eq!(s3.fail_requests.load(Ordering::SeqCst), 0);
+        assert_eq!(writer.load(Path::new("db/meta")).unwrap(), b"retried");
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::{mpsc, Arc, RwLock};
@@ -8,7 +8,10 @@ use threadpool::ThreadPool;
 use super::azure_writer::AzureBlobWriter;
 use super::file_writer::{BlobWriter, FileBlobWriter, VersionedChecksummedBlobWriter};
 use super::gcs_writer::GCSBlobWriter;
-use super::meta_store::{MetaStore, PartitionMetadata, SubpartitionMetadata};
+use super::s3_writer::S3BlobWriter;
+use super::meta_store::{
+    ColumnRange, MetaStore, PartitionMetadata, RetentionPolicy, SubpartitionMetadata,
+};
 use super::partition_segment::PartitionSegment;
 use super::wal_segment::WalSegment;
 use super::{ColumnLoader, PartitionID};
@@ -45,6 +48,74 @@ impl ColumnLoader for Storage {
     }
 }
 
+/// Selects the storage backend by the prefix of `path` (gs://, az://, s3://, local files otherwise) and returns it
+/// together with the path of the database within the backend. Requests for s3:// paths are sent to `s3_endpoint` if
+/// given, for S3-compatible stores such as MinIO.
+pub fn blob_writer_for_path(path: &Path, s3_endpoint: Option<String>) -> (Arc<dyn BlobWriter>, PathBuf) {
+    let (writer, path): (Box<dyn BlobWriter>, PathBuf) = if path.starts_with("gs://") {
+        let components = path.components().collect::<Vec<_>>();
+        if components.len() < 2 {
+            panic!("Invalid GCS path: {:?}", path);
+        }
+        let bucket = components[1]
+            .as_os_str()
+            .to_str()
+            .expect("Invalid GCS path");
+        // create new path that omits the first two components
+        let path = components[2..]
+            .iter()
+            .map(|c| c.as_os_str())
+            .collect::<PathBuf>();
+        (
+            Box::new(GCSBlobWriter::new(bucket.to_string()).unwrap()),
+            path,
+        )
+    } else if path.starts_with("az://") {
+        let components = path.components().collect::<Vec<_>>();
+        if components.len() < 3 {
+            panic!("Invalid Azure path: {:?}", path);
+        }
+        let account = components[1]
+            .as_os_str()
+            .to_str()
+            .expect("Invalid Azure path");
+        let container = components[2]
+            .as_os_str()
+            .to_str()
+            .expect("Invalid Azure path");
+        // create new path that omits the first three components
+        let path = components[3..]
+            .iter()
+            .map(|c| c.as_os_str())
+            .collect::<PathBuf>();
+        (
+            Box::new(AzureBlobWriter::new(account, container).unwrap()),
+            path,
+        )
+    } else if path.starts_with("s3://") {
+        let components = path.components().collect::<Vec<_>>();
+        if components.len() < 2 {
+            panic!("Invalid S3 path: {:?}", path);
+        }
+        let bucket = components[1]
+            .as_os_str()
+            .to_str()
+            .expect("Invalid S3 path");
+        // create new path that omits the first two components
+        let path = components[2..]
+            .iter()
+            .map(|c| c.as_os_str())
+            .collect::<PathBuf>();
+        (
+            Box::new(S3BlobWriter::new(bucket.to_string(), s3_endpoint).unwrap()),
+            path,
+        )
+    } else {
+        (Box::new(FileBlobWriter::new()), path.to_owned())
+    };
+    (writer.into(), path)
+}
+
 pub struct Storage {
     wal_dir: PathBuf,
     meta_db_path: PathBuf,
@@ -57,56 +128,25 @@ pub struct Storage {
 }
 
 impl Storage {
+    /// Selects the storage backend by the prefix of `path`, see `blob_writer_for_path`.
     pub fn new(
         path: &Path,
         perf_counter: Arc<PerfCounter>,
         readonly: bool,
         io_threads: usize,
     ) -> (Storage, Vec<WalSegment<'static>>, u64) {
-        let (writer, path): (Box<dyn BlobWriter + Send + Sync + 'static>, PathBuf) =
-            if path.starts_with("gs://") {
-                let components = path.components().collect::<Vec<_>>();
-                if components.len() < 2 {
-                    panic!("Invalid GCS path: {:?}", path);
-                }
-                let bucket = components[1]
-                    .as_os_str()
-                    .to_str()
-                    .expect("Invalid GCS path");
-                // create new path that omits the first two components
-                let path = components[2..]
-                    .iter()
-                    .map(|c| c.as_os_str())
-                    .collect::<PathBuf>();
-                (
-                    Box::new(GCSBlobWriter::new(bucket.to_string()).unwrap()),
-                    path,
-                )
-            } else if path.starts_with("az://") {
-                let components = path.components().collect::<Vec<_>>();
-                if components.len() < 3 {
-                    panic!("Invalid Azure path: {:?}", path);
-                }
-                let account = components[1]
-                    .as_os_str()
-                    .to_str()
-                    .expect("Invalid Azure path");
-                let container = components[2]
-                    .as_os_str()
-                    .to_str()
-                    .expect("Invalid Azure path");
-                // create new path that omits the first three components
-                let path = components[3..]
-                    .iter()
-                    .map(|c| c.as_os_str())
-                    .collect::<PathBuf>();
-                (
-                    Box::new(AzureBlobWriter::new(account, container).unwrap()),
-                    path,
-                )
-            } else {
-                (Box::new(FileBlobWriter::new()), path.to_owned())
-            };
+        let (writer, path) = blob_writer_for_path(path, None);
+        Storage::with_blob_writer(&path, writer, perf_counter, readonly, io_threads)
+    }
+
+    /// Stores the database at `path` within `writer`.
+    pub fn with_blob_writer(
+        path: &Path,
+        writer: Arc<dyn BlobWriter>,
+        perf_counter: Arc<PerfCounter>,
+        readonly: bool,
+        io_threads: usize,
+    ) -> (Storage, Vec<WalSegment<'static>>, u64) {
         let writer = Arc::new(VersionedChecksummedBlobWriter::new(writer));
         let meta_db_path = path.join("meta");
         let wal_dir = path.join("wal");
@@ -359,6 +399,7 @@ impl Storage {
     }
 
     // Combine set of partitions into single new partition.
+    #[allow(clippy::too_many_arguments)]
     pub fn prepare_compact(
         &self,
         table: &str,
@@ -367,6 +408,7 @@ impl Storage {
         subpartitions: Vec<Vec<Arc<Column>>>,
         old_partitions: &[PartitionID],
         offset: usize,
+        column_ranges: HashMap<String, ColumnRange>,
     ) -> Vec<(u64, String)> {
         log::debug!(
             "compacting {} partitions into {} for table {}",
@@ -388,6 +430,7 @@ impl Storage {
             offset,
             subpartitions: metadata,
             subpartitions_by_last_column,
+            column_ranges,
         };
         self.write_subpartitions(&partition, subpartitions, true);
 
@@ -450,6 +493,21 @@ impl Storage {
         tracer.end_span(span_delete_orphaned_partitions);
     }
 
+    /// Sets or removes the retention policy of a table and persists the metastore.
+    pub fn persist_retention_policy(
+        &self,
+        table: &str,
+        policy: Option<RetentionPolicy>,
+        tracer: &mut SimpleTracer,
+    ) {
+        let meta_store = {
+            let mut meta_store = self.meta_store.write().unwrap();
+            meta_store.set_retention_policy(table, policy);
+            meta_store.clone()
+        };
+        self.write_metastore(&meta_store, tracer);
+    }
+
     pub fn persist_metastore(&self, earliest_uncommited_wal_id: u64, tracer: &mut SimpleTracer) {
         let span_persist_metastore = tracer.start_span("persist_metastore");
         let span_clone_meta_store = tracer.start_span("clone_meta_store");
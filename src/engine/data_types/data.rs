@@ -84,6 +84,18 @@ pub trait Data<'a>: Send + Sync {
     fn cast_ref_val_rows(&self) -> &ValRows<'a> {
         panic!("{}", self.type_error("cast_ref_val_rows"))
     }
+    fn cast_ref_val_sets(&self) -> &ValSets<'a> {
+        panic!("{}", self.type_error("cast_ref_val_sets"))
+    }
+    fn cast_ref_sketches(&self) -> &Sketches {
+        panic!("{}", self.type_error("cast_ref_sketches"))
+    }
+    fn cast_ref_float_lists(&self) -> &FloatLists {
+        panic!("{}", self.type_error("cast_ref_float_lists"))
+    }
+    fn cast_ref_welford_states(&self) -> &WelfordStates {
+        panic!("{}", self.type_error("cast_ref_welford_states"))
+    }
 
     fn cast_ref_mut_str(&mut self) -> &mut Vec<&'a str> {
         panic!("{}", self.type_error("cast_ref_mut_str"))
@@ -135,6 +147,18 @@ pub trait Data<'a>: Send + Sync {
     fn cast_ref_mut_val_rows(&mut self) -> &mut ValRows<'a> {
         panic!("{}", self.type_error("cast_ref_mut_val_rows"))
     }
+    fn cast_ref_mut_val_sets(&mut self) -> &mut ValSets<'a> {
+        panic!("{}", self.type_error("cast_ref_mut_val_sets"))
+    }
+    fn cast_ref_mut_sketches(&mut self) -> &mut Sketches {
+        panic!("{}", self.type_error("cast_ref_mut_sketches"))
+    }
+    fn cast_ref_mut_float_lists(&mut self) -> &mut FloatLists {
+        panic!("{}", self.type_error("cast_ref_mut_float_lists"))
+    }
+    fn cast_ref_mut_welford_states(&mut self) -> &mut WelfordStates {
+        panic!("{}", self.type_error("cast_ref_mut_welford_states"))
+    }
 
     fn to_mixed(&self) -> Vec<Val<'a>> {
         panic!("{}", self.type_error("to_mixed"))
@@ -147,6 +171,19 @@ pub trait Data<'a>: Send + Sync {
     fn display(&self) -> String;
 }
 
+/// Column of per-group partial aggregation states that are not plain values (e.g. sets or sketches).
+/// States are merged across batches and only converted into their final values once all partial results have been combined.
+pub trait AggregationStates<'a>: Data<'a> + Default + Clone + 'a {
+    type State: Clone + 'a;
+
+    fn states(&self) -> &Vec<Self::State>;
+    fn states_mut(&mut self) -> &mut Vec<Self::State>;
+    fn merge(state: &mut Self::State, other: &Self::State);
+
+    fn unwrap<'b>(data: &'b dyn Data<'a>) -> &'b Self;
+    fn unwrap_mut<'b>(data: &'b mut dyn Data<'a>) -> &'b mut Self;
+}
+
 impl<'a> DataSource for BoxedData<'a> {
     fn encoding_type(&self) -> EncodingType {
         self.get_type()
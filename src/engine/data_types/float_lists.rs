@@ -0,0 +1,124 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::engine::data_types::*;
+use crate::ingest::raw_val::RawVal;
+
+#[derive(Debug, Clone, Default)]
+pub struct FloatLists {
+    pub data: Vec<Vec<f64>>,
+}
+
+impl FloatLists {
+    /// Continuous percentile `p` of each list, `None` for empty lists.
+    pub fn percentiles(&mut self, p: f64) -> Vec<Option<f64>> {
+        self.data.iter_mut().map(|values| percentile_cont(values, p)).collect()
+    }
+}
+
+/// Interpolates linearly between the two values closest to rank `p * (len - 1)`.
+pub fn percentile_cont(values: &mut [f64], p: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable_by(f64::total_cmp);
+    let rank = p.clamp(0.0, 1.0) * (values.len() - 1) as f64;
+    let (lower, upper) = (values[rank.floor() as usize], values[rank.ceil() as usize]);
+    Some(lower + (upper - lower) * rank.fract())
+}
+
+impl<'a> Data<'a> for FloatLists {
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+    fn get_raw(&self, _i: usize) -> RawVal {
+        panic!("{}", self.type_error("get_raw"))
+    }
+    fn get_type(&self) -> EncodingType {
+        EncodingType::FloatLists
+    }
+
+    fn append_all(&mut self, _other: &dyn Data<'a>, _count: usize) -> Option<BoxedData<'a>> {
+        panic!("{}", self.type_error("append_all"))
+    }
+
+    fn slice_box<'b>(&'b self, _from: usize, _to: usize) -> BoxedData<'b>
+    where
+        'a: 'b,
+    {
+        panic!("{}", self.type_error("slice_box"))
+    }
+
+    fn type_error(&self, func_name: &str) -> String {
+        format!("FloatLists.{}", func_name)
+    }
+
+    fn display(&self) -> String {
+        format!("FloatLists{}", self)
+    }
+
+    fn cast_ref_float_lists(&self) -> &FloatLists {
+        self
+    }
+    fn cast_ref_mut_float_lists(&mut self) -> &mut FloatLists {
+        self
+    }
+}
+
+impl<'a> AggregationStates<'a> for FloatLists {
+    type State = Vec<f64>;
+
+    fn states(&self) -> &Vec<Vec<f64>> {
+        &self.data
+    }
+    fn states_mut(&mut self) -> &mut Vec<Vec<f64>> {
+        &mut self.data
+    }
+    fn merge(state: &mut Vec<f64>, other: &Vec<f64>) {
+        state.extend_from_slice(other);
+    }
+
+    fn unwrap<'b>(data: &'b dyn Data<'a>) -> &'b FloatLists {
+        data.cast_ref_float_lists()
+    }
+    fn unwrap_mut<'b>(data: &'b mut dyn Data<'a>) -> &'b mut FloatLists {
+        data.cast_ref_mut_float_lists()
+    }
+}
+
+impl fmt::Display for FloatLists {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const MAX_LISTS: usize = 10;
+        write!(
+            f,
+            "[{}",
+            self.data
+                .iter()
+                .take(MAX_LISTS)
+                .map(|list| format!("[{}]", list.iter().join(", ")))
+                .join(", ")
+        )?;
+        if self.data.len() > MAX_LISTS {
+            write!(f, ", ...] ({} more)", self.data.len() - MAX_LISTS)
+        } else {
+            write!(f, "]")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile_cont() {
+        let mut values = vec![4.0, 1.0, 3.0, 2.0];
+        assert_eq!(percentile_cont(&mut values, 0.0), Some(1.0));
+        assert_eq!(percentile_cont(&mut values, 0.5), Some(2.5));
+        assert_eq!(percentile_cont(&mut values, 0.25), Some(1.75));
+        assert_eq!(percentile_cont(&mut values, 1.0), Some(4.0));
+        assert_eq!(percentile_cont(&mut [7.0], 0.3), Some(7.0));
+        assert_eq!(percentile_cont(&mut [], 0.5), None);
+    }
+}
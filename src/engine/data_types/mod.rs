@@ -1,20 +1,28 @@
 mod byte_slices;
 mod data;
+mod float_lists;
 mod nullable_vec_data;
 mod scalar_data;
+mod sketches;
 mod types;
 mod val_rows;
+mod val_sets;
 mod vec_data;
+mod welford_states;
 
 use ordered_float::OrderedFloat;
 
 pub use self::byte_slices::*;
 pub use self::data::*;
+pub use self::float_lists::*;
 pub use self::nullable_vec_data::*;
 pub use self::scalar_data::*;
+pub use self::sketches::*;
 pub use self::types::*;
 pub use self::val_rows::*;
+pub use self::val_sets::*;
 pub use self::vec_data::*;
+pub use self::welford_states::*;
 
 #[allow(non_camel_case_types)]
 pub type of64 = OrderedFloat<f64>;
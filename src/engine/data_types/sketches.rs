@@ -0,0 +1,109 @@
+use std::fmt;
+
+use crate::engine::data_types::*;
+use crate::ingest::raw_val::RawVal;
+use crate::mem_store::value::Val;
+use crate::sketch::{HyperLogLog, TDigest};
+
+/// Partial aggregation state of approximate aggregates.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sketch {
+    HyperLogLog(HyperLogLog),
+    TDigest(TDigest),
+}
+
+impl Sketch {
+    pub fn insert(&mut self, val: &Val) {
+        match self {
+            _ if *val == Val::Null => {}
+            Sketch::HyperLogLog(hll) => hll.insert(val),
+            Sketch::TDigest(digest) => {
+                if let Some(x) = val.to_f64() {
+                    digest.insert(x)
+                }
+            }
+        }
+    }
+
+    pub fn merge(&mut self, other: &Sketch) {
+        match (self, other) {
+            (Sketch::HyperLogLog(hll), Sketch::HyperLogLog(other)) => hll.merge(other),
+            (Sketch::TDigest(digest), Sketch::TDigest(other)) => digest.merge(other),
+            (s, o) => panic!("Cannot merge sketches {:?} and {:?}", s, o),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Sketches {
+    pub data: Vec<Sketch>,
+}
+
+impl<'a> Data<'a> for Sketches {
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+    fn get_raw(&self, _i: usize) -> RawVal {
+        panic!("{}", self.type_error("get_raw"))
+    }
+    fn get_type(&self) -> EncodingType {
+        EncodingType::Sketches
+    }
+
+    fn append_all(&mut self, _other: &dyn Data<'a>, _count: usize) -> Option<BoxedData<'a>> {
+        panic!("{}", self.type_error("append_all"))
+    }
+
+    fn slice_box<'b>(&'b self, _from: usize, _to: usize) -> BoxedData<'b>
+    where
+        'a: 'b,
+    {
+        panic!("{}", self.type_error("slice_box"))
+    }
+
+    fn type_error(&self, func_name: &str) -> String {
+        format!("Sketches.{}", func_name)
+    }
+
+    fn display(&self) -> String {
+        format!("Sketches{}", self)
+    }
+
+    fn cast_ref_sketches(&self) -> &Sketches {
+        self
+    }
+    fn cast_ref_mut_sketches(&mut self) -> &mut Sketches {
+        self
+    }
+}
+
+impl<'a> AggregationStates<'a> for Sketches {
+    type State = Sketch;
+
+    fn states(&self) -> &Vec<Sketch> {
+        &self.data
+    }
+    fn states_mut(&mut self) -> &mut Vec<Sketch> {
+        &mut self.data
+    }
+    fn merge(state: &mut Sketch, other: &Sketch) {
+        state.merge(other);
+    }
+
+    fn unwrap<'b>(data: &'b dyn Data<'a>) -> &'b Sketches {
+        data.cast_ref_sketches()
+    }
+    fn unwrap_mut<'b>(data: &'b mut dyn Data<'a>) -> &'b mut Sketches {
+        data.cast_ref_mut_sketches()
+    }
+}
+
+impl fmt::Display for Sketches {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.data.first() {
+            Some(Sketch::HyperLogLog(_)) => write!(f, "[HyperLogLog; {}]", self.data.len()),
+            Some(Sketch::TDigest(_)) => write!(f, "[TDigest; {}]", self.data.len()),
+            None => write!(f, "[]"),
+        }
+    }
+}
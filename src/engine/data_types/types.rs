@@ -50,6 +50,15 @@ pub enum EncodingType {
     // Used as grouping key during aggregation/sorting operation when we cannot bit or byte pack the columns that make up the grouping key
     ValRows,
 
+    // Per-group sets of distinct values, partial aggregation state of `COUNT(DISTINCT x)`
+    ValSets,
+    // Per-group sketches, partial aggregation state of approximate aggregates
+    Sketches,
+    // Per-group lists of values, partial aggregation state of exact percentiles
+    FloatLists,
+    // Per-group running moments, partial aggregation state of variance and correlation aggregates
+    WelfordStates,
+
     Premerge,
     MergeOp,
 }
@@ -129,6 +138,10 @@ impl EncodingType {
             | EncodingType::ConstVal
             | EncodingType::ByteSlices(_)
             | EncodingType::ValRows
+            | EncodingType::ValSets
+            | EncodingType::Sketches
+            | EncodingType::FloatLists
+            | EncodingType::WelfordStates
             | EncodingType::Premerge
             | EncodingType::MergeOp => false,
         }
@@ -162,6 +175,10 @@ impl EncodingType {
             | EncodingType::ConstVal
             | EncodingType::ByteSlices(_)
             | EncodingType::ValRows
+            | EncodingType::ValSets
+            | EncodingType::Sketches
+            | EncodingType::FloatLists
+            | EncodingType::WelfordStates
             | EncodingType::Premerge
             | EncodingType::MergeOp => false,
         }
@@ -194,6 +211,10 @@ impl EncodingType {
             | EncodingType::ConstVal
             | EncodingType::ByteSlices(_)
             | EncodingType::ValRows
+            | EncodingType::ValSets
+            | EncodingType::Sketches
+            | EncodingType::FloatLists
+            | EncodingType::WelfordStates
             | EncodingType::Premerge
             | EncodingType::MergeOp => *self,
         }
@@ -221,6 +242,10 @@ impl EncodingType {
             | EncodingType::Bitvec
             | EncodingType::ByteSlices(_)
             | EncodingType::ValRows
+            | EncodingType::ValSets
+            | EncodingType::Sketches
+            | EncodingType::FloatLists
+            | EncodingType::WelfordStates
             | EncodingType::Premerge
             | EncodingType::MergeOp => false,
             EncodingType::ScalarF64
@@ -254,6 +279,10 @@ impl EncodingType {
             | EncodingType::Bitvec
             | EncodingType::ByteSlices(_)
             | EncodingType::ValRows
+            | EncodingType::ValSets
+            | EncodingType::Sketches
+            | EncodingType::FloatLists
+            | EncodingType::WelfordStates
             | EncodingType::Premerge
             | EncodingType::Null
             | EncodingType::MergeOp => false,
@@ -314,6 +343,10 @@ impl EncodingType {
             EncodingType::ValRows => 25,
             EncodingType::Premerge => 26,
             EncodingType::MergeOp => 27,
+            EncodingType::ValSets => 28,
+            EncodingType::Sketches => 29,
+            EncodingType::FloatLists => 30,
+            EncodingType::WelfordStates => 31,
             EncodingType::ByteSlices(x) => 64 + u8::try_from(x).unwrap(),
         }
     }
@@ -0,0 +1,99 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::engine::data_types::*;
+use crate::ingest::raw_val::RawVal;
+use crate::mem_store::value::Val;
+
+#[derive(Debug, Clone, Default)]
+pub struct ValSets<'a> {
+    pub data: Vec<HashSet<Val<'a>>>,
+}
+
+impl<'a> ValSets<'a> {
+    /// Number of distinct values in each set.
+    pub fn counts(&self) -> Vec<i64> {
+        self.data.iter().map(|set| set.len() as i64).collect()
+    }
+}
+
+impl<'a> Data<'a> for ValSets<'a> {
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+    fn get_raw(&self, i: usize) -> RawVal {
+        RawVal::Int(self.data[i].len() as i64)
+    }
+    fn get_type(&self) -> EncodingType {
+        EncodingType::ValSets
+    }
+
+    fn append_all(&mut self, _other: &dyn Data<'a>, _count: usize) -> Option<BoxedData<'a>> {
+        panic!("{}", self.type_error("append_all"))
+    }
+
+    fn slice_box<'b>(&'b self, _from: usize, _to: usize) -> BoxedData<'b>
+    where
+        'a: 'b,
+    {
+        panic!("{}", self.type_error("slice_box"))
+    }
+
+    fn type_error(&self, func_name: &str) -> String {
+        format!("ValSets.{}", func_name)
+    }
+
+    fn display(&self) -> String {
+        format!("ValSets{}", self)
+    }
+
+    fn cast_ref_val_sets(&self) -> &ValSets<'a> {
+        self
+    }
+    fn cast_ref_mut_val_sets(&mut self) -> &mut ValSets<'a> {
+        self
+    }
+}
+
+impl<'a> AggregationStates<'a> for ValSets<'a> {
+    type State = HashSet<Val<'a>>;
+
+    fn states(&self) -> &Vec<HashSet<Val<'a>>> {
+        &self.data
+    }
+    fn states_mut(&mut self) -> &mut Vec<HashSet<Val<'a>>> {
+        &mut self.data
+    }
+    fn merge(state: &mut HashSet<Val<'a>>, other: &HashSet<Val<'a>>) {
+        state.extend(other.iter().cloned());
+    }
+
+    fn unwrap<'b>(data: &'b dyn Data<'a>) -> &'b ValSets<'a> {
+        data.cast_ref_val_sets()
+    }
+    fn unwrap_mut<'b>(data: &'b mut dyn Data<'a>) -> &'b mut ValSets<'a> {
+        data.cast_ref_mut_val_sets()
+    }
+}
+
+impl fmt::Display for ValSets<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const MAX_SETS: usize = 10;
+        write!(
+            f,
+            "[{}",
+            self.data
+                .iter()
+                .take(MAX_SETS)
+                .map(|set| format!("{{{}}}", set.iter().map(|x| format!("{:?}", x)).join(", ")))
+                .join(", ")
+        )?;
+        if self.data.len() > MAX_SETS {
+            write!(f, ", ...] ({} more)", self.data.len() - MAX_SETS)
+        } else {
+            write!(f, "]")
+        }
+    }
+}
@@ -169,6 +169,12 @@ impl FromBytes<i64> for i64 {
     }
 }
 
+impl FromBytes<usize> for usize {
+    fn from_bytes(bytes: &[u8]) -> usize {
+        Cursor::new(bytes).read_u64::<NativeEndian>().unwrap() as usize
+    }
+}
+
 pub trait CastUsize {
     fn cast_usize(&self) -> usize;
 }
@@ -193,6 +199,10 @@ impl CastUsize for u64 {
     fn cast_usize(&self) -> usize { *self as usize }
 }
 
+impl CastUsize for usize {
+    fn cast_usize(&self) -> usize { *self }
+}
+
 #[derive(Debug, PartialEq, PartialOrd, Ord, Eq, Copy, Clone)]
 pub enum MergeOp {
     TakeLeft,
@@ -0,0 +1,175 @@
+use std::fmt;
+
+use crate::engine::data_types::*;
+use crate::engine::operators::Aggregator;
+use crate::ingest::raw_val::RawVal;
+
+/// Running count, means and (co)variances of a pair of variables, updated with Welford's algorithm.
+/// Univariate statistics are computed from states where both variables are identical.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Welford {
+    count: f64,
+    mean_x: f64,
+    mean_y: f64,
+    m2_x: f64,
+    m2_y: f64,
+    c_xy: f64,
+}
+
+impl Welford {
+    pub fn insert(&mut self, x: f64, y: f64) {
+        self.count += 1.0;
+        let delta_x = x - self.mean_x;
+        let delta_y = y - self.mean_y;
+        self.mean_x += delta_x / self.count;
+        self.mean_y += delta_y / self.count;
+        self.m2_x += delta_x * (x - self.mean_x);
+        self.m2_y += delta_y * (y - self.mean_y);
+        self.c_xy += delta_x * (y - self.mean_y);
+    }
+
+    /// Combines the states of two disjoint sets of values (Chan et al.).
+    pub fn merge(&mut self, other: &Welford) {
+        if other.count == 0.0 {
+            return;
+        }
+        let count = self.count + other.count;
+        let delta_x = other.mean_x - self.mean_x;
+        let delta_y = other.mean_y - self.mean_y;
+        let weight = self.count * other.count / count;
+        self.mean_x += delta_x * other.count / count;
+        self.mean_y += delta_y * other.count / count;
+        self.m2_x += other.m2_x + delta_x * delta_x * weight;
+        self.m2_y += other.m2_y + delta_y * delta_y * weight;
+        self.c_xy += other.c_xy + delta_x * delta_y * weight;
+        self.count = count;
+    }
+
+    /// Evaluates `aggregator`, returns `None` if it is undefined for the values observed so far.
+    pub fn finalize(&self, aggregator: Aggregator) -> Option<f64> {
+        match aggregator {
+            Aggregator::Variance if self.count > 1.0 => Some(self.m2_x / (self.count - 1.0)),
+            Aggregator::VariancePop if self.count > 0.0 => Some(self.m2_x / self.count),
+            Aggregator::StdDev if self.count > 1.0 => Some((self.m2_x / (self.count - 1.0)).sqrt()),
+            Aggregator::StdDevPop if self.count > 0.0 => Some((self.m2_x / self.count).sqrt()),
+            Aggregator::Corr if self.m2_x > 0.0 && self.m2_y > 0.0 => {
+                Some((self.c_xy / (self.m2_x * self.m2_y).sqrt()).clamp(-1.0, 1.0))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WelfordStates {
+    pub data: Vec<Welford>,
+}
+
+impl<'a> Data<'a> for WelfordStates {
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+    fn get_raw(&self, _i: usize) -> RawVal {
+        panic!("{}", self.type_error("get_raw"))
+    }
+    fn get_type(&self) -> EncodingType {
+        EncodingType::WelfordStates
+    }
+
+    fn append_all(&mut self, _other: &dyn Data<'a>, _count: usize) -> Option<BoxedData<'a>> {
+        panic!("{}", self.type_error("append_all"))
+    }
+
+    fn slice_box<'b>(&'b self, _from: usize, _to: usize) -> BoxedData<'b>
+    where
+        'a: 'b,
+    {
+        panic!("{}", self.type_error("slice_box"))
+    }
+
+    fn type_error(&self, func_name: &str) -> String {
+        format!("WelfordStates.{}", func_name)
+    }
+
+    fn display(&self) -> String {
+        format!("WelfordStates{}", self)
+    }
+
+    fn cast_ref_welford_states(&self) -> &WelfordStates {
+        self
+    }
+    fn cast_ref_mut_welford_states(&mut self) -> &mut WelfordStates {
+        self
+    }
+}
+
+impl<'a> AggregationStates<'a> for WelfordStates {
+    type State = Welford;
+
+    fn states(&self) -> &Vec<Welford> {
+        &self.data
+    }
+    fn states_mut(&mut self) -> &mut Vec<Welford> {
+        &mut self.data
+    }
+    fn merge(state: &mut Welford, other: &Welford) {
+        state.merge(other);
+    }
+
+    fn unwrap<'b>(data: &'b dyn Data<'a>) -> &'b WelfordStates {
+        data.cast_ref_welford_states()
+    }
+    fn unwrap_mut<'b>(data: &'b mut dyn Data<'a>) -> &'b mut WelfordStates {
+        data.cast_ref_mut_welford_states()
+    }
+}
+
+impl fmt::Display for WelfordStates {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[Welford; {}]", self.data.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let xs = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        let mut all = Welford::default();
+        let mut left = Welford::default();
+        let mut right = Welford::default();
+        for (i, &x) in xs.iter().enumerate() {
+            all.insert(x, x);
+            if i < 3 {
+                left.insert(x, x);
+            } else {
+                right.insert(x, x);
+            }
+        }
+        left.merge(&right);
+        left.merge(&Welford::default());
+        for welford in [all, left] {
+            assert_eq!(welford.finalize(Aggregator::VariancePop), Some(4.0));
+            assert_eq!(welford.finalize(Aggregator::StdDevPop), Some(2.0));
+            assert_eq!(welford.finalize(Aggregator::Variance), Some(32.0 / 7.0));
+            assert_eq!(welford.finalize(Aggregator::Corr), Some(1.0));
+        }
+    }
+
+    #[test]
+    fn test_corr() {
+        let mut welford = Welford::default();
+        for (x, y) in [(1.0, 3.0), (2.0, 1.0), (3.0, -1.0)] {
+            welford.insert(x, y);
+        }
+        assert_eq!(welford.finalize(Aggregator::Corr), Some(-1.0));
+
+        let mut single = Welford::default();
+        single.insert(1.0, 1.0);
+        assert_eq!(single.finalize(Aggregator::Variance), None);
+        assert_eq!(single.finalize(Aggregator::VariancePop), Some(0.0));
+        assert_eq!(single.finalize(Aggregator::Corr), None);
+    }
+}
@@ -1,5 +1,7 @@
+use crate::bitvec::BitVecMut;
 use crate::engine::*;
 use crate::mem_store::column::DataSource;
+use ordered_float::OrderedFloat;
 use std::cmp::min;
 use std::collections::HashMap;
 use std::ops::Range;
@@ -49,6 +51,58 @@ impl<'a> BatchResult<'a> {
         Ok(())
     }
 
+    /// Converts partial aggregation states that are only meaningful while batches are still being combined into their final values.
+    pub fn finalize_aggregates(&mut self) {
+        for i in 0..self.aggregations.len() {
+            let (index, aggregator) = self.aggregations[i];
+            let finalized = match self.columns[index].get_type() {
+                EncodingType::ValSets => Box::new(self.columns[index].cast_ref_val_sets().counts()),
+                EncodingType::Sketches => {
+                    let sketches = &mut self.columns[index].cast_ref_mut_sketches().data;
+                    match aggregator {
+                        Aggregator::ApproxQuantile(q) => nullable_f64(
+                            sketches
+                                .iter_mut()
+                                .map(|sketch| match sketch {
+                                    Sketch::TDigest(digest) => digest.quantile(q.0),
+                                    _ => panic!("Expected TDigest for {:?}", aggregator),
+                                })
+                                .collect(),
+                        ),
+                        _ => Box::new(
+                            sketches
+                                .iter()
+                                .map(|sketch| match sketch {
+                                    Sketch::HyperLogLog(hll) => hll.estimate(),
+                                    _ => panic!("Expected HyperLogLog for {:?}", aggregator),
+                                })
+                                .collect::<Vec<i64>>(),
+                        ),
+                    }
+                }
+                EncodingType::FloatLists => {
+                    let p = match aggregator {
+                        Aggregator::PercentileCont(p) => p.0,
+                        _ => panic!("Expected PercentileCont for {:?}", aggregator),
+                    };
+                    nullable_f64(self.columns[index].cast_ref_mut_float_lists().percentiles(p))
+                }
+                EncodingType::WelfordStates => nullable_f64(
+                    self.columns[index]
+                        .cast_ref_welford_states()
+                        .data
+                        .iter()
+                        .map(|welford| welford.finalize(aggregator))
+                        .collect(),
+                ),
+                _ => continue,
+            };
+            // States can be shared by multiple aggregations, e.g. different percentiles of the same column
+            self.aggregations[i].0 = self.columns.len();
+            self.columns.push(finalized);
+        }
+    }
+
     #[must_use]
     pub fn into_columns(
         self,
@@ -58,8 +112,8 @@ impl<'a> BatchResult<'a> {
     ) {
         let mut cols = HashMap::<String, Arc<dyn DataSource>>::default();
         let columns = self.columns.into_iter().map(Arc::new).collect::<Vec<_>>();
-        for projection in self.projection {
-            cols.insert(format!("_cs{}", projection), columns[projection].clone());
+        for (i, &projection) in self.projection.iter().enumerate() {
+            cols.insert(format!("_cs{}", i), columns[projection].clone());
         }
         for (i, &(aggregation, _)) in self.aggregations.iter().enumerate() {
             cols.insert(format!("_ca{}", i), columns[aggregation].clone());
@@ -185,7 +239,7 @@ pub fn combine<'a>(
 
         let mut executor = qp.prepare(data, batch_size, false)?;
         let mut results = executor.prepare_no_columns();
-        executor.run(1, &mut results, batch1.show || batch2.show)?;
+        executor.run(1, &mut results, batch1.show || batch2.show, None)?;
 
         let (columns, projection, aggregations, _) =
             results.collect_aliased(&group_by_cols, &aggregates, &[]);
@@ -300,7 +354,7 @@ pub fn combine<'a>(
 
             let mut executor = qp.prepare(data, batch_size, false)?;
             let mut results = executor.prepare_no_columns();
-            executor.run(1, &mut results, batch1.show || batch2.show)?;
+            executor.run(1, &mut results, batch1.show || batch2.show, None)?;
             let (columns, projection, _, order_by) =
                 results.collect_aliased(&projection, &[], &order_by);
 
@@ -440,3 +494,15 @@ fn null_to_val(qp: &mut QueryPlanner, plan: TypedBufferRef) -> TypedBufferRef {
         plan
     }
 }
+
+fn nullable_f64<'a>(values: Vec<Option<f64>>) -> BoxedData<'a> {
+    let mut present = vec![0u8; values.len().div_ceil(8)];
+    let mut data = Vec::with_capacity(values.len());
+    for (i, value) in values.into_iter().enumerate() {
+        if value.is_some() {
+            present.set(i);
+        }
+        data.push(OrderedFloat(value.unwrap_or(0.0)));
+    }
+    data.make_nullable(&present)
+}
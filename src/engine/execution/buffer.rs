@@ -59,6 +59,10 @@ impl BufferRef<Any> {
     pub fn scalar_string(self) -> BufferRef<Scalar<String>> { self.transmute() }
 
     pub fn val_rows<'a>(self) -> BufferRef<ValRows<'a>> { self.transmute() }
+    pub fn val_sets<'a>(self) -> BufferRef<ValSets<'a>> { self.transmute() }
+    pub fn sketches(self) -> BufferRef<Sketches> { self.transmute() }
+    pub fn float_lists(self) -> BufferRef<FloatLists> { self.transmute() }
+    pub fn welford_states(self) -> BufferRef<WelfordStates> { self.transmute() }
     pub fn val<'a>(self) -> BufferRef<Val<'a>> { self.transmute() }
 
     pub fn string(self) -> BufferRef<String> { self.transmute() }
@@ -127,6 +131,12 @@ impl From<BufferRef<u32>> for TypedBufferRef {
     }
 }
 
+impl From<BufferRef<u64>> for TypedBufferRef {
+    fn from(buffer: BufferRef<u64>) -> TypedBufferRef {
+        TypedBufferRef::new(buffer.any(), EncodingType::U64)
+    }
+}
+
 impl From<BufferRef<u8>> for TypedBufferRef {
     fn from(buffer: BufferRef<u8>) -> TypedBufferRef {
         TypedBufferRef::new(buffer.any(), EncodingType::U8)
@@ -187,6 +197,30 @@ impl<'a> From<BufferRef<ValRows<'a>>> for TypedBufferRef {
     }
 }
 
+impl<'a> From<BufferRef<ValSets<'a>>> for TypedBufferRef {
+    fn from(buffer: BufferRef<ValSets<'a>>) -> TypedBufferRef {
+        TypedBufferRef::new(buffer.any(), EncodingType::ValSets)
+    }
+}
+
+impl From<BufferRef<Sketches>> for TypedBufferRef {
+    fn from(buffer: BufferRef<Sketches>) -> TypedBufferRef {
+        TypedBufferRef::new(buffer.any(), EncodingType::Sketches)
+    }
+}
+
+impl From<BufferRef<FloatLists>> for TypedBufferRef {
+    fn from(buffer: BufferRef<FloatLists>) -> TypedBufferRef {
+        TypedBufferRef::new(buffer.any(), EncodingType::FloatLists)
+    }
+}
+
+impl From<BufferRef<WelfordStates>> for TypedBufferRef {
+    fn from(buffer: BufferRef<WelfordStates>) -> TypedBufferRef {
+        TypedBufferRef::new(buffer.any(), EncodingType::WelfordStates)
+    }
+}
+
 impl<'a> From<BufferRef<Val<'a>>> for TypedBufferRef {
     fn from(buffer: BufferRef<Val<'a>>) -> TypedBufferRef {
         TypedBufferRef::new(buffer.any(), EncodingType::Val)
@@ -365,6 +399,26 @@ impl TypedBufferRef {
         Ok(self.buffer.val_rows())
     }
 
+    pub fn val_sets<'a>(&self) -> Result<BufferRef<ValSets<'a>>, QueryError> {
+        ensure!(self.tag == EncodingType::ValSets, "{:?} != ValSets", self.tag);
+        Ok(self.buffer.val_sets())
+    }
+
+    pub fn sketches(&self) -> Result<BufferRef<Sketches>, QueryError> {
+        ensure!(self.tag == EncodingType::Sketches, "{:?} != Sketches", self.tag);
+        Ok(self.buffer.sketches())
+    }
+
+    pub fn float_lists(&self) -> Result<BufferRef<FloatLists>, QueryError> {
+        ensure!(self.tag == EncodingType::FloatLists, "{:?} != FloatLists", self.tag);
+        Ok(self.buffer.float_lists())
+    }
+
+    pub fn welford_states(&self) -> Result<BufferRef<WelfordStates>, QueryError> {
+        ensure!(self.tag == EncodingType::WelfordStates, "{:?} != WelfordStates", self.tag);
+        Ok(self.buffer.welford_states())
+    }
+
     pub fn val<'a>(&self) -> Result<BufferRef<Val<'a>>, QueryError> {
         ensure!(self.tag == EncodingType::Val, "{:?} != Val", self.tag);
         Ok(self.buffer.val())
@@ -0,0 +1,44 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::errors::QueryError;
+
+/// Shared between a running query and the caller of the query to abort execution before the query completes, either on
+/// request or once its timeout has elapsed.
+#[derive(Debug)]
+pub struct CancellationToken {
+    canceled: AtomicBool,
+    start_time: Instant,
+    timeout: Option<Duration>,
+}
+
+impl CancellationToken {
+    pub fn new(timeout: Option<Duration>) -> CancellationToken {
+        CancellationToken {
+            canceled: AtomicBool::new(false),
+            start_time: Instant::now(),
+            timeout,
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.canceled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_canceled(&self) -> bool {
+        self.canceled.load(Ordering::SeqCst)
+    }
+
+    /// Returns an error if the query should stop executing.
+    pub fn check(&self) -> Result<(), QueryError> {
+        if self.is_canceled() {
+            bail!(QueryError::Aborted, "canceled by request")
+        }
+        match self.timeout {
+            Some(timeout) if self.start_time.elapsed() >= timeout => {
+                bail!(QueryError::Aborted, "exceeded timeout of {:?}", timeout)
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use ordered_float::OrderedFloat;
+
+use crate::engine::planning::DownsampleMethod;
+
+/// Selects at most `max_points` of the `(x, y, row)` points and returns their rows ordered by `x`.
+pub fn downsample(
+    method: DownsampleMethod,
+    mut points: Vec<(f64, f64, usize)>,
+    max_points: usize,
+) -> Vec<usize> {
+    points.sort_by_key(|&(x, _, row)| (OrderedFloat(x), row));
+    let selected = if points.len() <= max_points {
+        (0..points.len()).collect()
+    } else {
+        match method {
+            DownsampleMethod::Lttb => lttb(&points, max_points),
+            DownsampleMethod::MinMax => min_max(&points, max_points),
+        }
+    };
+    selected.into_iter().map(|i| points[i].2).collect()
+}
+
+/// Largest-Triangle-Three-Buckets: always keeps the first and last point, and from each of the `max_points - 2`
+/// buckets in between the point that forms the largest triangle with the previously selected point and the
+/// average of the next bucket.
+fn lttb(points: &[(f64, f64, usize)], max_points: usize) -> Vec<usize> {
+    if max_points < 3 {
+        return [0, points.len() - 1][..max_points].to_vec();
+    }
+    let bucket_size = (points.len() - 2) as f64 / (max_points - 2) as f64;
+    let bucket_start = |bucket: usize| (bucket as f64 * bucket_size) as usize + 1;
+    let mut selected = Vec::with_capacity(max_points);
+    selected.push(0);
+    let mut a = 0;
+    for bucket in 0..max_points - 2 {
+        let next = bucket_start(bucket + 1)..bucket_start(bucket + 2).min(points.len());
+        let next_len = next.len().max(1) as f64;
+        let (avg_x, avg_y) = points[next]
+            .iter()
+            .fold((0.0, 0.0), |(x, y), p| (x + p.0 / next_len, y + p.1 / next_len));
+        let (ax, ay, _) = points[a];
+        let mut max_area = -1.0;
+        let start = bucket_start(bucket);
+        for (i, &(bx, by, _)) in points[start..bucket_start(bucket + 1)].iter().enumerate() {
+            let area = ((ax - avg_x) * (by - ay) - (ax - bx) * (avg_y - ay)).abs();
+            if area > max_area {
+                max_area = area;
+                a = start + i;
+            }
+        }
+        selected.push(a);
+    }
+    selected.push(points.len() - 1);
+    selected
+}
+
+/// Splits the points into `max_points / 2` buckets and keeps the points with minimum and maximum `y` of each.
+fn min_max(points: &[(f64, f64, usize)], max_points: usize) -> Vec<usize> {
+    let buckets = (max_points / 2).max(1);
+    let bucket_start = |bucket: usize| bucket * points.len() / buckets;
+    let mut selected = Vec::with_capacity(max_points);
+    for bucket in 0..buckets {
+        let range = bucket_start(bucket)..bucket_start(bucket + 1);
+        let start = range.start;
+        let min = range.clone().min_by_key(|&i| OrderedFloat(points[i].1)).unwrap_or(start);
+        let max = range.max_by_key(|&i| OrderedFloat(points[i].1)).unwrap_or(start);
+        selected.push(min.min(max));
+        if min != max && max_points > 1 {
+            selected.push(min.max(max));
+        }
+    }
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(ys: &[f64]) -> Vec<(f64, f64, usize)> {
+        ys.iter().enumerate().map(|(i, &y)| (i as f64, y, i)).collect()
+    }
+
+    #[test]
+    fn test_lttb() {
+        let ys = [0.0, 1.0, 0.0, 0.0, 10.0, 0.0, 0.0, -30.0, 0.0, 0.0];
+        assert_eq!(downsample(DownsampleMethod::Lttb, points(&ys), 20), (0..10).collect::<Vec<_>>());
+        assert_eq!(downsample(DownsampleMethod::Lttb, points(&ys), 4), vec![0, 4, 7, 9]);
+        assert_eq!(downsample(DownsampleMethod::Lttb, points(&ys), 1), vec![0]);
+    }
+
+    #[test]
+    fn test_min_max() {
+        let ys = [0.0, 1.0, 0.0, 0.0, 10.0, 0.0, 0.0, -3.0, 0.0, 5.0];
+        assert_eq!(downsample(DownsampleMethod::MinMax, points(&ys), 4), vec![0, 4, 7, 9]);
+        assert_eq!(downsample(DownsampleMethod::MinMax, points(&ys), 3), vec![4, 7]);
+    }
+
+    #[test]
+    fn test_sorts_by_x() {
+        let points = vec![(3.0, 1.0, 0), (1.0, 2.0, 1), (2.0, 3.0, 2)];
+        assert_eq!(downsample(DownsampleMethod::Lttb, points, 10), vec![1, 2, 0]);
+    }
+}
@@ -120,14 +120,16 @@ impl<'a> QueryExecutor<'a> {
         Scratchpad::new(self.buffer_provider.buffer_count(), HashMap::default())
     }
 
+    /// Runs all stages, checking `cancellation` (if any) after every batch so that long running stages can be aborted.
     pub fn run(
         &mut self,
         len: usize,
         scratchpad: &mut Scratchpad<'a>,
         show: bool,
+        cancellation: Option<&CancellationToken>,
     ) -> Result<(), QueryError> {
         for stage in 0..self.stages.len() {
-            self.run_stage(len, stage, scratchpad, show)?;
+            self.run_stage(len, stage, scratchpad, show, cancellation)?;
         }
         Ok(())
     }
@@ -534,30 +536,34 @@ impl<'a> QueryExecutor<'a> {
         }
 
         // Insert operation to buffer output for any streaming op in a streaming stage that has non-streaming consumers
+        // or consumers in a different stage
         let mut substitutions = vec![];
         let mut count = self.buffer_provider.buffer_count();
         let mut block_output_buffers = HashMap::new();
         let mut new_ops = vec![]; // new op and corresponding stage
         for (i, consumer) in self.ops.iter().enumerate() {
             for input in consumer.inputs() {
-                if !consumer.can_stream_input(input.i) || !total_order[stage_for_op[i]].stream {
-                    for &producer in &producers[input.i] {
-                        if self.ops[producer].can_stream_output(input.i)
-                            && !block_output[producer]
-                            && total_order[stage_for_op[producer]].stream
-                        {
-                            substitutions.push((i, input.i));
-                            if let Entry::Vacant(e) = block_output_buffers.entry(input.i) {
-                                new_ops.push((
-                                    self.buffer_provider.all_buffers[input.i].tag,
-                                    input,
-                                    stage_for_op[producer],
-                                ));
-                                e.insert(count);
-                                count += 1
-                            }
-                            break;
+                let block_input =
+                    !consumer.can_stream_input(input.i) || !total_order[stage_for_op[i]].stream;
+                for &producer in &producers[input.i] {
+                    let cross_stage = stage_for_op[producer] != stage_for_op[i]
+                        && !self.buffer_provider.all_buffers[input.i].tag.is_scalar();
+                    if (block_input || cross_stage)
+                        && self.ops[producer].can_stream_output(input.i)
+                        && !block_output[producer]
+                        && total_order[stage_for_op[producer]].stream
+                    {
+                        substitutions.push((i, input.i));
+                        if let Entry::Vacant(e) = block_output_buffers.entry(input.i) {
+                            new_ops.push((
+                                self.buffer_provider.all_buffers[input.i].tag,
+                                input,
+                                stage_for_op[producer],
+                            ));
+                            e.insert(count);
+                            count += 1
                         }
+                        break;
                     }
                 }
             }
@@ -588,6 +594,12 @@ impl<'a> QueryExecutor<'a> {
             let mut already_substituted = vec![];
             for input in consumer.inputs() {
                 if consumer.can_stream_input(input.i) && !already_substituted.contains(&input.i) {
+                    // Inputs that were replaced by block buffers above
+                    if input.i >= producers.len() {
+                        substitutions.push((i, input.i));
+                        already_substituted.push(input.i);
+                        continue;
+                    }
                     for &producer in &producers[input.i] {
                         if !self.ops[producer].can_stream_output(input.i)
                             || block_output[producer]
@@ -676,6 +688,7 @@ impl<'a> QueryExecutor<'a> {
         stage: usize,
         scratchpad: &mut Scratchpad<'a>,
         show: bool,
+        cancellation: Option<&CancellationToken>,
     ) -> Result<(), QueryError> {
         let (max_length, batch_size) = self.init_stage(column_length, stage, scratchpad);
         let stream = self.stages[stage].stream;
@@ -719,6 +732,9 @@ impl<'a> QueryExecutor<'a> {
                 }
                 has_more |= self.ops[op].has_more() && stream;
             }
+            if let Some(cancellation) = cancellation {
+                cancellation.check()?;
+            }
             iters += 1;
         }
         for &(op, _) in &self.stages[stage].ops {
@@ -1,10 +1,16 @@
 pub mod query_task;
 mod buffer;
+mod cancellation;
 mod executor;
 mod batch_merging;
+mod downsample;
+pub(crate) mod pruning;
 mod scratchpad;
+mod window;
 
 pub use self::buffer::*;
+pub use self::cancellation::CancellationToken;
 pub use self::scratchpad::*;
 pub use self::executor::*;
+pub use self::downsample::downsample;
 pub use self::batch_merging::{BatchResult, combine};
\ No newline at end of file
@@ -0,0 +1,179 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::disk_store::meta_store::ColumnRange;
+use crate::ingest::raw_val::{parse_timestamp, RawVal};
+use crate::syntax::expression::{Expr, Func2Type};
+use crate::TimeUnit;
+
+/// Returns `false` only if no row with column values within `ranges` can satisfy `filter`.
+/// Columns without range might contain any value (or be missing from the partition entirely).
+pub fn may_match(filter: &Expr, ranges: &HashMap<String, ColumnRange>) -> bool {
+    match filter {
+        Expr::Func2(Func2Type::And, lhs, rhs) => may_match(lhs, ranges) && may_match(rhs, ranges),
+        Expr::Func2(Func2Type::Or, lhs, rhs) => may_match(lhs, ranges) || may_match(rhs, ranges),
+        Expr::Func2(op, lhs, rhs) => match (&**lhs, &**rhs) {
+            (Expr::ColName(column), Expr::Const(value)) => match ranges.get(column) {
+                Some(range) => range_may_match(*op, range, value),
+                None => true,
+            },
+            (Expr::Const(value), Expr::ColName(column)) => match (ranges.get(column), flip(*op)) {
+                (Some(range), Some(op)) => range_may_match(op, range, value),
+                _ => true,
+            },
+            _ => true,
+        },
+        _ => true,
+    }
+}
+
+/// Whether any value `x` with `range.min <= x <= range.max` can satisfy `x <op> value`.
+fn range_may_match(op: Func2Type, range: &ColumnRange, value: &RawVal) -> bool {
+    // String literals compared with integer columns are timestamps, in seconds unless the column has a timestamp type
+    let timestamp;
+    let value = match (value, &range.min) {
+        (RawVal::Str(s), RawVal::Int(_)) => {
+            match parse_timestamp(s, range.timestamp_unit.unwrap_or(TimeUnit::Second)) {
+                Some(ts) => {
+                    timestamp = RawVal::Int(ts);
+                    &timestamp
+                }
+                None => return true,
+            }
+        }
+        _ => value,
+    };
+    let (min, max) = match (compare(&range.min, value), compare(&range.max, value)) {
+        (Some(min), Some(max)) => (min, max),
+        // Comparison with value of different type, don't attempt to determine result
+        _ => return true,
+    };
+    match op {
+        Func2Type::Equals => min != Ordering::Greater && max != Ordering::Less,
+        Func2Type::NotEquals => min != Ordering::Equal || max != Ordering::Equal,
+        Func2Type::LT => min == Ordering::Less,
+        Func2Type::LTE => min != Ordering::Greater,
+        Func2Type::GT => max == Ordering::Greater,
+        Func2Type::GTE => max != Ordering::Less,
+        _ => true,
+    }
+}
+
+/// Operator that yields the same result with the order of operands reversed.
+fn flip(op: Func2Type) -> Option<Func2Type> {
+    match op {
+        Func2Type::Equals | Func2Type::NotEquals => Some(op),
+        Func2Type::LT => Some(Func2Type::GT),
+        Func2Type::LTE => Some(Func2Type::GTE),
+        Func2Type::GT => Some(Func2Type::LT),
+        Func2Type::GTE => Some(Func2Type::LTE),
+        _ => None,
+    }
+}
+
+/// Compares values the same way as the query engine, integers are converted to floats when compared with floats.
+fn compare(lhs: &RawVal, rhs: &RawVal) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (RawVal::Int(l), RawVal::Int(r)) => Some(l.cmp(r)),
+        (RawVal::Float(l), RawVal::Float(r)) => l.0.partial_cmp(&r.0),
+        (RawVal::Int(l), RawVal::Float(r)) => (*l as f64).partial_cmp(&r.0),
+        (RawVal::Float(l), RawVal::Int(r)) => l.0.partial_cmp(&(*r as f64)),
+        (RawVal::Str(l), RawVal::Str(r)) => Some(l.cmp(r)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::parser::parse_query;
+
+    fn may_match_query(filter: &str) -> bool {
+        let query = parse_query(&format!("SELECT * FROM t WHERE {}", filter)).unwrap();
+        let ranges = HashMap::from([
+            (
+                "ts".to_string(),
+                ColumnRange {
+                    min: RawVal::Int(100),
+                    max: RawVal::Int(200),
+                    timestamp_unit: None,
+                },
+            ),
+            (
+                "value".to_string(),
+                ColumnRange {
+                    min: RawVal::Float(0.5.into()),
+                    max: RawVal::Float(1.5.into()),
+                    timestamp_unit: None,
+                },
+            ),
+            (
+                "name".to_string(),
+                ColumnRange {
+                    min: RawVal::Str("bob".to_string()),
+                    max: RawVal::Str("eve".to_string()),
+                    timestamp_unit: None,
+                },
+            ),
+            (
+                // 2024-01-10 to 2024-01-20
+                "time".to_string(),
+                ColumnRange {
+                    min: RawVal::Int(1_704_844_800_000),
+                    max: RawVal::Int(1_705_708_800_000),
+                    timestamp_unit: Some(TimeUnit::Millisecond),
+                },
+            ),
+        ]);
+        may_match(&query.filter, &ranges)
+    }
+
+    #[test]
+    fn test_int_range() {
+        assert!(may_match_query("ts > 150"));
+        assert!(may_match_query("ts >= 200"));
+        assert!(!may_match_query("ts > 200"));
+        assert!(!may_match_query("ts < 100"));
+        assert!(may_match_query("ts <= 100"));
+        assert!(!may_match_query("ts = 99"));
+        assert!(may_match_query("ts = 100"));
+        assert!(!may_match_query("250 < ts"));
+        assert!(may_match_query("ts > 1.5"));
+        assert!(!may_match_query("ts > 200.5"));
+    }
+
+    #[test]
+    fn test_float_and_string_range() {
+        assert!(!may_match_query("value > 2"));
+        assert!(may_match_query("value < 1"));
+        assert!(!may_match_query("name = 'alice'"));
+        assert!(may_match_query("name = 'carol'"));
+        assert!(!may_match_query("name > 'f'"));
+        // Comparisons between values of different type are never used for pruning
+        assert!(may_match_query("name > 5"));
+    }
+
+    #[test]
+    fn test_timestamp_range() {
+        assert!(may_match_query("time > '2024-01-15'"));
+        assert!(!may_match_query("time > '2024-01-20'"));
+        assert!(!may_match_query("time < '2024-01-09T23:59:59Z'"));
+        assert!(may_match_query("time <= '2024-01-10 00:00:00'"));
+        assert!(!may_match_query("'2024-01-25' <= time"));
+        assert!(!may_match_query("time = '2024-02-01'"));
+        // Integer columns without timestamp type are compared as seconds, like the query engine does
+        assert!(!may_match_query("ts > '2024-01-15'"));
+        assert!(may_match_query("ts < '2024-01-15'"));
+        assert!(may_match_query("time > 'not a timestamp'"));
+    }
+
+    #[test]
+    fn test_connectives() {
+        assert!(!may_match_query("ts > 150 AND value > 2"));
+        assert!(may_match_query("ts > 250 OR value < 1"));
+        assert!(!may_match_query("ts > 250 OR value > 2"));
+        assert!(may_match_query("NOT (ts > 250)"));
+        assert!(may_match_query("missing > 0 AND ts > 150"));
+        assert!(!may_match_query("missing > 0 AND ts > 250"));
+    }
+}
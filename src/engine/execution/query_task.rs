@@ -2,7 +2,9 @@ use std::cmp;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::iter;
 use std::mem;
+use std::ops::Range;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -12,15 +14,20 @@ use itertools::Itertools;
 use ordered_float::OrderedFloat;
 use serde::{Deserialize, Serialize};
 
+use super::downsample;
+use super::pruning;
+use super::window;
 use crate::engine::*;
 use crate::ingest::raw_val::RawVal;
 use crate::mem_store::column::DataSource;
 use crate::mem_store::partition::Partition;
+use crate::mem_store::strings::fast_build_string_column;
 use crate::observability::QueryPerfCounter;
 use crate::scheduler::disk_read_scheduler::DiskReadScheduler;
 use crate::scheduler::*;
 use crate::syntax::expression::Expr;
-use crate::QueryResult;
+use crate::syntax::limit::LimitClause;
+use crate::{LogicalType, QueryResult, TimeUnit};
 
 pub struct QueryTask {
     main_phase: NormalFormQuery,
@@ -29,14 +36,25 @@ pub struct QueryTask {
     rowformat: bool,
     show: Vec<usize>,
     partitions: Vec<Arc<Partition>>,
+    // Rows covered by each partition, shifted so that partitions of different tables do not overlap
+    partition_ranges: Vec<Range<usize>>,
     referenced_cols: HashSet<String>,
+    // Whether the query references the synthetic `_table` column
+    table_column: bool,
     output_colnames: Vec<String>,
+    output_exprs: Vec<Expr>,
+    // Downsampling applied to the final result, index of the select column with the x values, and the query limit
+    // which is applied only after downsampling
+    downsample: Option<(Downsample, usize, LimitClause)>,
+    // Window functions evaluated over the final result, and the query limit which is applied only after evaluating them
+    windows: Option<(Vec<WindowColumns>, LimitClause)>,
     // Tells us how to reconstruct final output in correct ordering from `projection` and `aggregate` columns
     result_column_sources: Vec<ResultColumn>,
     start_time: Instant,
     db: Arc<DiskReadScheduler>,
     perf_counter: Arc<QueryPerfCounter>,
     batch_size: usize,
+    cancellation: Arc<CancellationToken>,
 
     // Lifetime is not actually static, but tied to the lifetime of this struct.
     // There is currently no good way to express this constraint in Rust.
@@ -48,12 +66,56 @@ pub struct QueryTask {
     sender: SharedSender<QueryResult>,
 }
 
+/// Window function and the select columns that evaluate its partition and order keys
+struct WindowColumns {
+    window: Window,
+    partition_by: Vec<usize>,
+    order_by: Vec<usize>,
+}
+
+/// Synthetic column that contains the name of the table of each row
+pub const TABLE_COLUMN: &str = "_table";
+
+/// Row ranges of partitions of different tables overlap, and skipped partitions leave gaps in the row ranges of a table,
+/// so each partition is assigned a contiguous range ordered by table and by the position of the partition in its table.
+fn partition_ranges(partitions: &[Arc<Partition>]) -> Vec<Range<usize>> {
+    let mut table_indices = HashMap::<&str, usize>::new();
+    for partition in partitions {
+        let next_index = table_indices.len();
+        table_indices.entry(partition.table_name()).or_insert(next_index);
+    }
+    let mut ranges = vec![0..0; partitions.len()];
+    let mut end = 0;
+    for i in (0..partitions.len())
+        .sorted_by_key(|&i| (table_indices[partitions[i].table_name()], partitions[i].range().start))
+    {
+        ranges[i] = end..(end + partitions[i].len());
+        end += partitions[i].len();
+    }
+    ranges
+}
+
+fn table_name_column(partition: &Partition) -> Arc<dyn DataSource> {
+    let (table, len) = (partition.table_name(), partition.len());
+    fast_build_string_column(
+        TABLE_COLUMN,
+        iter::repeat_n(table, len),
+        len,
+        false,
+        false,
+        table.len() * len,
+        None,
+    )
+}
+
 pub struct QueryState<'a> {
     completed_batches: usize,
     partial_results: BTreeMap<usize, BatchResult<'a>>,
     explains: Vec<String>,
     rows_collected: usize,
     colstacks: Vec<Vec<HashMap<String, Arc<dyn DataSource>>>>,
+    // Logical types of referenced columns, falls back to `Plain` if partitions disagree
+    column_types: HashMap<String, LogicalType>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -74,6 +136,8 @@ pub enum BasicTypeColumn {
     String(Vec<String>),
     Null(usize),
     Mixed(Vec<RawVal>),
+    Bool(Vec<bool>),
+    Timestamp(TimeUnit, Vec<i64>),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -91,11 +155,13 @@ impl QueryTask {
         rowformat: bool,
         explain: bool,
         show: Vec<usize>,
-        source: Vec<Arc<Partition>>,
+        mut source: Vec<Arc<Partition>>,
         db: Arc<DiskReadScheduler>,
         sender: SharedSender<QueryResult>,
         batch_size: usize,
         column_names: Option<Vec<String>>,
+        cancellation: Arc<CancellationToken>,
+        perf_counter: Arc<QueryPerfCounter>,
     ) -> Result<QueryTask, QueryError> {
         let start_time = Instant::now();
         if query.is_select_star() {
@@ -106,14 +172,109 @@ impl QueryTask {
                     expr: Expr::ColName(name.clone()),
                     name,
                 })
-                .sorted_by(|a, b| a.name.cmp(&b.name))
                 .collect()
         }
 
-        let referenced_cols = query.find_referenced_cols();
+        let output_colnames = query.select.iter().map(|c| c.name.clone()).collect();
+        let output_exprs = query.select.iter().map(|c| c.expr.clone()).collect();
+        // x values are added as hidden select column if they are not already part of the result
+        let downsample = query.downsample.clone().map(|downsample| {
+            let x = match query.select.iter().position(|c| c.expr == downsample.x) {
+                Some(x) => x,
+                None => {
+                    query.select.push(ColumnInfo {
+                        expr: downsample.x.clone(),
+                        name: "_downsample_x".to_string(),
+                    });
+                    query.select.len() - 1
+                }
+            };
+            let limit = mem::replace(
+                &mut query.limit,
+                LimitClause {
+                    limit: u64::MAX,
+                    offset: 0,
+                },
+            );
+            (downsample, x, limit)
+        });
+
+        // Partition and order keys of windows are added as hidden select columns
+        let windows = if query.windows.is_empty() {
+            None
+        } else {
+            if downsample.is_some() {
+                bail!(QueryError::NotImplemented, "Window functions combined with LTTB or MINMAX")
+            }
+            if query.distinct {
+                bail!(QueryError::NotImplemented, "Window functions combined with DISTINCT")
+            }
+            let windows: Vec<WindowColumns> = mem::take(&mut query.windows)
+                .into_iter()
+                .enumerate()
+                .map(|(i, window)| {
+                    let mut hidden_column = |expr: &Expr, name: String| {
+                        match query.select.iter().position(|c| c.expr == *expr) {
+                            Some(column) => column,
+                            None => {
+                                query.select.push(ColumnInfo {
+                                    expr: expr.clone(),
+                                    name,
+                                });
+                                query.select.len() - 1
+                            }
+                        }
+                    };
+                    let partition_by = window
+                        .partition_by
+                        .iter()
+                        .enumerate()
+                        .map(|(j, expr)| hidden_column(expr, format!("_window{}_partition{}", i, j)))
+                        .collect();
+                    let order_by = window
+                        .order_by
+                        .iter()
+                        .enumerate()
+                        .map(|(j, (expr, _))| hidden_column(expr, format!("_window{}_order{}", i, j)))
+                        .collect();
+                    WindowColumns {
+                        window,
+                        partition_by,
+                        order_by,
+                    }
+                })
+                .collect();
+            // Functions without argument evaluate a placeholder, which has to be an aggregate if rows are grouped
+            let grouped = !query.group_by.is_empty()
+                || query.having.is_some()
+                || query.select.iter().any(|c| Query::ensure_no_aggregates(&c.expr).is_err());
+            if grouped {
+                for WindowColumns { window, .. } in &windows {
+                    if !window.function.has_argument() {
+                        query.select[window.column].expr =
+                            Expr::Aggregate(Aggregator::Count, Box::new(Expr::Const(RawVal::Int(1))));
+                    }
+                }
+            }
+            let limit = mem::replace(
+                &mut query.limit,
+                LimitClause {
+                    limit: u64::MAX,
+                    offset: 0,
+                },
+            );
+            Some((windows, limit))
+        };
+
+        // Skip partitions that cannot contain matching rows before any of their columns are loaded
+        if query.join.is_none() {
+            source.retain(|partition| pruning::may_match(&query.filter, partition.column_ranges()));
+        }
+
+        let mut referenced_cols = query.find_referenced_cols();
+        let table_column = referenced_cols.remove(TABLE_COLUMN);
 
         let (main_phase, final_pass, result_column_sources) = query.normalize()?;
-        let output_colnames = query.select.iter().map(|c| c.name.clone()).collect();
 
         let task = QueryTask {
             main_phase,
@@ -121,14 +282,20 @@ impl QueryTask {
             explain,
             rowformat,
             show,
+            partition_ranges: partition_ranges(&source),
             partitions: source,
             referenced_cols,
+            table_column,
             output_colnames,
+            output_exprs,
+            downsample,
+            windows,
             result_column_sources,
             start_time,
             db,
-            perf_counter: Arc::default(),
+            perf_counter,
             batch_size,
+            cancellation,
 
             unsafe_state: Mutex::new(QueryState {
                 partial_results: BTreeMap::new(),
@@ -136,6 +303,7 @@ impl QueryTask {
                 explains: Vec::new(),
                 rows_collected: 0,
                 colstacks: Vec::new(),
+                column_types: HashMap::new(),
             }),
             batch_index: AtomicUsize::new(0),
             completed: AtomicBool::new(false),
@@ -162,16 +330,31 @@ impl QueryTask {
     }
 
     pub fn run(&self) {
-        let mut rows_scanned = 0;
         let mut rows_collected = 0;
         let mut colstack = Vec::new();
         let mut batch_results = BTreeMap::<usize, BatchResult>::new();
         let mut explains = Vec::new();
+        let mut column_types = HashMap::<String, LogicalType>::new();
         while let Some((partition, id)) = self.next_partition() {
+            if let Err(error) = self.cancellation.check() {
+                self.fail_with(error);
+                return;
+            }
             let show = self.show.contains(&id);
-            let cols =
+            let mut cols =
                 partition.get_cols(&self.referenced_cols, &self.db, self.perf_counter.as_ref());
-            rows_scanned += cols.iter().next().map_or(0, |c| c.1.len());
+            self.perf_counter.scanned_partition(partition.len() as u64);
+            if self.table_column {
+                cols.insert(TABLE_COLUMN.to_string(), table_name_column(partition));
+            }
+            if let Some(join) = &self.main_phase.join {
+                join.add_columns(&mut cols);
+            }
+            for (name, col) in &cols {
+                if col.encoding_type() != EncodingType::Null {
+                    merge_logical_type(&mut column_types, name, col.logical_type());
+                }
+            }
             let unsafe_cols = unsafe {
                 mem::transmute::<
                     &HashMap<String, Arc<dyn DataSource>>,
@@ -184,8 +367,9 @@ impl QueryTask {
                     self.explain,
                     show,
                     id,
-                    partition.range(),
+                    self.partition_ranges[id].clone(),
                     self.batch_size,
+                    &self.cancellation,
                 )
             } else {
                 self.main_phase.run_aggregate(
@@ -193,8 +377,9 @@ impl QueryTask {
                     self.explain,
                     show,
                     id,
-                    partition.range(),
+                    self.partition_ranges[id].clone(),
                     self.batch_size,
+                    &self.cancellation,
                 )
             } {
                 Ok(result) => result,
@@ -227,10 +412,12 @@ impl QueryTask {
             // TODO: abort early if we have selected sufficient number of rows from initial partition
         }
 
+        // Must happen before pushing results, the last push produces the final output
+        self.push_column_types(column_types);
+
         // TODO: parallelize combining results from different threads
         for (_, result) in batch_results {
-            self.push_result(result, rows_scanned, rows_collected, explains.clone());
-            rows_scanned = 0;
+            self.push_result(result, rows_collected, explains.clone());
             rows_collected = 0;
             explains.clear();
         }
@@ -279,7 +466,6 @@ impl QueryTask {
     fn push_result(
         &self,
         result: BatchResult,
-        rows_scanned: usize,
         rows_collected: usize,
         explains: Vec<String>,
     ) {
@@ -289,7 +475,6 @@ impl QueryTask {
         }
         state.completed_batches += result.batch_count;
         state.explains.extend(explains);
-        self.perf_counter.scanned(rows_scanned as u64);
         state.rows_collected += rows_collected;
 
         let result = unsafe { mem::transmute::<BatchResult, BatchResult<'static>>(result) };
@@ -319,7 +504,12 @@ impl QueryTask {
                 self.fail_with_no_lock(error);
                 return;
             }
-            let full_result = owned_results.into_iter().next().unwrap().1;
+            if let Err(error) = self.cancellation.check() {
+                self.fail_with_no_lock(error);
+                return;
+            }
+            let mut full_result = owned_results.into_iter().next().unwrap().1;
+            full_result.finalize_aggregates();
             let final_result = if let Some(final_pass) = &self.final_pass {
                 let (data_sources, _unsafe_referenced_buffers) = full_result.into_columns();
                 let cols = unsafe {
@@ -328,26 +518,44 @@ impl QueryTask {
                         &'static HashMap<String, Arc<dyn DataSource>>,
                     >(&data_sources)
                 };
-                let full_result = final_pass
-                    .run(
-                        cols,
-                        self.explain,
-                        !self.show.is_empty(),
-                        0xdead_beef,
-                        0..cols.iter().next().map(|(_, c)| c.len()).unwrap_or(0),
-                        self.batch_size,
-                    )
-                    .unwrap()
-                    .0;
-                self.convert_to_output_format(&full_result, &state.explains)
+                let full_result = match final_pass.run(
+                    cols,
+                    self.explain,
+                    !self.show.is_empty(),
+                    0xdead_beef,
+                    0..cols.iter().next().map(|(_, c)| c.len()).unwrap_or(0),
+                    self.batch_size,
+                    &self.cancellation,
+                ) {
+                    Ok((full_result, _)) => full_result,
+                    Err(error) => {
+                        self.fail_with_no_lock(error);
+                        return;
+                    }
+                };
+                self.convert_to_output_format(&full_result, &state.explains, &state.column_types)
             } else {
-                self.convert_to_output_format(&full_result, &state.explains)
+                self.convert_to_output_format(&full_result, &state.explains, &state.column_types)
+            };
+            let final_result = match final_result {
+                Ok(final_result) => final_result,
+                Err(error) => {
+                    self.fail_with_no_lock(error);
+                    return;
+                }
             };
             self.sender.send(Ok(final_result));
             self.completed.store(true, Ordering::SeqCst);
         }
     }
 
+    fn push_column_types(&self, column_types: HashMap<String, LogicalType>) {
+        let mut state = self.unsafe_state.lock().unwrap();
+        for (name, logical_type) in column_types {
+            merge_logical_type(&mut state.column_types, &name, logical_type);
+        }
+    }
+
     fn push_colstack(&self, colstack: Vec<HashMap<String, Arc<dyn DataSource>>>) {
         let mut state = self.unsafe_state.lock().unwrap();
         state.colstacks.push(colstack);
@@ -377,28 +585,79 @@ impl QueryTask {
         &self,
         full_result: &BatchResult,
         explains: &[String],
-    ) -> QueryOutput {
-        let lo = self
-            .final_pass
-            .as_ref()
-            .map(|x| &x.limit)
-            .unwrap_or(&self.main_phase.limit);
+        column_types: &HashMap<String, LogicalType>,
+    ) -> Result<QueryOutput, QueryError> {
+        let lo = match &self.windows {
+            Some((_, limit)) => limit,
+            None => self
+                .final_pass
+                .as_ref()
+                .map(|x| &x.limit)
+                .unwrap_or(&self.main_phase.limit),
+        };
         let limit = lo.limit as usize;
         let offset = lo.offset as usize;
         let count = cmp::min(limit, full_result.len() - offset);
         full_result.validate().unwrap();
+        let mut logical_types = self
+            .output_exprs
+            .iter()
+            .map(|expr| expr.logical_type(column_types))
+            .collect::<Vec<_>>();
+
+        let column_index = |proj: &ResultColumn| match proj {
+            ResultColumn::Proj(i) => full_result.projection[*i],
+            ResultColumn::Agg(i) => full_result.aggregations[*i].0,
+        };
+        // Values of the window function for all rows, by select column
+        let mut window_values = vec![None; self.output_colnames.len()];
+        for WindowColumns { window, partition_by, order_by } in self.windows.iter().flat_map(|(w, _)| w) {
+            let values = |columns: &[usize], i: usize| {
+                columns
+                    .iter()
+                    .map(|&c| full_result.columns[column_index(&self.result_column_sources[c])].get_raw(i))
+                    .collect::<Vec<_>>()
+            };
+            let args = (0..full_result.len())
+                .map(|i| values(&[window.column], i).pop().unwrap())
+                .collect::<Vec<_>>();
+            let partition_keys = (0..full_result.len()).map(|i| values(partition_by, i)).collect::<Vec<_>>();
+            let order_keys = (0..full_result.len()).map(|i| values(order_by, i)).collect::<Vec<_>>();
+            window_values[window.column] = Some(window::evaluate(window, &args, &partition_keys, &order_keys)?);
+            if !window.function.preserves_logical_type() {
+                logical_types[window.column] = LogicalType::Plain;
+            }
+        }
+        let selected_rows = self.downsample.as_ref().map(|(downsample, x, limit)| {
+            let xs = &full_result.columns[column_index(&self.result_column_sources[*x])];
+            let ys = &full_result.columns[column_index(&self.result_column_sources[downsample.column])];
+            let points = (0..full_result.len())
+                .filter_map(|i| Some((as_f64(xs.get_raw(i))?, as_f64(ys.get_raw(i))?, i)))
+                .collect();
+            downsample::downsample(downsample.method, points, downsample.max_points)
+                .into_iter()
+                .skip(limit.offset as usize)
+                .take(limit.limit as usize)
+                .collect::<Vec<_>>()
+        });
 
         let mut rows = None;
         if self.rowformat {
             let mut result_rows = Vec::new();
-            for i in offset..(count + offset) {
+            let row_indices = match &selected_rows {
+                Some(selected_rows) => selected_rows.clone(),
+                None => (offset..(count + offset)).collect(),
+            };
+            for i in row_indices {
                 let mut record = Vec::with_capacity(self.output_colnames.len());
-                for proj in &self.result_column_sources {
-                    let index = match proj {
-                        ResultColumn::Proj(i) => full_result.projection[*i],
-                        ResultColumn::Agg(i) => full_result.aggregations[*i].0,
+                for ((proj, &logical_type), window_values) in
+                    self.result_column_sources.iter().zip(&logical_types).zip(&window_values)
+                {
+                    let value = match window_values {
+                        Some(values) => values[i].clone(),
+                        None => full_result.columns[column_index(proj)].get_raw(i),
                     };
-                    record.push(full_result.columns[index].get_raw(i));
+                    record.push(value.with_logical_type(logical_type));
                 }
                 result_rows.push(record);
             }
@@ -411,17 +670,31 @@ impl QueryTask {
         }
 
         let mut columns = vec![];
-        for (colname, proj) in self.output_colnames.iter().zip(&self.result_column_sources) {
-            let index = match proj {
-                ResultColumn::Proj(i) => full_result.projection[*i],
-                ResultColumn::Agg(i) => full_result.aggregations[*i].0,
+        for (((colname, proj), &logical_type), window_values) in self
+            .output_colnames
+            .iter()
+            .zip(&self.result_column_sources)
+            .zip(&logical_types)
+            .zip(window_values)
+        {
+            let index = column_index(proj);
+            let column = match (window_values, &selected_rows) {
+                (Some(mut values), _) => {
+                    BasicTypeColumn::from_raw_vals(values.drain(offset..offset + count).collect())
+                }
+                (None, Some(selected_rows)) => {
+                    let column = full_result.columns[index].slice_box(0, full_result.len());
+                    BasicTypeColumn::from_boxed_data(column).select(selected_rows)
+                }
+                (None, None) => {
+                    let column = full_result.columns[index].slice_box(offset, offset + count);
+                    BasicTypeColumn::from_boxed_data(column)
+                }
             };
-            let column = full_result.columns[index].slice_box(offset, offset + count);
-            let column = BasicTypeColumn::from_boxed_data(column);
-            columns.push((colname.clone(), column));
+            columns.push((colname.clone(), column.with_logical_type(logical_type)));
         }
 
-        QueryOutput {
+        Ok(QueryOutput {
             colnames: self.output_colnames.clone(),
             rows,
             columns,
@@ -432,7 +705,7 @@ impl QueryTask {
                 files_opened: self.perf_counter.files_opened(),
                 disk_read_bytes: self.perf_counter.disk_read_bytes(),
             },
-        }
+        })
     }
 
     fn combined_limit(&self) -> usize {
@@ -501,6 +774,10 @@ impl BasicTypeColumn {
             | EncodingType::ScalarString
             | EncodingType::ConstVal
             | EncodingType::ValRows
+            | EncodingType::ValSets
+            | EncodingType::Sketches
+            | EncodingType::FloatLists
+            | EncodingType::WelfordStates
             | EncodingType::ByteSlices(_)
             | EncodingType::Premerge
             | EncodingType::MergeOp => {
@@ -509,6 +786,64 @@ impl BasicTypeColumn {
         }
     }
 
+    fn from_raw_vals(values: Vec<RawVal>) -> BasicTypeColumn {
+        let ints = values
+            .iter()
+            .map(|v| match *v {
+                RawVal::Int(i) => Some(i),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>();
+        let floats = values
+            .iter()
+            .map(|v| match *v {
+                RawVal::Float(f) => Some(f.0),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>();
+        if let Some(ints) = ints {
+            BasicTypeColumn::Int(ints)
+        } else if let Some(floats) = floats {
+            BasicTypeColumn::Float(floats)
+        } else if values.iter().all(|v| *v == RawVal::Null) {
+            BasicTypeColumn::Null(values.len())
+        } else {
+            BasicTypeColumn::Mixed(values)
+        }
+    }
+
+    fn select(self, rows: &[usize]) -> BasicTypeColumn {
+        fn select<T: Clone>(values: Vec<T>, rows: &[usize]) -> Vec<T> {
+            rows.iter().map(|&i| values[i].clone()).collect()
+        }
+        match self {
+            BasicTypeColumn::Int(v) => BasicTypeColumn::Int(select(v, rows)),
+            BasicTypeColumn::Float(v) => BasicTypeColumn::Float(select(v, rows)),
+            BasicTypeColumn::String(v) => BasicTypeColumn::String(select(v, rows)),
+            BasicTypeColumn::Null(_) => BasicTypeColumn::Null(rows.len()),
+            BasicTypeColumn::Mixed(v) => BasicTypeColumn::Mixed(select(v, rows)),
+            BasicTypeColumn::Bool(v) => BasicTypeColumn::Bool(select(v, rows)),
+            BasicTypeColumn::Timestamp(unit, v) => BasicTypeColumn::Timestamp(unit, select(v, rows)),
+        }
+    }
+
+    fn with_logical_type(self, logical_type: LogicalType) -> BasicTypeColumn {
+        match (self, logical_type) {
+            (BasicTypeColumn::Int(v), LogicalType::Bool) => {
+                BasicTypeColumn::Bool(v.into_iter().map(|i| i != 0).collect())
+            }
+            (BasicTypeColumn::Int(v), LogicalType::Timestamp(unit)) => {
+                BasicTypeColumn::Timestamp(unit, v)
+            }
+            (BasicTypeColumn::Mixed(v), logical_type) => BasicTypeColumn::Mixed(
+                v.into_iter()
+                    .map(|val| val.with_logical_type(logical_type))
+                    .collect(),
+            ),
+            (column, _) => column,
+        }
+    }
+
     pub fn len(&self) -> usize {
         match self {
             BasicTypeColumn::Int(v) => v.len(),
@@ -516,6 +851,8 @@ impl BasicTypeColumn {
             BasicTypeColumn::String(v) => v.len(),
             BasicTypeColumn::Null(v) => *v,
             BasicTypeColumn::Mixed(v) => v.len(),
+            BasicTypeColumn::Bool(v) => v.len(),
+            BasicTypeColumn::Timestamp(_, v) => v.len(),
         }
     }
 
@@ -534,7 +871,35 @@ impl PartialEq for BasicTypeColumn {
             (BasicTypeColumn::String(a), BasicTypeColumn::String(b)) => a == b,
             (BasicTypeColumn::Null(a), BasicTypeColumn::Null(b)) => a == b,
             (BasicTypeColumn::Mixed(a), BasicTypeColumn::Mixed(b)) => a == b,
+            (BasicTypeColumn::Bool(a), BasicTypeColumn::Bool(b)) => a == b,
+            (BasicTypeColumn::Timestamp(u, a), BasicTypeColumn::Timestamp(v, b)) => {
+                u == v && a == b
+            }
             _ => false,
         }
     }
 }
+
+fn as_f64(val: RawVal) -> Option<f64> {
+    match val {
+        RawVal::Int(i) | RawVal::Timestamp(i, _) => Some(i as f64),
+        RawVal::Float(f) => Some(f.0),
+        RawVal::Bool(b) => Some(b as u8 as f64),
+        RawVal::Str(_) | RawVal::Null => None,
+    }
+}
+
+fn merge_logical_type(
+    column_types: &mut HashMap<String, LogicalType>,
+    name: &str,
+    logical_type: LogicalType,
+) {
+    column_types
+        .entry(name.to_string())
+        .and_modify(|existing| {
+            if *existing != logical_type {
+                *existing = LogicalType::Plain
+            }
+        })
+        .or_insert(logical_type);
+}
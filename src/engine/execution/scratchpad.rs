@@ -85,6 +85,14 @@ impl<'a> Scratchpad<'a> {
         RefMut::map(self.get_any_mut(index.any()), |x| x.cast_ref_mut_val_rows())
     }
 
+    pub fn get_states<T: AggregationStates<'a>>(&self, index: BufferRef<T>) -> Ref<T> {
+        Ref::map(self.get_any(index.any()), |x| T::unwrap(x))
+    }
+
+    pub fn get_mut_states<T: AggregationStates<'a>>(&self, index: BufferRef<T>) -> RefMut<T> {
+        RefMut::map(self.get_any_mut(index.any()), |x| T::unwrap_mut(x))
+    }
+
     pub fn get_mut_nullable<T: VecData<T> + 'a>(
         &self,
         index: BufferRef<Nullable<T>>,
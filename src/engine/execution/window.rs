@@ -0,0 +1,308 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+use ordered_float::OrderedFloat;
+
+use crate::engine::planning::{FrameBound, Window, WindowFunction};
+use crate::errors::QueryError;
+use crate::ingest::raw_val::RawVal;
+
+/// Evaluates `window` given the function argument, partition keys and order keys of each row of the query result,
+/// and returns the value of the window function for each row.
+pub fn evaluate(
+    window: &Window,
+    args: &[RawVal],
+    partition_keys: &[Vec<RawVal>],
+    order_keys: &[Vec<RawVal>],
+) -> Result<Vec<RawVal>, QueryError> {
+    let desc = window.order_by.iter().map(|&(_, desc)| desc).collect::<Vec<_>>();
+    let compare_order = |a: usize, b: usize| {
+        order_keys[a]
+            .iter()
+            .zip(&order_keys[b])
+            .zip(&desc)
+            .map(|((a, b), &desc)| if desc { compare(b, a) } else { compare(a, b) })
+            .find(|&ordering| ordering != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    };
+    let compare_partition = |a: usize, b: usize| compare_keys(&partition_keys[a], &partition_keys[b]);
+
+    let mut rows = (0..args.len()).collect::<Vec<_>>();
+    rows.sort_by(|&a, &b| compare_partition(a, b).then_with(|| compare_order(a, b)));
+
+    let mut result = vec![RawVal::Null; args.len()];
+    let mut start = 0;
+    while start < rows.len() {
+        let end = start
+            + rows[start..]
+                .iter()
+                .position(|&row| compare_partition(rows[start], row) != Ordering::Equal)
+                .unwrap_or(rows.len() - start);
+        let partition = &rows[start..end];
+        let values = partition.iter().map(|&row| &args[row]).collect::<Vec<_>>();
+        let frames = frames(window, partition.len(), |a, b| {
+            compare_order(partition[a], partition[b]) == Ordering::Equal
+        });
+        for (value, &row) in evaluate_partition(&window.function, &values, &frames)?
+            .into_iter()
+            .zip(partition)
+        {
+            result[row] = value;
+        }
+        start = end;
+    }
+    Ok(result)
+}
+
+/// Returns the range of rows in the frame of each row of a partition with `len` rows.
+fn frames(window: &Window, len: usize, peers: impl Fn(usize, usize) -> bool) -> Vec<(usize, usize)> {
+    // First and last + 1 row with the same order keys as each row
+    let mut peer_ranges = Vec::with_capacity(len);
+    if window.frame.peers {
+        let mut start = 0;
+        while start < len {
+            let end = (start + 1..len).find(|&i| !peers(start, i)).unwrap_or(len);
+            peer_ranges.extend((start..end).map(|_| (start, end)));
+            start = end;
+        }
+    } else {
+        peer_ranges.extend((0..len).map(|i| (i, i + 1)));
+    }
+    (0..len)
+        .map(|i| {
+            let start = match window.frame.start {
+                FrameBound::UnboundedPreceding => 0,
+                FrameBound::Preceding(n) => i.saturating_sub(n),
+                FrameBound::CurrentRow => peer_ranges[i].0,
+                FrameBound::Following(n) => (i + n).min(len),
+                FrameBound::UnboundedFollowing => len,
+            };
+            let end = match window.frame.end {
+                FrameBound::UnboundedPreceding => 0,
+                FrameBound::Preceding(n) => (i + 1).saturating_sub(n),
+                FrameBound::CurrentRow => peer_ranges[i].1,
+                FrameBound::Following(n) => (i + n + 1).min(len),
+                FrameBound::UnboundedFollowing => len,
+            };
+            (start, end.max(start))
+        })
+        .collect()
+}
+
+/// Evaluates the window function over the ordered values of a single partition. Frame boundaries never decrease
+/// from one row to the next, which allows aggregates to be maintained incrementally.
+fn evaluate_partition(
+    function: &WindowFunction,
+    values: &[&RawVal],
+    frames: &[(usize, usize)],
+) -> Result<Vec<RawVal>, QueryError> {
+    let len = values.len();
+    Ok(match function {
+        WindowFunction::RowNumber => (1..=len as i64).map(RawVal::Int).collect(),
+        WindowFunction::Lag(offset, default) => (0..len)
+            .map(|i| match i.checked_sub(*offset) {
+                Some(j) => values[j].clone(),
+                None => default.clone(),
+            })
+            .collect(),
+        WindowFunction::Lead(offset, default) => (0..len)
+            .map(|i| match values.get(i + offset) {
+                Some(value) => (*value).clone(),
+                None => default.clone(),
+            })
+            .collect(),
+        WindowFunction::CountRows => frames.iter().map(|&(start, end)| RawVal::Int((end - start) as i64)).collect(),
+        WindowFunction::Sum | WindowFunction::Avg | WindowFunction::Count => {
+            let mut sum = Sum::default();
+            let (mut start, mut end) = (0, 0);
+            let mut result = Vec::with_capacity(len);
+            for &(frame_start, frame_end) in frames {
+                for value in &values[end..frame_end] {
+                    sum.add(value)?;
+                }
+                for value in &values[start..frame_start] {
+                    sum.remove(value)?;
+                }
+                (start, end) = (frame_start, frame_end);
+                result.push(match function {
+                    WindowFunction::Sum => sum.sum()?,
+                    WindowFunction::Avg => sum.avg(),
+                    _ => RawVal::Int(sum.count as i64),
+                });
+            }
+            result
+        }
+        WindowFunction::Min | WindowFunction::Max => {
+            let keep = if *function == WindowFunction::Min { Ordering::Less } else { Ordering::Greater };
+            // Rows in the frame that may be the minimum/maximum of this or a later frame, the first row is the current
+            // minimum/maximum
+            let mut candidates = VecDeque::<usize>::new();
+            let mut end = 0;
+            let mut result = Vec::with_capacity(len);
+            for &(frame_start, frame_end) in frames {
+                for i in end..frame_end {
+                    if *values[i] == RawVal::Null {
+                        continue;
+                    }
+                    while let Some(&last) = candidates.back() {
+                        if compare(values[i], values[last]) == keep {
+                            candidates.pop_back();
+                        } else {
+                            break;
+                        }
+                    }
+                    candidates.push_back(i);
+                }
+                end = frame_end;
+                while candidates.front().is_some_and(|&i| i < frame_start) {
+                    candidates.pop_front();
+                }
+                result.push(match candidates.front() {
+                    Some(&i) => values[i].clone(),
+                    None => RawVal::Null,
+                });
+            }
+            result
+        }
+    })
+}
+
+/// Sum and count of the non-null values in a window frame. Integers are summed exactly, unless the frame also
+/// contains floats.
+#[derive(Default)]
+struct Sum {
+    int: i128,
+    float: f64,
+    floats: usize,
+    count: usize,
+}
+
+impl Sum {
+    fn add(&mut self, value: &RawVal) -> Result<(), QueryError> {
+        match *value {
+            RawVal::Int(i) => self.int += i as i128,
+            RawVal::Float(f) => {
+                self.float += f.0;
+                self.floats += 1;
+            }
+            RawVal::Null => return Ok(()),
+            _ => bail!(QueryError::TypeError, "Cannot sum non-numeric value {}", value),
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    fn remove(&mut self, value: &RawVal) -> Result<(), QueryError> {
+        match *value {
+            RawVal::Int(i) => self.int -= i as i128,
+            RawVal::Float(f) => {
+                self.float -= f.0;
+                self.floats -= 1;
+            }
+            RawVal::Null => return Ok(()),
+            _ => bail!(QueryError::TypeError, "Cannot sum non-numeric value {}", value),
+        }
+        self.count -= 1;
+        Ok(())
+    }
+
+    fn sum(&self) -> Result<RawVal, QueryError> {
+        Ok(if self.count == 0 {
+            RawVal::Null
+        } else if self.floats > 0 {
+            RawVal::Float(OrderedFloat(self.float + self.int as f64))
+        } else {
+            RawVal::Int(i64::try_from(self.int).map_err(|_| QueryError::Overflow)?)
+        })
+    }
+
+    // Consistent with AVG aggregates, the average of integers is rounded towards zero
+    fn avg(&self) -> RawVal {
+        if self.count == 0 {
+            RawVal::Null
+        } else if self.floats > 0 {
+            RawVal::Float(OrderedFloat((self.float + self.int as f64) / self.count as f64))
+        } else {
+            RawVal::Int((self.int / self.count as i128) as i64)
+        }
+    }
+}
+
+fn compare_keys(a: &[RawVal], b: &[RawVal]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| compare(a, b))
+        .find(|&ordering| ordering != Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Compares integers and floats by their numeric value, and orders nulls last.
+fn compare(a: &RawVal, b: &RawVal) -> Ordering {
+    match (a, b) {
+        (RawVal::Int(a), RawVal::Float(b)) => OrderedFloat(*a as f64).cmp(b),
+        (RawVal::Float(a), RawVal::Int(b)) => a.cmp(&OrderedFloat(*b as f64)),
+        _ => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::planning::WindowFrame;
+    use crate::syntax::expression::Expr;
+
+    fn window(function: WindowFunction, frame: WindowFrame) -> Window {
+        Window {
+            function,
+            column: 0,
+            partition_by: vec![],
+            order_by: vec![(Expr::ColName("x".to_string()), false)],
+            frame,
+        }
+    }
+
+    fn ints(values: &[i64]) -> Vec<RawVal> {
+        values.iter().map(|&i| RawVal::Int(i)).collect()
+    }
+
+    #[test]
+    fn test_moving_sum() {
+        let frame = WindowFrame {
+            peers: false,
+            start: FrameBound::Preceding(1),
+            end: FrameBound::CurrentRow,
+        };
+        let result = evaluate(
+            &window(WindowFunction::Sum, frame),
+            &[RawVal::Int(30), RawVal::Null, RawVal::Int(20), RawVal::Int(40)],
+            &[vec![], vec![], vec![], vec![]],
+            &[ints(&[3]), ints(&[1]), ints(&[2]), ints(&[4])],
+        )
+        .unwrap();
+        assert_eq!(result, vec![RawVal::Int(50), RawVal::Null, RawVal::Int(20), RawVal::Int(70)]);
+    }
+
+    #[test]
+    fn test_running_max_with_peers() {
+        let result = evaluate(
+            &window(WindowFunction::Max, WindowFrame::implicit(true)),
+            &ints(&[5, 7, 3, 1, 9]),
+            &[ints(&[0]), ints(&[0]), ints(&[0]), ints(&[1]), ints(&[1])],
+            &[ints(&[1]), ints(&[2]), ints(&[2]), ints(&[1]), ints(&[2])],
+        )
+        .unwrap();
+        assert_eq!(result, ints(&[5, 7, 7, 1, 9]));
+    }
+
+    #[test]
+    fn test_lag() {
+        let result = evaluate(
+            &window(WindowFunction::Lag(2, RawVal::Int(-1)), WindowFrame::implicit(true)),
+            &ints(&[10, 20, 30, 40]),
+            &[vec![], vec![], vec![], vec![]],
+            &[ints(&[4]), ints(&[3]), ints(&[2]), ints(&[1])],
+        )
+        .unwrap();
+        assert_eq!(result, ints(&[30, 40, -1, -1]));
+    }
+}
@@ -0,0 +1,64 @@
+use crate::engine::*;
+use crate::mem_store::value::Val;
+
+#[derive(Debug)]
+pub struct AggregateSketch<'a, U> {
+    pub input: BufferRef<Val<'a>>,
+    pub grouping: BufferRef<U>,
+    pub output: BufferRef<Sketches>,
+    pub max_index: BufferRef<Scalar<i64>>,
+    pub empty: Sketch,
+}
+
+impl<'a, U: GenericIntVec<U>> VecOperator<'a> for AggregateSketch<'a, U> {
+    fn execute(&mut self, _: bool, scratchpad: &mut Scratchpad<'a>) -> Result<(), QueryError> {
+        let vals = scratchpad.get(self.input);
+        let grouping = scratchpad.get(self.grouping);
+        let mut sketches = scratchpad.get_mut_states(self.output);
+
+        let len = scratchpad.get_scalar(&self.max_index) as usize + 1;
+        if len > sketches.data.len() {
+            sketches.data.resize(len, self.empty.clone());
+        }
+
+        for (i, val) in grouping.iter().zip(vals.iter()) {
+            sketches.data[i.cast_usize()].insert(val);
+        }
+
+        Ok(())
+    }
+
+    fn init(&mut self, _: usize, _: usize, scratchpad: &mut Scratchpad<'a>) {
+        scratchpad.set_any(self.output.any(), Box::new(Sketches::default()));
+    }
+
+    fn inputs(&self) -> Vec<BufferRef<Any>> {
+        vec![self.grouping.any(), self.input.any(), self.max_index.any()]
+    }
+    fn inputs_mut(&mut self) -> Vec<&mut usize> {
+        vec![
+            &mut self.grouping.i,
+            &mut self.input.i,
+            &mut self.max_index.i,
+        ]
+    }
+    fn outputs(&self) -> Vec<BufferRef<Any>> {
+        vec![self.output.any()]
+    }
+    fn can_stream_input(&self, _: usize) -> bool {
+        true
+    }
+    fn can_stream_output(&self, _: usize) -> bool {
+        false
+    }
+    fn allocates(&self) -> bool {
+        true
+    }
+
+    fn display_op(&self, _: bool) -> String {
+        format!("{}[{}].insert({})", self.output, self.grouping, self.input)
+    }
+    fn display_output(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,69 @@
+use crate::engine::*;
+use crate::mem_store::value::Val;
+
+#[derive(Debug)]
+pub struct AggregateWelford<'a, U> {
+    pub x: BufferRef<Val<'a>>,
+    pub y: BufferRef<Val<'a>>,
+    pub grouping: BufferRef<U>,
+    pub output: BufferRef<WelfordStates>,
+    pub max_index: BufferRef<Scalar<i64>>,
+}
+
+impl<'a, U: GenericIntVec<U>> VecOperator<'a> for AggregateWelford<'a, U> {
+    fn execute(&mut self, _: bool, scratchpad: &mut Scratchpad<'a>) -> Result<(), QueryError> {
+        let xs = scratchpad.get(self.x);
+        let ys = scratchpad.get(self.y);
+        let grouping = scratchpad.get(self.grouping);
+        let mut states = scratchpad.get_mut_states(self.output);
+
+        let len = scratchpad.get_scalar(&self.max_index) as usize + 1;
+        if len > states.data.len() {
+            states.data.resize(len, Welford::default());
+        }
+
+        for ((i, x), y) in grouping.iter().zip(xs.iter()).zip(ys.iter()) {
+            // Only pairs where both values are present contribute
+            if let (Some(x), Some(y)) = (x.to_f64(), y.to_f64()) {
+                states.data[i.cast_usize()].insert(x, y);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn init(&mut self, _: usize, _: usize, scratchpad: &mut Scratchpad<'a>) {
+        scratchpad.set_any(self.output.any(), Box::new(WelfordStates::default()));
+    }
+
+    fn inputs(&self) -> Vec<BufferRef<Any>> {
+        vec![self.grouping.any(), self.x.any(), self.y.any(), self.max_index.any()]
+    }
+    fn inputs_mut(&mut self) -> Vec<&mut usize> {
+        vec![
+            &mut self.grouping.i,
+            &mut self.x.i,
+            &mut self.y.i,
+            &mut self.max_index.i,
+        ]
+    }
+    fn outputs(&self) -> Vec<BufferRef<Any>> {
+        vec![self.output.any()]
+    }
+    fn can_stream_input(&self, _: usize) -> bool {
+        true
+    }
+    fn can_stream_output(&self, _: usize) -> bool {
+        false
+    }
+    fn allocates(&self) -> bool {
+        true
+    }
+
+    fn display_op(&self, _: bool) -> String {
+        format!("{}[{}].insert({}, {})", self.output, self.grouping, self.x, self.y)
+    }
+    fn display_output(&self) -> bool {
+        false
+    }
+}
@@ -1,11 +1,56 @@
+use ordered_float::OrderedFloat;
+
 // TODO: would probably be better to have two types here, an UntypedAggregator emitted by parser which is then converted into the right TypedAggregator by query planner
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Aggregator {
-    SumI64 = 0,
-    SumF64 = 1,
-    Count = 2,
-    MaxI64 = 3,
-    MaxF64 = 4,
-    MinI64 = 5,
-    MinF64 = 6,
-}
\ No newline at end of file
+    SumI64,
+    SumF64,
+    Count,
+    MaxI64,
+    MaxF64,
+    MinI64,
+    MinF64,
+    CountDistinct,
+    ApproxCountDistinct,
+    // Quantile in [0, 1]
+    ApproxQuantile(OrderedFloat<f64>),
+    // Percentile in [0, 1]
+    PercentileCont(OrderedFloat<f64>),
+    Variance,
+    VariancePop,
+    StdDev,
+    StdDevPop,
+    Corr,
+}
+
+impl Aggregator {
+    /// Unique byte representation of the aggregator, used to identify common subexpressions in query plans.
+    pub fn signature(self) -> [u8; 9] {
+        let mut signature = [0; 9];
+        signature[0] = match self {
+            Aggregator::SumI64 => 0,
+            Aggregator::SumF64 => 1,
+            Aggregator::Count => 2,
+            Aggregator::MaxI64 => 3,
+            Aggregator::MaxF64 => 4,
+            Aggregator::MinI64 => 5,
+            Aggregator::MinF64 => 6,
+            Aggregator::CountDistinct => 7,
+            Aggregator::ApproxCountDistinct => 8,
+            Aggregator::ApproxQuantile(q) => {
+                signature[1..].copy_from_slice(&q.0.to_ne_bytes());
+                9
+            }
+            Aggregator::PercentileCont(p) => {
+                signature[1..].copy_from_slice(&p.0.to_ne_bytes());
+                10
+            }
+            Aggregator::Variance => 11,
+            Aggregator::VariancePop => 12,
+            Aggregator::StdDev => 13,
+            Aggregator::StdDevPop => 14,
+            Aggregator::Corr => 15,
+        };
+        signature
+    }
+}
@@ -0,0 +1,117 @@
+use crate::bitvec::*;
+use crate::engine::*;
+
+/// Selects the elements of `lhs` where `cond` is nonzero and the elements of `rhs` otherwise.
+#[derive(Debug)]
+pub struct Blend<T> {
+    pub cond: BufferRef<u8>,
+    pub lhs: BufferRef<T>,
+    pub rhs: BufferRef<T>,
+    pub output: BufferRef<T>,
+}
+
+impl<'a, T: VecData<T> + 'a> VecOperator<'a> for Blend<T> {
+    fn execute(&mut self, stream: bool, scratchpad: &mut Scratchpad<'a>) -> Result<(), QueryError> {
+        let cond = scratchpad.get(self.cond);
+        let lhs = scratchpad.get(self.lhs);
+        let rhs = scratchpad.get(self.rhs);
+        let mut output = scratchpad.get_mut(self.output);
+        if stream { output.clear(); }
+        for ((&c, &l), &r) in cond.iter().zip(lhs.iter()).zip(rhs.iter()) {
+            output.push(if c != 0 { l } else { r });
+        }
+        Ok(())
+    }
+
+    fn init(&mut self, _: usize, batch_size: usize, scratchpad: &mut Scratchpad<'a>) {
+        scratchpad.set(self.output, Vec::with_capacity(batch_size));
+    }
+
+    fn inputs(&self) -> Vec<BufferRef<Any>> { vec![self.cond.any(), self.lhs.any(), self.rhs.any()] }
+    fn inputs_mut(&mut self) -> Vec<&mut usize> { vec![&mut self.cond.i, &mut self.lhs.i, &mut self.rhs.i] }
+    fn outputs(&self) -> Vec<BufferRef<Any>> { vec![self.output.any()] }
+    fn can_stream_input(&self, _: usize) -> bool { true }
+    fn can_stream_output(&self, _: usize) -> bool { true }
+    fn can_block_output(&self) -> bool { true }
+    fn allocates(&self) -> bool { true }
+
+    fn display_op(&self, _: bool) -> String {
+        format!("if {} then {} else {}", self.cond, self.lhs, self.rhs)
+    }
+}
+
+/// Selects the null map entries of `lhs` where `cond` is nonzero and the null map entries of `rhs` otherwise.
+#[derive(Debug)]
+pub struct BlendNullMaps {
+    pub cond: BufferRef<u8>,
+    pub lhs: BufferRef<Nullable<Any>>,
+    pub rhs: BufferRef<Nullable<Any>>,
+    pub present: BufferRef<u8>,
+}
+
+impl<'a> VecOperator<'a> for BlendNullMaps {
+    fn execute(&mut self, stream: bool, scratchpad: &mut Scratchpad<'a>) -> Result<(), QueryError> {
+        let cond = scratchpad.get(self.cond);
+        let lhs = scratchpad.get_null_map(self.lhs);
+        let rhs = scratchpad.get_null_map(self.rhs);
+        let mut present = scratchpad.get_mut(self.present);
+        if stream { present.clear(); }
+        present.resize(cond.len().div_ceil(8), 0);
+        for (i, &c) in cond.iter().enumerate() {
+            if (c != 0 && lhs.is_set(i)) || (c == 0 && rhs.is_set(i)) {
+                present.set(i);
+            }
+        }
+        Ok(())
+    }
+
+    fn init(&mut self, _: usize, batch_size: usize, scratchpad: &mut Scratchpad<'a>) {
+        scratchpad.set(self.present, Vec::with_capacity(batch_size.div_ceil(8)));
+    }
+
+    fn inputs(&self) -> Vec<BufferRef<Any>> { vec![self.cond.any(), self.lhs.any(), self.rhs.any()] }
+    fn inputs_mut(&mut self) -> Vec<&mut usize> { vec![&mut self.cond.i, &mut self.lhs.i, &mut self.rhs.i] }
+    fn outputs(&self) -> Vec<BufferRef<Any>> { vec![self.present.any()] }
+    fn can_stream_input(&self, _: usize) -> bool { true }
+    fn can_stream_output(&self, _: usize) -> bool { true }
+    fn allocates(&self) -> bool { true }
+
+    fn display_op(&self, _: bool) -> String {
+        format!("blend_null_maps({}, {}, {})", self.cond, self.lhs, self.rhs)
+    }
+}
+
+/// Outputs a vector with the same length as `like` with all values equal to `scalar`.
+#[derive(Debug)]
+pub struct ExpandScalar<T> {
+    pub scalar: BufferRef<Scalar<T>>,
+    pub like: BufferRef<Any>,
+    pub expanded: BufferRef<T>,
+}
+
+impl<'a, T: VecData<T> + ScalarData<T> + 'a> VecOperator<'a> for ExpandScalar<T> {
+    fn execute(&mut self, stream: bool, scratchpad: &mut Scratchpad<'a>) -> Result<(), QueryError> {
+        let len = scratchpad.get_any(self.like).len();
+        let value = scratchpad.get_scalar(&self.scalar);
+        let mut expanded = scratchpad.get_mut(self.expanded);
+        if stream { expanded.clear(); }
+        expanded.extend(std::iter::repeat_n(value, len));
+        Ok(())
+    }
+
+    fn init(&mut self, _: usize, batch_size: usize, scratchpad: &mut Scratchpad<'a>) {
+        scratchpad.set(self.expanded, Vec::with_capacity(batch_size));
+    }
+
+    fn inputs(&self) -> Vec<BufferRef<Any>> { vec![self.scalar.any(), self.like] }
+    fn inputs_mut(&mut self) -> Vec<&mut usize> { vec![&mut self.scalar.i, &mut self.like.i] }
+    fn outputs(&self) -> Vec<BufferRef<Any>> { vec![self.expanded.any()] }
+    fn can_stream_input(&self, _: usize) -> bool { true }
+    fn can_stream_output(&self, _: usize) -> bool { true }
+    fn can_block_output(&self) -> bool { true }
+    fn allocates(&self) -> bool { true }
+
+    fn display_op(&self, _: bool) -> String {
+        format!("expand({}, len({}))", self.scalar, self.like)
+    }
+}
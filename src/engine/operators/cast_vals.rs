@@ -0,0 +1,155 @@
+use crate::bitvec::*;
+use crate::engine::*;
+use crate::mem_store::Val;
+
+use super::functions::CastType;
+use super::string_map::encode_offset_len;
+
+/// Converts `vals` to integers or floats.
+/// Values that cannot be converted are null, or an error if `strict` is set.
+pub struct ParseVals<'a, T> {
+    pub vals: BufferRef<Val<'a>>,
+    pub strict: bool,
+    pub parsed: BufferRef<Nullable<T>>,
+}
+
+pub trait ParseVal: Sized + Default {
+    const CAST_TYPE: CastType;
+    fn parse_val(val: Val) -> Option<Self>;
+}
+
+impl ParseVal for i64 {
+    const CAST_TYPE: CastType = CastType::Integer;
+    fn parse_val(val: Val) -> Option<i64> { CastType::to_i64(val) }
+}
+
+impl ParseVal for of64 {
+    const CAST_TYPE: CastType = CastType::Float;
+    fn parse_val(val: Val) -> Option<of64> { CastType::to_f64(val).map(of64::from) }
+}
+
+impl<'a, T: VecData<T> + ParseVal + 'a> VecOperator<'a> for ParseVals<'a, T> {
+    fn execute(&mut self, stream: bool, scratchpad: &mut Scratchpad<'a>) -> Result<(), QueryError> {
+        let vals = scratchpad.get(self.vals);
+        let (mut data, mut present) = scratchpad.get_mut_nullable(self.parsed);
+        if stream {
+            data.clear();
+            present.clear();
+        }
+        present.resize((data.len() + vals.len()).div_ceil(8), 0u8);
+        let offset = data.len();
+        for (i, &val) in vals.iter().enumerate() {
+            match T::parse_val(val) {
+                Some(x) => {
+                    data.push(x);
+                    present.set(offset + i);
+                }
+                None if self.strict && val != Val::Null => {
+                    bail!(QueryError::TypeError, "Cannot cast {} to {:?}", val, T::CAST_TYPE)
+                }
+                None => data.push(T::default()),
+            }
+        }
+        Ok(())
+    }
+
+    fn init(&mut self, _: usize, batch_size: usize, scratchpad: &mut Scratchpad<'a>) {
+        scratchpad.set_nullable(self.parsed, Vec::with_capacity(batch_size), vec![]);
+    }
+
+    fn inputs(&self) -> Vec<BufferRef<Any>> { vec![self.vals.any()] }
+    fn inputs_mut(&mut self) -> Vec<&mut usize> { vec![&mut self.vals.i] }
+    fn outputs(&self) -> Vec<BufferRef<Any>> { vec![self.parsed.any()] }
+    fn can_stream_input(&self, _: usize) -> bool { true }
+    fn can_stream_output(&self, _: usize) -> bool { true }
+    fn can_block_output(&self) -> bool { true }
+    fn allocates(&self) -> bool { true }
+
+    fn display_op(&self, _: bool) -> String {
+        format!("cast({} as {:?})", self.vals, T::CAST_TYPE)
+    }
+}
+
+/// Formats `vals` as strings and stores them in the same format as string dictionaries,
+/// so they can be resolved with `DictLookup`. Nulls are formatted as empty strings.
+#[derive(Debug)]
+pub struct FormatVals<'a> {
+    pub vals: BufferRef<Val<'a>>,
+    pub offset_len: BufferRef<u64>,
+    pub backing_store: BufferRef<u8>,
+}
+
+impl<'a> VecOperator<'a> for FormatVals<'a> {
+    fn execute(&mut self, _: bool, scratchpad: &mut Scratchpad<'a>) -> Result<(), QueryError> {
+        let (offset_len, backing_store) = {
+            let vals = scratchpad.get(self.vals);
+            let mut offset_len = Vec::with_capacity(vals.len());
+            let mut backing_store = String::new();
+            for &val in vals.iter() {
+                let offset = backing_store.len();
+                CastType::format(val, &mut backing_store);
+                offset_len.push(encode_offset_len(offset, backing_store.len() - offset)?);
+            }
+            (offset_len, backing_store)
+        };
+        scratchpad.set(self.offset_len, offset_len);
+        scratchpad.set(self.backing_store, backing_store.into_bytes());
+        Ok(())
+    }
+
+    fn inputs(&self) -> Vec<BufferRef<Any>> { vec![self.vals.any()] }
+    fn inputs_mut(&mut self) -> Vec<&mut usize> { vec![&mut self.vals.i] }
+    fn outputs(&self) -> Vec<BufferRef<Any>> { vec![self.offset_len.any(), self.backing_store.any()] }
+    fn can_stream_input(&self, _: usize) -> bool { false }
+    fn can_stream_output(&self, _: usize) -> bool { false }
+    fn allocates(&self) -> bool { true }
+
+    fn display_op(&self, _: bool) -> String {
+        format!("cast({} as String)", self.vals)
+    }
+}
+
+/// Combines `strings` with the null map of `vals`, where `strings` were formatted from `vals`.
+#[derive(Debug)]
+pub struct NullableFormattedVals<'a> {
+    pub vals: BufferRef<Val<'a>>,
+    pub strings: BufferRef<&'a str>,
+    pub nullable: BufferRef<Nullable<&'a str>>,
+}
+
+impl<'a> VecOperator<'a> for NullableFormattedVals<'a> {
+    fn execute(&mut self, stream: bool, scratchpad: &mut Scratchpad<'a>) -> Result<(), QueryError> {
+        let vals = scratchpad.get(self.vals);
+        let strings = scratchpad.get(self.strings);
+        let (mut data, mut present) = scratchpad.get_mut_nullable(self.nullable);
+        if stream {
+            data.clear();
+            present.clear();
+        }
+        present.resize((data.len() + vals.len()).div_ceil(8), 0u8);
+        let offset = data.len();
+        for (i, (&val, &string)) in vals.iter().zip(strings.iter()).enumerate() {
+            data.push(string);
+            if val != Val::Null {
+                present.set(offset + i);
+            }
+        }
+        Ok(())
+    }
+
+    fn init(&mut self, _: usize, batch_size: usize, scratchpad: &mut Scratchpad<'a>) {
+        scratchpad.set_nullable(self.nullable, Vec::with_capacity(batch_size), vec![]);
+    }
+
+    fn inputs(&self) -> Vec<BufferRef<Any>> { vec![self.vals.any(), self.strings.any()] }
+    fn inputs_mut(&mut self) -> Vec<&mut usize> { vec![&mut self.vals.i, &mut self.strings.i] }
+    fn outputs(&self) -> Vec<BufferRef<Any>> { vec![self.nullable.any()] }
+    fn can_stream_input(&self, _: usize) -> bool { true }
+    fn can_stream_output(&self, _: usize) -> bool { true }
+    fn can_block_output(&self) -> bool { true }
+    fn allocates(&self) -> bool { true }
+
+    fn display_op(&self, _: bool) -> String {
+        format!("nullable({}, null_map({}))", self.strings, self.vals)
+    }
+}
@@ -1,4 +1,5 @@
 use crate::engine::*;
+use std::collections::HashSet;
 use std::str;
 
 #[derive(Debug)]
@@ -45,6 +46,56 @@ impl<'a, T: GenericIntVec<T>> VecOperator<'a> for DictLookup<'a, T> {
     }
 }
 
+/// Determines whether the strings referenced by dictionary `indices` are contained in `set`.
+/// Membership is only checked once for each dictionary entry.
+#[derive(Debug)]
+pub struct DictIsIn<T> {
+    pub indices: BufferRef<T>,
+    pub dict_indices: BufferRef<u64>,
+    pub dict_data: BufferRef<u8>,
+    pub set: HashSet<String>,
+    pub is_member: Vec<u8>,
+    pub output: BufferRef<u8>,
+}
+
+impl<'a, T: GenericIntVec<T>> VecOperator<'a> for DictIsIn<T> {
+    fn execute(&mut self, stream: bool, scratchpad: &mut Scratchpad<'a>) -> Result<(), QueryError> {
+        if self.is_member.is_empty() {
+            let dict_indices = scratchpad.get(self.dict_indices);
+            let dict_data = scratchpad.get(self.dict_data);
+            self.is_member = dict_indices.iter().map(|offset_len| {
+                let offset = (offset_len >> 24) as usize;
+                let len = (offset_len & 0x00ff_ffff) as usize;
+                let string = unsafe { str::from_utf8_unchecked(&dict_data[offset..(offset + len)]) };
+                self.set.contains(string) as u8
+            }).collect();
+        }
+        let indices = scratchpad.get(self.indices);
+        let mut output = scratchpad.get_mut(self.output);
+        if stream { output.clear(); }
+        for i in indices.iter() {
+            output.push(self.is_member[i.cast_usize()]);
+        }
+        Ok(())
+    }
+
+    fn init(&mut self, _: usize, batch_size: usize, scratchpad: &mut Scratchpad<'a>) {
+        scratchpad.set(self.output, Vec::with_capacity(batch_size));
+    }
+
+    fn inputs(&self) -> Vec<BufferRef<Any>> { vec![self.indices.any(), self.dict_indices.any(), self.dict_data.any()] }
+    fn inputs_mut(&mut self) -> Vec<&mut usize> { vec![&mut self.indices.i, &mut self.dict_indices.i, &mut self.dict_data.i] }
+    fn outputs(&self) -> Vec<BufferRef<Any>> { vec![self.output.any()] }
+    fn can_stream_input(&self, buffer: usize) -> bool { buffer == self.indices.i }
+    fn can_stream_output(&self, _: usize) -> bool { true }
+    fn can_block_output(&self) -> bool { true }
+    fn allocates(&self) -> bool { true }
+
+    fn display_op(&self, _: bool) -> String {
+        format!("{}[{}[{}]] IN {:?}", self.dict_data, self.dict_indices, self.indices, self.set)
+    }
+}
+
 #[derive(Debug)]
 pub struct InverseDictLookup<'a> {
     pub dict_indices: BufferRef<u64>,
@@ -1,17 +1,138 @@
-use chrono::{DateTime, Datelike};
+use std::collections::HashSet;
+
+use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc};
+use ordered_float::OrderedFloat;
 
 use crate::engine::of64;
+use crate::mem_store::Val;
 
 use super::map_operator::MapOp;
 
 
-pub struct ToYear;
+/// Granularity that `date_trunc` rounds timestamps down to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TimeGranularity {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    // Weeks start on Monday
+    Week,
+    Month,
+    Quarter,
+    Year,
+}
+
+impl TimeGranularity {
+    pub fn parse(granularity: &str) -> Option<TimeGranularity> {
+        match granularity.to_lowercase().as_str() {
+            "second" => Some(TimeGranularity::Second),
+            "minute" => Some(TimeGranularity::Minute),
+            "hour" => Some(TimeGranularity::Hour),
+            "day" => Some(TimeGranularity::Day),
+            "week" => Some(TimeGranularity::Week),
+            "month" => Some(TimeGranularity::Month),
+            "quarter" => Some(TimeGranularity::Quarter),
+            "year" => Some(TimeGranularity::Year),
+            _ => None,
+        }
+    }
+
+    /// Rounds unix timestamp in seconds down to the start of the enclosing interval.
+    pub fn truncate(self, unix_ts: i64) -> i64 {
+        const DAY: i64 = 24 * 60 * 60;
+        match self {
+            TimeGranularity::Second => unix_ts,
+            TimeGranularity::Minute => unix_ts - unix_ts.rem_euclid(60),
+            TimeGranularity::Hour => unix_ts - unix_ts.rem_euclid(60 * 60),
+            TimeGranularity::Day => unix_ts - unix_ts.rem_euclid(DAY),
+            TimeGranularity::Week => {
+                // 1970-01-01 was a Thursday, which is 3 days after the start of the week
+                let days = unix_ts.div_euclid(DAY);
+                (days - (days + 3).rem_euclid(7)) * DAY
+            }
+            TimeGranularity::Month | TimeGranularity::Quarter | TimeGranularity::Year => {
+                let datetime = datetime(unix_ts, 1);
+                let month0 = match self {
+                    TimeGranularity::Month => datetime.month0(),
+                    TimeGranularity::Quarter => datetime.month0() / 3 * 3,
+                    _ => 0,
+                };
+                NaiveDate::from_ymd_opt(datetime.year(), month0 + 1, 1)
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+                    .map_or(unix_ts, |start| start.and_utc().timestamp())
+            }
+        }
+    }
+}
+
+// Timestamps outside of the range supported by chrono are mapped to the unix epoch
+fn datetime(ts: i64, per_second: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(ts.div_euclid(per_second), 0).unwrap_or_default()
+}
+
+pub struct ToYear {
+    pub per_second: i64,
+}
 
 impl MapOp<i64, i64> for ToYear {
-    fn apply(&self, unix_ts: i64) -> i64 { i64::from(DateTime::from_timestamp(unix_ts, 0).unwrap().year()) }
+    fn apply(&self, ts: i64) -> i64 { i64::from(datetime(ts, self.per_second).year()) }
     fn name() -> &'static str { "to_year" }
 }
 
+pub struct ToMonth {
+    pub per_second: i64,
+}
+
+impl MapOp<i64, i64> for ToMonth {
+    fn apply(&self, ts: i64) -> i64 { i64::from(datetime(ts, self.per_second).month()) }
+    fn name() -> &'static str { "to_month" }
+}
+
+/// ISO 8601 day of the week, 1 for Monday through 7 for Sunday.
+pub struct ToDayOfWeek {
+    pub per_second: i64,
+}
+
+impl MapOp<i64, i64> for ToDayOfWeek {
+    fn apply(&self, ts: i64) -> i64 {
+        i64::from(datetime(ts, self.per_second).weekday().number_from_monday())
+    }
+    fn name() -> &'static str { "to_day_of_week" }
+}
+
+pub struct ToHour {
+    pub per_second: i64,
+}
+
+impl MapOp<i64, i64> for ToHour {
+    fn apply(&self, ts: i64) -> i64 { i64::from(datetime(ts, self.per_second).hour()) }
+    fn name() -> &'static str { "to_hour" }
+}
+
+pub struct DateTrunc {
+    pub granularity: TimeGranularity,
+    pub per_second: i64,
+}
+
+impl MapOp<i64, i64> for DateTrunc {
+    fn apply(&self, ts: i64) -> i64 {
+        let unix_ts = ts.div_euclid(self.per_second);
+        self.granularity.truncate(unix_ts).saturating_mul(self.per_second)
+    }
+    fn name() -> &'static str { "date_trunc" }
+}
+
+/// Rounds timestamps down to a multiple of `width`.
+pub struct TimeBucket {
+    pub width: i64,
+}
+
+impl MapOp<i64, i64> for TimeBucket {
+    fn apply(&self, ts: i64) -> i64 { ts - ts.rem_euclid(self.width) }
+    fn name() -> &'static str { "time_bucket" }
+}
+
 pub struct Floor;
 
 impl MapOp<of64, i64> for Floor {
@@ -19,6 +140,163 @@ impl MapOp<of64, i64> for Floor {
     fn name() -> &'static str { "floor" }
 }
 
+/// Target type of `CAST` expressions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CastType {
+    Integer,
+    Float,
+    String,
+}
+
+impl CastType {
+    /// Converts `val` to an integer, returns `None` for nulls and values that cannot be converted.
+    /// Floats are truncated towards zero and strings must contain a valid integer.
+    pub fn to_i64(val: Val) -> Option<i64> {
+        match val {
+            Val::Integer(i) => Some(i),
+            Val::Bool(b) => Some(b as i64),
+            Val::Float(f) if f.is_finite() && f.0 >= i64::MIN as f64 && f.0 < i64::MAX as f64 => Some(f.0 as i64),
+            Val::Str(s) => s.trim().parse::<i64>().ok(),
+            Val::Float(_) | Val::Null => None,
+        }
+    }
+
+    /// Converts `val` to a float, returns `None` for nulls and strings that do not contain a valid number.
+    pub fn to_f64(val: Val) -> Option<f64> {
+        match val {
+            Val::Str(s) => s.trim().parse::<f64>().ok(),
+            _ => val.to_f64(),
+        }
+    }
+
+    /// Appends the string representation of `val` to `out`, returns `false` for nulls.
+    pub fn format(val: Val, out: &mut String) -> bool {
+        use std::fmt::Write;
+        match val {
+            Val::Null => return false,
+            Val::Str(s) => out.push_str(s),
+            Val::Integer(i) => write!(out, "{}", i).unwrap(),
+            Val::Float(f) => write!(out, "{}", f.0).unwrap(),
+            Val::Bool(b) => write!(out, "{}", b).unwrap(),
+        }
+        true
+    }
+}
+
+/// Numeric function evaluated by the `Math` operator.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MathFunction {
+    Abs,
+    Sign,
+    Ceil,
+    // Number of decimal places, negative values round to powers of ten
+    Round(i64),
+    Sqrt,
+    Ln,
+    Log10,
+    Exp,
+}
+
+impl MathFunction {
+    /// Unique byte representation of the function, used to identify common subexpressions in query plans.
+    pub fn signature(&self) -> Vec<u8> {
+        format!("{:?}", self).into_bytes()
+    }
+
+    /// Whether the function is only defined on floats and integer arguments have to be converted first.
+    pub fn is_float_only(self) -> bool {
+        matches!(self, MathFunction::Sqrt | MathFunction::Ln | MathFunction::Log10 | MathFunction::Exp)
+    }
+
+    /// Whether the result for float arguments is always integral and returned as integer (like `floor`).
+    pub fn is_integral(self) -> bool {
+        match self {
+            MathFunction::Ceil => true,
+            MathFunction::Round(digits) => digits <= 0,
+            _ => false,
+        }
+    }
+
+    pub fn apply_f64(self, f: f64) -> f64 {
+        match self {
+            MathFunction::Abs => f.abs(),
+            MathFunction::Sign => {
+                if f > 0.0 {
+                    1.0
+                } else if f < 0.0 {
+                    -1.0
+                } else {
+                    f
+                }
+            }
+            MathFunction::Ceil => f.ceil(),
+            MathFunction::Round(0) => f.round(),
+            MathFunction::Round(digits) => {
+                let scale = 10f64.powi(digits.clamp(-308, 308) as i32);
+                let rounded = (f * scale).round() / scale;
+                if rounded.is_finite() { rounded } else { f }
+            }
+            MathFunction::Sqrt => f.sqrt(),
+            MathFunction::Ln => f.ln(),
+            MathFunction::Log10 => f.log10(),
+            MathFunction::Exp => f.exp(),
+        }
+    }
+
+    pub fn apply_i64(self, i: i64) -> i64 {
+        match self {
+            MathFunction::Abs => i.saturating_abs(),
+            MathFunction::Sign => i.signum(),
+            MathFunction::Round(digits) if digits < 0 => {
+                // Rounds half away from zero to a multiple of 10^-digits
+                match 10i64.checked_pow((-digits).min(u32::MAX as i64) as u32) {
+                    Some(scale) => {
+                        let remainder = i % scale;
+                        let truncated = i - remainder;
+                        if 2 * remainder.unsigned_abs() >= scale as u64 {
+                            truncated.saturating_add(i.signum() * scale)
+                        } else {
+                            truncated
+                        }
+                    }
+                    None => 0,
+                }
+            }
+            MathFunction::Ceil | MathFunction::Round(_) => i,
+            MathFunction::Sqrt | MathFunction::Ln | MathFunction::Log10 | MathFunction::Exp => {
+                self.apply_f64(i as f64) as i64
+            }
+        }
+    }
+}
+
+pub struct FloatMath {
+    pub function: MathFunction,
+}
+
+impl MapOp<of64, of64> for FloatMath {
+    fn apply(&self, f: of64) -> of64 { OrderedFloat(self.function.apply_f64(f.0)) }
+    fn name() -> &'static str { "math" }
+}
+
+pub struct FloatToIntMath {
+    pub function: MathFunction,
+}
+
+impl MapOp<of64, i64> for FloatToIntMath {
+    fn apply(&self, f: of64) -> i64 { self.function.apply_f64(f.0) as i64 }
+    fn name() -> &'static str { "math" }
+}
+
+pub struct IntMath {
+    pub function: MathFunction,
+}
+
+impl MapOp<i64, i64> for IntMath {
+    fn apply(&self, i: i64) -> i64 { self.function.apply_i64(i) }
+    fn name() -> &'static str { "math" }
+}
+
 pub struct BooleanNot;
 
 impl MapOp<u8, u8> for BooleanNot {
@@ -48,3 +326,110 @@ impl<'a> MapOp<&'a str, i64> for Length {
     fn apply(&self, s: &'a str) -> i64 { s.len() as i64 }
     fn name() -> &'static str { "length" }
 }
+
+
+pub struct StartsWith {
+    pub prefix: String,
+}
+
+impl<'a> MapOp<&'a str, u8> for StartsWith {
+    fn apply(&self, s: &'a str) -> u8 { s.starts_with(&self.prefix) as u8 }
+    fn name() -> &'static str { "starts_with" }
+}
+
+
+pub struct IsInStrings {
+    pub set: HashSet<String>,
+}
+
+impl<'a> MapOp<&'a str, u8> for IsInStrings {
+    fn apply(&self, s: &'a str) -> u8 { self.set.contains(s) as u8 }
+    fn name() -> &'static str { "is_in" }
+}
+
+
+pub struct IsInI64 {
+    pub set: HashSet<i64>,
+}
+
+impl MapOp<i64, u8> for IsInI64 {
+    fn apply(&self, x: i64) -> u8 { self.set.contains(&x) as u8 }
+    fn name() -> &'static str { "is_in" }
+}
+
+
+/// Transformation from strings to strings, evaluated by the `StringMap` operator.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StringFunction {
+    Lower,
+    Upper,
+    Trim,
+    TrimStart,
+    TrimEnd,
+    // 1-based character offset, negative offsets count from the end of the string
+    Substr { start: i64, length: Option<i64> },
+    // 1-based index of the field, negative indices count from the end of the string
+    SplitPart { delimiter: String, index: i64 },
+    Replace { from: String, to: String },
+    RegexpExtract { regex: String, group: usize },
+    Concat { prefix: String, suffix: String },
+}
+
+impl StringFunction {
+    /// Unique byte representation of the function, used to identify common subexpressions in query plans.
+    pub fn signature(&self) -> Vec<u8> {
+        format!("{:?}", self).into_bytes()
+    }
+
+    /// Appends the result of applying the function to `s` to `out`.
+    /// `regex` is the compiled pattern of `RegexpExtract`.
+    pub fn apply(&self, s: &str, regex: Option<&regex::Regex>, out: &mut String) {
+        match self {
+            StringFunction::Lower => out.extend(s.chars().flat_map(char::to_lowercase)),
+            StringFunction::Upper => out.extend(s.chars().flat_map(char::to_uppercase)),
+            StringFunction::Trim => out.push_str(s.trim()),
+            StringFunction::TrimStart => out.push_str(s.trim_start()),
+            StringFunction::TrimEnd => out.push_str(s.trim_end()),
+            StringFunction::Substr { start, length } => {
+                let len = s.chars().count() as i64;
+                let begin = if *start < 0 { (len + start).max(0) } else { start - 1 };
+                let end = match length {
+                    Some(length) => (begin + length).min(len),
+                    None => len,
+                };
+                let begin = begin.max(0);
+                if begin < end {
+                    out.extend(s.chars().skip(begin as usize).take((end - begin) as usize));
+                }
+            }
+            StringFunction::SplitPart { delimiter, index } => {
+                let field = if delimiter.is_empty() {
+                    if index.abs() == 1 { Some(s) } else { None }
+                } else if *index > 0 {
+                    s.split(delimiter.as_str()).nth(*index as usize - 1)
+                } else {
+                    s.rsplit(delimiter.as_str()).nth((-index) as usize - 1)
+                };
+                out.push_str(field.unwrap_or(""));
+            }
+            StringFunction::Replace { from, to } => {
+                if from.is_empty() {
+                    out.push_str(s);
+                } else {
+                    out.push_str(&s.replace(from.as_str(), to));
+                }
+            }
+            StringFunction::RegexpExtract { group, .. } => {
+                let regex = regex.expect("regexp_extract without compiled regex");
+                if let Some(m) = regex.captures(s).and_then(|captures| captures.get(*group)) {
+                    out.push_str(m.as_str());
+                }
+            }
+            StringFunction::Concat { prefix, suffix } => {
+                out.push_str(prefix);
+                out.push_str(s);
+                out.push_str(suffix);
+            }
+        }
+    }
+}
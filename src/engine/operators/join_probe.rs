@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use ordered_float::OrderedFloat;
+
+use crate::engine::*;
+use crate::ingest::raw_val::RawVal;
+use crate::mem_store::Val;
+
+/// Maps the join keys of a broadcast join table to the index of the row containing the key.
+#[derive(Default)]
+pub struct JoinKeyIndex {
+    ints: HashMap<i64, usize>,
+    floats: HashMap<OrderedFloat<f64>, usize>,
+    strings: HashMap<String, usize>,
+}
+
+impl JoinKeyIndex {
+    /// Returns the row that previously contained `key`, if any. Null keys are never inserted.
+    pub fn insert(&mut self, key: &RawVal, row: usize) -> Option<usize> {
+        match *key {
+            RawVal::Int(i) | RawVal::Timestamp(i, _) => self.ints.insert(i, row),
+            RawVal::Bool(b) => self.ints.insert(b as i64, row),
+            RawVal::Float(f) => self.floats.insert(f, row),
+            RawVal::Str(ref s) => self.strings.insert(s.clone(), row),
+            RawVal::Null => None,
+        }
+    }
+
+    pub fn get(&self, key: Val) -> Option<usize> {
+        match key {
+            Val::Integer(i) => self.ints.get(&i),
+            Val::Bool(b) => self.ints.get(&(b as i64)),
+            Val::Float(f) => self.floats.get(&f),
+            Val::Str(s) => self.strings.get(s),
+            Val::Null => None,
+        }
+        .copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ints.len() + self.floats.len() + self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl fmt::Debug for JoinKeyIndex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "JoinKeyIndex({} keys)", self.len())
+    }
+}
+
+/// Outputs the row of the joined table that matches each key, or `unmatched` if there is no matching row.
+pub struct JoinProbe<'a> {
+    pub keys: BufferRef<Val<'a>>,
+    pub index: Arc<JoinKeyIndex>,
+    pub unmatched: usize,
+    pub rows: BufferRef<usize>,
+}
+
+impl<'a> VecOperator<'a> for JoinProbe<'a> {
+    fn execute(&mut self, stream: bool, scratchpad: &mut Scratchpad<'a>) -> Result<(), QueryError> {
+        let keys = scratchpad.get(self.keys);
+        let mut rows = scratchpad.get_mut(self.rows);
+        if stream { rows.clear(); }
+        for &key in keys.iter() {
+            rows.push(self.index.get(key).unwrap_or(self.unmatched));
+        }
+        Ok(())
+    }
+
+    fn init(&mut self, _: usize, batch_size: usize, scratchpad: &mut Scratchpad<'a>) {
+        scratchpad.set(self.rows, Vec::with_capacity(batch_size));
+    }
+
+    fn inputs(&self) -> Vec<BufferRef<Any>> { vec![self.keys.any()] }
+    fn inputs_mut(&mut self) -> Vec<&mut usize> { vec![&mut self.keys.i] }
+    fn outputs(&self) -> Vec<BufferRef<Any>> { vec![self.rows.any()] }
+    fn can_stream_input(&self, _: usize) -> bool { true }
+    fn can_stream_output(&self, _: usize) -> bool { true }
+    fn can_block_output(&self) -> bool { true }
+    fn allocates(&self) -> bool { true }
+
+    fn display_op(&self, _: bool) -> String {
+        format!("join_probe({}, {:?})", self.keys, self.index)
+    }
+}
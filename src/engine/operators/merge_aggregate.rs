@@ -53,6 +53,90 @@ where
     }
 }
 
+#[derive(Debug)]
+pub struct MergeAggregateStates<T> {
+    pub merge_ops: BufferRef<MergeOp>,
+    pub left: BufferRef<T>,
+    pub right: BufferRef<T>,
+    pub aggregated: BufferRef<T>,
+    pub aggregator: Aggregator,
+}
+
+impl<'a, T: AggregationStates<'a>> VecOperator<'a> for MergeAggregateStates<T> {
+    fn execute(&mut self, _: bool, scratchpad: &mut Scratchpad<'a>) -> Result<(), QueryError> {
+        let aggregated = {
+            let ops = scratchpad.get(self.merge_ops);
+            let left = scratchpad.get_states(self.left);
+            let right = scratchpad.get_states(self.right);
+            merge_states::<T>(&ops, left.states(), right.states())
+        };
+        let mut result = T::default();
+        *result.states_mut() = aggregated;
+        scratchpad.set_any(self.aggregated.any(), Box::new(result));
+        Ok(())
+    }
+
+    fn inputs(&self) -> Vec<BufferRef<Any>> {
+        vec![self.left.any(), self.right.any(), self.merge_ops.any()]
+    }
+    fn inputs_mut(&mut self) -> Vec<&mut usize> {
+        vec![&mut self.left.i, &mut self.right.i, &mut self.merge_ops.i]
+    }
+    fn outputs(&self) -> Vec<BufferRef<Any>> {
+        vec![self.aggregated.any()]
+    }
+    fn can_stream_input(&self, _: usize) -> bool {
+        false
+    }
+    fn can_stream_output(&self, _: usize) -> bool {
+        false
+    }
+    fn allocates(&self) -> bool {
+        true
+    }
+
+    fn display_op(&self, _: bool) -> String {
+        format!(
+            "merge_aggregate({:?}; {}, {}, {})",
+            self.aggregator, self.merge_ops, self.left, self.right
+        )
+    }
+}
+
+fn merge_states<'a, T: AggregationStates<'a>>(
+    ops: &[MergeOp],
+    left: &[T::State],
+    right: &[T::State],
+) -> Vec<T::State> {
+    if left.is_empty() {
+        return right.to_vec();
+    } else if right.is_empty() {
+        return left.to_vec();
+    }
+
+    let mut result: Vec<T::State> = Vec::with_capacity(ops.len());
+    let mut i = 0;
+    let mut j = 0;
+    for op in ops {
+        match *op {
+            MergeOp::TakeLeft => {
+                result.push(left[i].clone());
+                i += 1;
+            }
+            MergeOp::TakeRight => {
+                result.push(right[j].clone());
+                j += 1;
+            }
+            MergeOp::MergeRight => {
+                let last = result.len() - 1;
+                T::merge(&mut result[last], &right[j]);
+                j += 1;
+            }
+        }
+    }
+    result
+}
+
 fn merge_aggregate<T: Combinable<T>>(
     ops: &[MergeOp],
     left: &[T],
@@ -1,16 +1,24 @@
 pub use self::aggregator::*;
 pub use self::comparator::*;
+pub use self::functions::{CastType, MathFunction, StringFunction, TimeGranularity};
+pub use self::join_probe::JoinKeyIndex;
 pub use self::vector_operator::*;
 
 pub mod vector_operator;
 pub mod comparator;
 
 mod aggregate;
+mod aggregate_distinct;
+mod aggregate_sketch;
+mod aggregate_values;
+mod aggregate_welford;
 mod assemble_nullable;
 mod binary_operator;
+mod blend;
 mod bit_unpack;
 mod bool_op;
 mod buffer_stream;
+mod cast_vals;
 mod collect;
 mod column_ops;
 mod combine_null_maps;
@@ -38,6 +46,7 @@ mod hashmap_grouping_val_rows;
 mod identity;
 mod indices;
 mod is_null;
+mod join_probe;
 mod lz4_decode;
 mod make_nullable;
 mod map_operator;
@@ -71,6 +80,7 @@ mod sort_by;
 mod sort_by_slices;
 mod sort_by_val_rows;
 mod stream_buffer;
+mod string_map;
 mod subpartition;
 mod to_val;
 mod top_n;
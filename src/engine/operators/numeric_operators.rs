@@ -31,6 +31,23 @@ pub struct Modulo<LHS, RHS> {
     rhs: PhantomData<RHS>,
 }
 
+pub struct Power<LHS, RHS> {
+    lhs: PhantomData<LHS>,
+    rhs: PhantomData<RHS>,
+}
+
+pub struct Greatest<LHS, RHS, OUT> {
+    lhs: PhantomData<LHS>,
+    rhs: PhantomData<RHS>,
+    out: PhantomData<OUT>,
+}
+
+pub struct Least<LHS, RHS, OUT> {
+    lhs: PhantomData<LHS>,
+    rhs: PhantomData<RHS>,
+    out: PhantomData<OUT>,
+}
+
 
 impl<LHS: PrimInt, RHS: PrimInt> BinaryOp<LHS, RHS, i64> for Addition<LHS, RHS> {
     #[inline]
@@ -130,3 +147,48 @@ impl<LHS: PrimInt, RHS: PrimInt> CheckedBinaryOp<LHS, RHS, i64> for Modulo<LHS,
         }
     }
 }
+
+impl<LHS: ToPrimitive, RHS: ToPrimitive> BinaryOp<LHS, RHS, OrderedFloat<f64>> for Power<LHS, RHS> {
+    #[inline]
+    fn perform(lhs: LHS, rhs: RHS) -> OrderedFloat<f64> {
+        OrderedFloat(lhs.to_f64().unwrap().powf(rhs.to_f64().unwrap()))
+    }
+
+    fn symbol() -> &'static str { "^" }
+}
+
+impl<LHS: PrimInt, RHS: PrimInt> BinaryOp<LHS, RHS, i64> for Greatest<LHS, RHS, i64> {
+    #[inline]
+    fn perform(lhs: LHS, rhs: RHS) -> i64 {
+        lhs.to_i64().unwrap().max(rhs.to_i64().unwrap())
+    }
+
+    fn symbol() -> &'static str { "greatest" }
+}
+
+impl<LHS: ToPrimitive, RHS: ToPrimitive> BinaryOp<LHS, RHS, OrderedFloat<f64>> for Greatest<LHS, RHS, OrderedFloat<f64>> {
+    #[inline]
+    fn perform(lhs: LHS, rhs: RHS) -> OrderedFloat<f64> {
+        OrderedFloat(lhs.to_f64().unwrap()).max(OrderedFloat(rhs.to_f64().unwrap()))
+    }
+
+    fn symbol() -> &'static str { "greatest" }
+}
+
+impl<LHS: PrimInt, RHS: PrimInt> BinaryOp<LHS, RHS, i64> for Least<LHS, RHS, i64> {
+    #[inline]
+    fn perform(lhs: LHS, rhs: RHS) -> i64 {
+        lhs.to_i64().unwrap().min(rhs.to_i64().unwrap())
+    }
+
+    fn symbol() -> &'static str { "least" }
+}
+
+impl<LHS: ToPrimitive, RHS: ToPrimitive> BinaryOp<LHS, RHS, OrderedFloat<f64>> for Least<LHS, RHS, OrderedFloat<f64>> {
+    #[inline]
+    fn perform(lhs: LHS, rhs: RHS) -> OrderedFloat<f64> {
+        OrderedFloat(lhs.to_f64().unwrap()).min(OrderedFloat(rhs.to_f64().unwrap()))
+    }
+
+    fn symbol() -> &'static str { "least" }
+}
@@ -54,9 +54,10 @@ impl<'a, T: 'a> VecOperator<'a> for SelectNullable<T> where T: VecData<T> {
             data_out.clear();
             present_out.clear();
         }
+        let offset = data_out.len();
         for (i, &index) in indices.iter().enumerate() {
             data_out.push(data[index]);
-            if (*present).is_set(index) { present_out.set(i) }
+            if (*present).is_set(index) { present_out.set(offset + i) }
         }
         Ok(())
     }
@@ -77,3 +78,39 @@ impl<'a, T: 'a> VecOperator<'a> for SelectNullable<T> where T: VecData<T> {
         format!("{}[{}]", self.input, self.indices)
     }
 }
+
+
+#[derive(Debug)]
+pub struct SelectStates<T> {
+    pub input: BufferRef<T>,
+    pub indices: BufferRef<usize>,
+    pub output: BufferRef<T>,
+}
+
+impl<'a, T: AggregationStates<'a>> VecOperator<'a> for SelectStates<T> {
+    fn execute(&mut self, _: bool, scratchpad: &mut Scratchpad<'a>) -> Result<(), QueryError> {
+        let input = scratchpad.get_states(self.input);
+        let indices = scratchpad.get(self.indices);
+        let mut output = scratchpad.get_mut_states(self.output);
+        let (states, output) = (input.states(), output.states_mut());
+        for i in indices.iter() {
+            output.push(states[*i].clone());
+        }
+        Ok(())
+    }
+
+    fn init(&mut self, _: usize, _: usize, scratchpad: &mut Scratchpad<'a>) {
+        scratchpad.set_any(self.output.any(), Box::<T>::default());
+    }
+
+    fn inputs(&self) -> Vec<BufferRef<Any>> { vec![self.input.any(), self.indices.any()] }
+    fn inputs_mut(&mut self) -> Vec<&mut usize> { vec![&mut self.input.i, &mut self.indices.i] }
+    fn outputs(&self) -> Vec<BufferRef<Any>> { vec![self.output.any()] }
+    fn can_stream_input(&self, _: usize) -> bool { false }
+    fn can_stream_output(&self, _: usize) -> bool { false }
+    fn allocates(&self) -> bool { true }
+
+    fn display_op(&self, _: bool) -> String {
+        format!("{}[{}]", self.input, self.indices)
+    }
+}
@@ -0,0 +1,93 @@
+use regex::Regex;
+
+use crate::engine::*;
+use super::functions::StringFunction;
+
+/// Applies `function` to all `strings` and stores the results in the same format as string dictionaries,
+/// so they can be resolved with `DictLookup`.
+#[derive(Debug)]
+pub struct StringMap<'a> {
+    pub strings: BufferRef<&'a str>,
+    pub function: StringFunction,
+    pub regex: Option<Regex>,
+    pub offset_len: BufferRef<u64>,
+    pub backing_store: BufferRef<u8>,
+}
+
+impl<'a> VecOperator<'a> for StringMap<'a> {
+    fn execute(&mut self, _: bool, scratchpad: &mut Scratchpad<'a>) -> Result<(), QueryError> {
+        let (offset_len, backing_store) = {
+            let strings = scratchpad.get(self.strings);
+            let mut offset_len = Vec::with_capacity(strings.len());
+            let mut backing_store = String::new();
+            for s in strings.iter() {
+                let offset = backing_store.len();
+                self.function.apply(s, self.regex.as_ref(), &mut backing_store);
+                offset_len.push(encode_offset_len(offset, backing_store.len() - offset)?);
+            }
+            (offset_len, backing_store)
+        };
+        scratchpad.set(self.offset_len, offset_len);
+        scratchpad.set(self.backing_store, backing_store.into_bytes());
+        Ok(())
+    }
+
+    fn inputs(&self) -> Vec<BufferRef<Any>> { vec![self.strings.any()] }
+    fn inputs_mut(&mut self) -> Vec<&mut usize> { vec![&mut self.strings.i] }
+    fn outputs(&self) -> Vec<BufferRef<Any>> { vec![self.offset_len.any(), self.backing_store.any()] }
+    fn can_stream_input(&self, _: usize) -> bool { false }
+    fn can_stream_output(&self, _: usize) -> bool { false }
+    fn allocates(&self) -> bool { true }
+
+    fn display_op(&self, _: bool) -> String {
+        format!("{:?}({})", self.function, self.strings)
+    }
+}
+
+/// Concatenates the strings in `lhs` and `rhs` and stores the results in the same format as string dictionaries.
+#[derive(Debug)]
+pub struct ConcatStrings<'a> {
+    pub lhs: BufferRef<&'a str>,
+    pub rhs: BufferRef<&'a str>,
+    pub offset_len: BufferRef<u64>,
+    pub backing_store: BufferRef<u8>,
+}
+
+impl<'a> VecOperator<'a> for ConcatStrings<'a> {
+    fn execute(&mut self, _: bool, scratchpad: &mut Scratchpad<'a>) -> Result<(), QueryError> {
+        let (offset_len, backing_store) = {
+            let lhs = scratchpad.get(self.lhs);
+            let rhs = scratchpad.get(self.rhs);
+            let mut offset_len = Vec::with_capacity(lhs.len());
+            let mut backing_store = String::new();
+            for (l, r) in lhs.iter().zip(rhs.iter()) {
+                let offset = backing_store.len();
+                backing_store.push_str(l);
+                backing_store.push_str(r);
+                offset_len.push(encode_offset_len(offset, backing_store.len() - offset)?);
+            }
+            (offset_len, backing_store)
+        };
+        scratchpad.set(self.offset_len, offset_len);
+        scratchpad.set(self.backing_store, backing_store.into_bytes());
+        Ok(())
+    }
+
+    fn inputs(&self) -> Vec<BufferRef<Any>> { vec![self.lhs.any(), self.rhs.any()] }
+    fn inputs_mut(&mut self) -> Vec<&mut usize> { vec![&mut self.lhs.i, &mut self.rhs.i] }
+    fn outputs(&self) -> Vec<BufferRef<Any>> { vec![self.offset_len.any(), self.backing_store.any()] }
+    fn can_stream_input(&self, _: usize) -> bool { false }
+    fn can_stream_output(&self, _: usize) -> bool { false }
+    fn allocates(&self) -> bool { true }
+
+    fn display_op(&self, _: bool) -> String {
+        format!("{} || {}", self.lhs, self.rhs)
+    }
+}
+
+pub(super) fn encode_offset_len(offset: usize, len: usize) -> Result<u64, QueryError> {
+    if len >= 1 << 24 {
+        bail!(QueryError::NotImplemented, "Strings longer than 16MiB (found {} bytes)", len)
+    }
+    Ok(((offset as u64) << 24) | len as u64)
+}
@@ -7,22 +7,30 @@ use crate::engine::Aggregator;
 use crate::engine::*;
 use crate::ingest::raw_val::RawVal;
 use crate::mem_store::*;
+use crate::sketch::{HyperLogLog, TDigest};
 use crate::QueryError;
 use std::fmt::Write;
 use std::intrinsics::type_name;
 use std::marker::PhantomData;
 use std::result::Result;
+use std::sync::Arc;
 
 use super::aggregate::*;
+use super::aggregate_distinct::AggregateDistinct;
+use super::aggregate_sketch::AggregateSketch;
+use super::aggregate_values::AggregateValues;
+use super::aggregate_welford::AggregateWelford;
 use super::assemble_nullable::AssembleNullable;
 use super::binary_operator::*;
+use super::blend::*;
 use super::bit_unpack::BitUnpackOperator;
 // use super::bool_op::*;
 use super::buffer_stream::*;
+use super::cast_vals::{FormatVals, NullableFormattedVals, ParseVals};
 use super::collect::Collect;
 use super::column_ops::*;
 use super::combine_null_maps::CombineNullMaps;
-use super::compact::Compact;
+use super::compact::{Compact, CompactStates};
 use super::compact_with_nullable::CompactWithNullable;
 use super::compact_nullable::CompactNullable;
 use super::compact_nullable_nullable::CompactNullableNullable;
@@ -46,10 +54,11 @@ use super::hashmap_grouping_val_rows::HashMapGroupingValRows;
 use super::identity::Identity;
 use super::indices::Indices;
 use super::is_null::*;
+use super::join_probe::JoinProbe;
 use super::make_nullable::MakeNullable;
 use super::map_operator::MapOperator;
 use super::merge::Merge;
-use super::merge_aggregate::MergeAggregate;
+use super::merge_aggregate::{MergeAggregate, MergeAggregateStates};
 use super::merge_deduplicate::MergeDeduplicate;
 use super::merge_deduplicate_partitioned::MergeDeduplicatePartitioned;
 use super::merge_drop::MergeDrop;
@@ -76,6 +85,7 @@ use super::sort_by::*;
 use super::sort_by_slices::SortBySlices;
 use super::sort_by_val_rows::SortByValRows;
 use super::stream_buffer::{StreamBuffer, StreamBufferNullable, StreamNullVec};
+use super::string_map::{ConcatStrings, StringMap};
 use super::subpartition::SubPartition;
 use super::to_val::*;
 use super::top_n::TopN;
@@ -503,6 +513,10 @@ pub mod operator {
         dict_data: BufferRef<u8>,
         output: BufferRef<&str>,
     ) -> Result<BoxedOperator, QueryError> {
+        if indices.tag == EncodingType::USize {
+            let indices = indices.usize()?;
+            return Ok(Box::new(DictLookup { indices, output, dict_indices, dict_data }));
+        }
         reify_types![
             "dict_lookup";
             indices: Integer;
@@ -510,6 +524,25 @@ pub mod operator {
         ]
     }
 
+    pub fn dict_is_in<'a>(
+        indices: TypedBufferRef,
+        dict_indices: BufferRef<u64>,
+        dict_data: BufferRef<u8>,
+        set: Vec<String>,
+        output: BufferRef<u8>,
+    ) -> Result<BoxedOperator<'a>, QueryError> {
+        let set = set.into_iter().collect();
+        if indices.tag == EncodingType::USize {
+            let indices = indices.usize()?;
+            return Ok(Box::new(DictIsIn { indices, dict_indices, dict_data, set, is_member: vec![], output }));
+        }
+        reify_types![
+            "dict_is_in";
+            indices: Integer;
+            Ok(Box::new(DictIsIn { indices, dict_indices, dict_data, set, is_member: vec![], output }))
+        ]
+    }
+
     pub fn lz4_decode<'a>(
         encoded: BufferRef<u8>,
         decoded_len: usize,
@@ -669,6 +702,30 @@ pub mod operator {
                 output.any(),
                 LengthSource::InputLength,
             ))
+        } else if input.tag == EncodingType::ValSets {
+            Ok(Box::new(SelectStates {
+                input: input.val_sets()?,
+                indices,
+                output: output.val_sets()?,
+            }))
+        } else if input.tag == EncodingType::Sketches {
+            Ok(Box::new(SelectStates {
+                input: input.sketches()?,
+                indices,
+                output: output.sketches()?,
+            }))
+        } else if input.tag == EncodingType::FloatLists {
+            Ok(Box::new(SelectStates {
+                input: input.float_lists()?,
+                indices,
+                output: output.float_lists()?,
+            }))
+        } else if input.tag == EncodingType::WelfordStates {
+            Ok(Box::new(SelectStates {
+                input: input.welford_states()?,
+                indices,
+                output: output.welford_states()?,
+            }))
         } else {
             reify_types! {
                 "select";
@@ -1035,6 +1092,90 @@ pub mod operator {
         }
     }
 
+    pub fn power<'a>(
+        lhs: TypedBufferRef,
+        rhs: TypedBufferRef,
+        output: BufferRef<of64>,
+    ) -> Result<BoxedOperator<'a>, QueryError> {
+        reify_types! {
+            "power";
+            lhs: ScalarI64, rhs: NumberNoU64;
+            Ok(Box::new(BinarySVOperator { lhs, rhs, output, op: PhantomData::<Power<_, _>> }));
+            lhs: ScalarF64, rhs: NumberNoU64;
+            Ok(Box::new(BinarySVOperator { lhs, rhs, output, op: PhantomData::<Power<_, _>> }));
+            lhs: NumberNoU64, rhs: ScalarI64;
+            Ok(Box::new(BinaryVSOperator { lhs, rhs, output, op: PhantomData::<Power<_, _>> }));
+            lhs: NumberNoU64, rhs: ScalarF64;
+            Ok(Box::new(BinaryVSOperator { lhs, rhs, output, op: PhantomData::<Power<_, _>> }));
+            lhs: NumberNoU64, rhs: NumberNoU64;
+            Ok(Box::new(BinaryOperator { lhs, rhs, output, op: PhantomData::<Power<_, _>> }))
+        }
+    }
+
+    pub fn greatest<'a>(
+        lhs: TypedBufferRef,
+        rhs: TypedBufferRef,
+        output: TypedBufferRef,
+    ) -> Result<BoxedOperator<'a>, QueryError> {
+        reify_types! {
+            "greatest";
+            lhs: ScalarI64, rhs: IntegerNoU64;
+            Ok(Box::new(BinaryVSOperator { lhs: rhs, rhs: lhs, output: output.into(), op: PhantomData::<Greatest<_, _, i64>> }));
+            lhs: IntegerNoU64, rhs: ScalarI64;
+            Ok(Box::new(BinaryVSOperator { lhs, rhs, output: output.into(), op: PhantomData::<Greatest<_, _, i64>> }));
+            lhs: IntegerNoU64, rhs: IntegerNoU64;
+            Ok(Box::new(BinaryOperator { lhs, rhs, output: output.into(), op: PhantomData::<Greatest<_, _, i64>> }));
+            lhs: Float, rhs: NumberNoU64;
+            Ok(Box::new(BinaryOperator { lhs, rhs, output: output.into(), op: PhantomData::<Greatest<_, _, OrderedFloat<f64>>> }));
+            lhs: NumberNoU64, rhs: Float;
+            Ok(Box::new(BinaryOperator { lhs, rhs, output: output.into(), op: PhantomData::<Greatest<_, _, OrderedFloat<f64>>> }));
+            lhs: ScalarI64, rhs: Float;
+            Ok(Box::new(BinarySVOperator { lhs, rhs, output: output.into(), op: PhantomData::<Greatest<_, _, OrderedFloat<f64>>> }));
+            lhs: Float, rhs: ScalarI64;
+            Ok(Box::new(BinaryVSOperator { lhs, rhs, output: output.into(), op: PhantomData::<Greatest<_, _, OrderedFloat<f64>>> }));
+            lhs: ScalarF64, rhs: Integer;
+            Ok(Box::new(BinarySVOperator { lhs, rhs, output: output.into(), op: PhantomData::<Greatest<_, _, OrderedFloat<f64>>> }));
+            lhs: Integer, rhs: ScalarF64;
+            Ok(Box::new(BinaryVSOperator { lhs, rhs, output: output.into(), op: PhantomData::<Greatest<_, _, OrderedFloat<f64>>> }));
+            lhs: ScalarF64, rhs: Float;
+            Ok(Box::new(BinarySVOperator { lhs, rhs, output: output.into(), op: PhantomData::<Greatest<_, _, OrderedFloat<f64>>> }));
+            lhs: Float, rhs: ScalarF64;
+            Ok(Box::new(BinaryVSOperator { lhs, rhs, output: output.into(), op: PhantomData::<Greatest<_, _, OrderedFloat<f64>>> }))
+        }
+    }
+
+    pub fn least<'a>(
+        lhs: TypedBufferRef,
+        rhs: TypedBufferRef,
+        output: TypedBufferRef,
+    ) -> Result<BoxedOperator<'a>, QueryError> {
+        reify_types! {
+            "least";
+            lhs: ScalarI64, rhs: IntegerNoU64;
+            Ok(Box::new(BinaryVSOperator { lhs: rhs, rhs: lhs, output: output.into(), op: PhantomData::<Least<_, _, i64>> }));
+            lhs: IntegerNoU64, rhs: ScalarI64;
+            Ok(Box::new(BinaryVSOperator { lhs, rhs, output: output.into(), op: PhantomData::<Least<_, _, i64>> }));
+            lhs: IntegerNoU64, rhs: IntegerNoU64;
+            Ok(Box::new(BinaryOperator { lhs, rhs, output: output.into(), op: PhantomData::<Least<_, _, i64>> }));
+            lhs: Float, rhs: NumberNoU64;
+            Ok(Box::new(BinaryOperator { lhs, rhs, output: output.into(), op: PhantomData::<Least<_, _, OrderedFloat<f64>>> }));
+            lhs: NumberNoU64, rhs: Float;
+            Ok(Box::new(BinaryOperator { lhs, rhs, output: output.into(), op: PhantomData::<Least<_, _, OrderedFloat<f64>>> }));
+            lhs: ScalarI64, rhs: Float;
+            Ok(Box::new(BinarySVOperator { lhs, rhs, output: output.into(), op: PhantomData::<Least<_, _, OrderedFloat<f64>>> }));
+            lhs: Float, rhs: ScalarI64;
+            Ok(Box::new(BinaryVSOperator { lhs, rhs, output: output.into(), op: PhantomData::<Least<_, _, OrderedFloat<f64>>> }));
+            lhs: ScalarF64, rhs: Integer;
+            Ok(Box::new(BinarySVOperator { lhs, rhs, output: output.into(), op: PhantomData::<Least<_, _, OrderedFloat<f64>>> }));
+            lhs: Integer, rhs: ScalarF64;
+            Ok(Box::new(BinaryVSOperator { lhs, rhs, output: output.into(), op: PhantomData::<Least<_, _, OrderedFloat<f64>>> }));
+            lhs: ScalarF64, rhs: Float;
+            Ok(Box::new(BinarySVOperator { lhs, rhs, output: output.into(), op: PhantomData::<Least<_, _, OrderedFloat<f64>>> }));
+            lhs: Float, rhs: ScalarF64;
+            Ok(Box::new(BinaryVSOperator { lhs, rhs, output: output.into(), op: PhantomData::<Least<_, _, OrderedFloat<f64>>> }))
+        }
+    }
+
     pub fn checked_multiplication<'a>(
         lhs: TypedBufferRef,
         rhs: TypedBufferRef,
@@ -1406,11 +1547,59 @@ pub mod operator {
     }
 
     #[allow(clippy::wrong_self_convention)]
-    pub fn to_year<'a>(input: BufferRef<i64>, output: BufferRef<i64>) -> BoxedOperator<'a> {
+    pub fn to_year<'a>(input: BufferRef<i64>, per_second: i64, output: BufferRef<i64>) -> BoxedOperator<'a> {
+        Box::new(MapOperator {
+            input,
+            output,
+            map: ToYear { per_second },
+        })
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    pub fn to_month<'a>(input: BufferRef<i64>, per_second: i64, output: BufferRef<i64>) -> BoxedOperator<'a> {
+        Box::new(MapOperator {
+            input,
+            output,
+            map: ToMonth { per_second },
+        })
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    pub fn to_day_of_week<'a>(input: BufferRef<i64>, per_second: i64, output: BufferRef<i64>) -> BoxedOperator<'a> {
         Box::new(MapOperator {
             input,
             output,
-            map: ToYear,
+            map: ToDayOfWeek { per_second },
+        })
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    pub fn to_hour<'a>(input: BufferRef<i64>, per_second: i64, output: BufferRef<i64>) -> BoxedOperator<'a> {
+        Box::new(MapOperator {
+            input,
+            output,
+            map: ToHour { per_second },
+        })
+    }
+
+    pub fn date_trunc<'a>(
+        input: BufferRef<i64>,
+        granularity: TimeGranularity,
+        per_second: i64,
+        output: BufferRef<i64>,
+    ) -> BoxedOperator<'a> {
+        Box::new(MapOperator {
+            input,
+            output,
+            map: DateTrunc { granularity, per_second },
+        })
+    }
+
+    pub fn time_bucket<'a>(input: BufferRef<i64>, width: i64, output: BufferRef<i64>) -> BoxedOperator<'a> {
+        Box::new(MapOperator {
+            input,
+            output,
+            map: TimeBucket { width },
         })
     }
 
@@ -1422,6 +1611,66 @@ pub mod operator {
         })
     }
 
+    pub fn blend<'a>(
+        cond: BufferRef<u8>,
+        lhs: TypedBufferRef,
+        rhs: TypedBufferRef,
+        output: TypedBufferRef,
+    ) -> Result<BoxedOperator<'a>, QueryError> {
+        reify_types! {
+            "blend";
+            lhs, rhs, output: Primitive;
+            Ok(Box::new(Blend { cond, lhs, rhs, output }))
+        }
+    }
+
+    pub fn blend_null_maps<'a>(
+        cond: BufferRef<u8>,
+        lhs: TypedBufferRef,
+        rhs: TypedBufferRef,
+        present: BufferRef<u8>,
+    ) -> Result<BoxedOperator<'a>, QueryError> {
+        Ok(Box::new(BlendNullMaps { cond, lhs: lhs.nullable_any()?, rhs: rhs.nullable_any()?, present }))
+    }
+
+    pub fn expand_scalar<'a>(
+        scalar: TypedBufferRef,
+        like: BufferRef<Any>,
+        expanded: TypedBufferRef,
+    ) -> Result<BoxedOperator<'a>, QueryError> {
+        match scalar.tag {
+            EncodingType::ScalarI64 => Ok(Box::new(ExpandScalar { scalar: scalar.scalar_i64()?, like, expanded: expanded.i64()? })),
+            EncodingType::ScalarF64 => Ok(Box::new(ExpandScalar { scalar: scalar.scalar_f64()?, like, expanded: expanded.f64()? })),
+            EncodingType::ScalarStr => Ok(Box::new(ExpandScalar { scalar: scalar.scalar_str()?, like, expanded: expanded.str()? })),
+            t => Err(fatal!("expand_scalar not supported for type {:?}", t)),
+        }
+    }
+
+    pub fn math<'a>(
+        input: TypedBufferRef,
+        function: MathFunction,
+        output: TypedBufferRef,
+    ) -> Result<BoxedOperator<'a>, QueryError> {
+        Ok(match (input.tag, output.tag) {
+            (EncodingType::F64, EncodingType::F64) => Box::new(MapOperator {
+                input: input.f64()?,
+                output: output.f64()?,
+                map: FloatMath { function },
+            }),
+            (EncodingType::F64, EncodingType::I64) => Box::new(MapOperator {
+                input: input.f64()?,
+                output: output.i64()?,
+                map: FloatToIntMath { function },
+            }),
+            (EncodingType::I64, EncodingType::I64) => Box::new(MapOperator {
+                input: input.i64()?,
+                output: output.i64()?,
+                map: IntMath { function },
+            }),
+            (input, output) => return Err(fatal!("math not supported for types {:?} -> {:?}", input, output)),
+        })
+    }
+
     pub fn regex<'a>(
         input: BufferRef<&'a str>,
         r: &str,
@@ -1444,6 +1693,88 @@ pub mod operator {
         })
     }
 
+    pub fn starts_with<'a>(input: BufferRef<&'a str>, prefix: &str, output: BufferRef<u8>) -> BoxedOperator<'a> {
+        Box::new(MapOperator {
+            input,
+            output,
+            map: StartsWith {
+                prefix: prefix.to_string(),
+            },
+        })
+    }
+
+    pub fn is_in_strings<'a>(input: BufferRef<&'a str>, set: Vec<String>, output: BufferRef<u8>) -> BoxedOperator<'a> {
+        Box::new(MapOperator {
+            input,
+            output,
+            map: IsInStrings {
+                set: set.into_iter().collect(),
+            },
+        })
+    }
+
+    pub fn is_in_i64<'a>(input: BufferRef<i64>, set: Vec<i64>, output: BufferRef<u8>) -> BoxedOperator<'a> {
+        Box::new(MapOperator {
+            input,
+            output,
+            map: IsInI64 {
+                set: set.into_iter().collect(),
+            },
+        })
+    }
+
+    pub fn string_map<'a>(
+        strings: BufferRef<&'a str>,
+        function: StringFunction,
+        offset_len: BufferRef<u64>,
+        backing_store: BufferRef<u8>,
+    ) -> Result<BoxedOperator<'a>, QueryError> {
+        let regex = match function {
+            StringFunction::RegexpExtract { ref regex, .. } => {
+                Some(Regex::new(regex).map_err(|e| fatal!("Invalid regex `{}`: {}", regex, e))?)
+            }
+            _ => None,
+        };
+        Ok(Box::new(StringMap { strings, function, regex, offset_len, backing_store }))
+    }
+
+    pub fn parse_vals<'a>(
+        vals: BufferRef<Val<'a>>,
+        strict: bool,
+        parsed: TypedBufferRef,
+    ) -> Result<BoxedOperator<'a>, QueryError> {
+        match parsed.tag {
+            EncodingType::NullableI64 => Ok(Box::new(ParseVals { vals, strict, parsed: parsed.nullable_i64()? })),
+            EncodingType::NullableF64 => Ok(Box::new(ParseVals { vals, strict, parsed: parsed.nullable_f64()? })),
+            _ => Err(fatal!("parse_vals not supported for type {:?}", parsed.tag)),
+        }
+    }
+
+    pub fn format_vals<'a>(
+        vals: BufferRef<Val<'a>>,
+        offset_len: BufferRef<u64>,
+        backing_store: BufferRef<u8>,
+    ) -> BoxedOperator<'a> {
+        Box::new(FormatVals { vals, offset_len, backing_store })
+    }
+
+    pub fn nullable_formatted_vals<'a>(
+        vals: BufferRef<Val<'a>>,
+        strings: BufferRef<&'a str>,
+        nullable: TypedBufferRef,
+    ) -> Result<BoxedOperator<'a>, QueryError> {
+        Ok(Box::new(NullableFormattedVals { vals, strings, nullable: nullable.nullable_str()? }))
+    }
+
+    pub fn concat_strings<'a>(
+        lhs: BufferRef<&'a str>,
+        rhs: BufferRef<&'a str>,
+        offset_len: BufferRef<u64>,
+        backing_store: BufferRef<u8>,
+    ) -> BoxedOperator<'a> {
+        Box::new(ConcatStrings { lhs, rhs, offset_len, backing_store })
+    }
+
     pub fn aggregate<'a>(
         input: TypedBufferRef,
         grouping: TypedBufferRef,
@@ -1480,6 +1811,65 @@ pub mod operator {
         }
     }
 
+    pub fn aggregate_distinct<'a>(
+        input: BufferRef<Val<'a>>,
+        grouping: TypedBufferRef,
+        max_index: BufferRef<Scalar<i64>>,
+        output: BufferRef<ValSets<'a>>,
+    ) -> Result<BoxedOperator<'a>, QueryError> {
+        reify_types! {
+            "aggregate_distinct";
+            grouping: Integer;
+            Ok(Box::new(AggregateDistinct { input, grouping, output, max_index }))
+        }
+    }
+
+    pub fn aggregate_sketch<'a>(
+        input: BufferRef<Val<'a>>,
+        grouping: TypedBufferRef,
+        max_index: BufferRef<Scalar<i64>>,
+        aggregator: Aggregator,
+        output: BufferRef<Sketches>,
+    ) -> Result<BoxedOperator<'a>, QueryError> {
+        let empty = match aggregator {
+            Aggregator::ApproxCountDistinct => Sketch::HyperLogLog(HyperLogLog::default()),
+            Aggregator::ApproxQuantile(_) => Sketch::TDigest(TDigest::default()),
+            _ => return Err(fatal!("{:?} is not a sketch aggregator", aggregator)),
+        };
+        reify_types! {
+            "aggregate_sketch";
+            grouping: Integer;
+            Ok(Box::new(AggregateSketch { input, grouping, output, max_index, empty }))
+        }
+    }
+
+    pub fn aggregate_values<'a>(
+        input: BufferRef<Val<'a>>,
+        grouping: TypedBufferRef,
+        max_index: BufferRef<Scalar<i64>>,
+        output: BufferRef<FloatLists>,
+    ) -> Result<BoxedOperator<'a>, QueryError> {
+        reify_types! {
+            "aggregate_values";
+            grouping: Integer;
+            Ok(Box::new(AggregateValues { input, grouping, output, max_index }))
+        }
+    }
+
+    pub fn aggregate_welford<'a>(
+        x: BufferRef<Val<'a>>,
+        y: BufferRef<Val<'a>>,
+        grouping: TypedBufferRef,
+        max_index: BufferRef<Scalar<i64>>,
+        output: BufferRef<WelfordStates>,
+    ) -> Result<BoxedOperator<'a>, QueryError> {
+        reify_types! {
+            "aggregate_welford";
+            grouping: Integer;
+            Ok(Box::new(AggregateWelford { x, y, grouping, output, max_index }))
+        }
+    }
+
     pub fn aggregate_f64<'a>(
         input: TypedBufferRef,
         grouping: TypedBufferRef,
@@ -1580,7 +1970,35 @@ pub mod operator {
         select: TypedBufferRef,
         compacted: TypedBufferRef,
     ) -> Result<BoxedOperator<'a>, QueryError> {
-        if data.is_nullable() && select.is_nullable() {
+        if data.tag == EncodingType::ValSets {
+            let (data, compacted) = (data.val_sets()?, compacted.val_sets()?);
+            reify_types! {
+                "compact_val_sets";
+                select: Integer;
+                Ok(Box::new(CompactStates { data, select, compacted }))
+            }
+        } else if data.tag == EncodingType::Sketches {
+            let (data, compacted) = (data.sketches()?, compacted.sketches()?);
+            reify_types! {
+                "compact_sketches";
+                select: Integer;
+                Ok(Box::new(CompactStates { data, select, compacted }))
+            }
+        } else if data.tag == EncodingType::FloatLists {
+            let (data, compacted) = (data.float_lists()?, compacted.float_lists()?);
+            reify_types! {
+                "compact_float_lists";
+                select: Integer;
+                Ok(Box::new(CompactStates { data, select, compacted }))
+            }
+        } else if data.tag == EncodingType::WelfordStates {
+            let (data, compacted) = (data.welford_states()?, compacted.welford_states()?);
+            reify_types! {
+                "compact_welford_states";
+                select: Integer;
+                Ok(Box::new(CompactStates { data, select, compacted }))
+            }
+        } else if data.is_nullable() && select.is_nullable() {
             reify_types! {
                 "compact_nullable_nullable";
                 data, compacted: NullablePrimitive, select: NullableInteger;
@@ -1654,6 +2072,15 @@ pub mod operator {
         })
     }
 
+    pub fn join_probe<'a>(
+        keys: BufferRef<Val<'a>>,
+        index: Arc<JoinKeyIndex>,
+        unmatched: usize,
+        rows: BufferRef<usize>,
+    ) -> BoxedOperator<'a> {
+        Box::new(JoinProbe { keys, index, unmatched, rows })
+    }
+
     pub fn sort_by<'a>(
         ranking: TypedBufferRef,
         indices: BufferRef<usize>,
@@ -1836,6 +2263,34 @@ pub mod operator {
                 aggregated: aggregated_out.f64()?,
                 aggregator,
             })),
+            EncodingType::ValSets => Ok(Box::new(MergeAggregateStates {
+                merge_ops,
+                left: left.val_sets()?,
+                right: right.val_sets()?,
+                aggregated: aggregated_out.val_sets()?,
+                aggregator,
+            })),
+            EncodingType::Sketches => Ok(Box::new(MergeAggregateStates {
+                merge_ops,
+                left: left.sketches()?,
+                right: right.sketches()?,
+                aggregated: aggregated_out.sketches()?,
+                aggregator,
+            })),
+            EncodingType::FloatLists => Ok(Box::new(MergeAggregateStates {
+                merge_ops,
+                left: left.float_lists()?,
+                right: right.float_lists()?,
+                aggregated: aggregated_out.float_lists()?,
+                aggregator,
+            })),
+            EncodingType::WelfordStates => Ok(Box::new(MergeAggregateStates {
+                merge_ops,
+                left: left.welford_states()?,
+                right: right.welford_states()?,
+                aggregated: aggregated_out.welford_states()?,
+                aggregator,
+            })),
             _ => panic!("Unsupported type {:?} for merge_aggregate", left.tag),
         }
     }
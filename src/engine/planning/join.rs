@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::engine::*;
+use crate::ingest::raw_val::RawVal;
+use crate::mem_store::column::DataSource;
+use crate::mem_store::column_buffer::ColumnBuffer;
+
+/// Maximum number of rows in a table that is joined with the queried table
+pub const MAX_JOIN_TABLE_ROWS: usize = 1 << 20;
+
+/// Small table that is fully materialized once per query and joined with every partition of the queried table.
+pub struct JoinTable {
+    /// Columns of the joined table, which are named by their qualified names when they are read during query execution.
+    /// Every column has one additional row of nulls at index `len`, which is selected for keys without a matching row.
+    pub columns: HashMap<String, Arc<dyn DataSource>>,
+    pub index: Arc<JoinKeyIndex>,
+    pub len: usize,
+}
+
+impl JoinTable {
+    pub fn new(join: &Join, colnames: &[String], rows: Vec<Vec<RawVal>>) -> Result<JoinTable, QueryError> {
+        let (table, key) = (&join.table, &join.right_key);
+        if rows.len() > MAX_JOIN_TABLE_ROWS {
+            bail!(
+                QueryError::NotImplemented,
+                "JOIN with table {} of more than {} rows",
+                table,
+                MAX_JOIN_TABLE_ROWS
+            )
+        }
+        let key_column = match colnames.iter().position(|c| c == key) {
+            Some(i) => i,
+            None => bail!(QueryError::ParseError, "Table {} has no column {}", table, key),
+        };
+        let len = rows.len();
+        let mut index = JoinKeyIndex::default();
+        let mut buffers = vec![ColumnBuffer::default(); colnames.len()];
+        for (i, row) in rows.into_iter().enumerate() {
+            if index.insert(&row[key_column], i).is_some() {
+                bail!(
+                    QueryError::NotImplemented,
+                    "JOIN on column {} of table {} which contains duplicate value {}",
+                    key,
+                    table,
+                    row[key_column]
+                )
+            }
+            for (buffer, val) in buffers.iter_mut().zip(row) {
+                buffer.push_val(val);
+            }
+        }
+        let columns = colnames
+            .iter()
+            .zip(buffers)
+            .map(|(name, mut buffer)| {
+                buffer.push_nulls(1);
+                let column: Arc<dyn DataSource> = buffer.finalize(&join.qualified_name(name));
+                (name.clone(), column)
+            })
+            .collect();
+        Ok(JoinTable {
+            columns,
+            index: Arc::new(index),
+            len,
+        })
+    }
+}
+
+impl fmt::Debug for JoinTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "JoinTable {{ columns: {:?}, len: {} }}", self.columns.keys(), self.len)
+    }
+}
@@ -1,12 +1,23 @@
 mod filter;
+mod join;
 pub mod planner;
 mod query;
 pub mod query_plan;
 
 pub use self::filter::Filter;
+pub use self::join::{JoinTable, MAX_JOIN_TABLE_ROWS};
 pub use self::planner::QueryPlanner;
 pub use self::query::ColumnInfo;
+pub use self::query::Downsample;
+pub use self::query::DownsampleMethod;
+pub use self::query::FrameBound;
+pub use self::query::Join;
+pub use self::query::JoinKind;
+pub use self::query::Mutation;
 pub use self::query::NormalFormQuery;
 pub use self::query::Query;
 pub use self::query::ResultColumn;
+pub use self::query::Window;
+pub use self::query::WindowFrame;
+pub use self::query::WindowFunction;
 pub use self::query_plan::QueryPlan;
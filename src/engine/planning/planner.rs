@@ -14,6 +14,8 @@ pub struct QueryPlanner {
     checkpoint: usize,
     cache_checkpoint: HashMap<[u8; 32], Vec<TypedBufferRef>>,
     pub buffer_provider: BufferProvider,
+    /// Qualifier of the columns of the joined table, and the row of the joined table matching each row of the partition
+    pub join_rows: Option<(String, BufferRef<usize>)>,
 }
 
 impl QueryPlanner {
@@ -46,6 +48,13 @@ impl QueryPlanner {
         &self.operations[op_index]
     }
 
+    /// Returns the rows of the joined table that should be read if `column` belongs to the joined table.
+    pub fn join_rows(&self, column: &str) -> Option<BufferRef<usize>> {
+        let (qualifier, rows) = self.join_rows.as_ref()?;
+        column.strip_prefix(qualifier.as_str())?.strip_prefix('.')?;
+        Some(*rows)
+    }
+
     pub fn enable_common_subexpression_elimination(&self) -> bool { true }
 
 
@@ -175,6 +184,36 @@ fn propagate_nullability(operation: &QueryPlan, bp: &mut BufferProvider) -> Rewr
             ];
             Rewrite::ReplaceWith(ops)
         }
+        Power { lhs, rhs, power } if power.is_nullable() => {
+            let power_non_null = bp.named_buffer("power_non_null", power.tag.non_nullable());
+            let mut ops = vec![Power {
+                lhs: lhs.forget_nullability(),
+                rhs: rhs.forget_nullability(),
+                power: power_non_null,
+            }];
+            ops.extend(combine_nulls(bp, lhs, rhs, power_non_null, power));
+            Rewrite::ReplaceWith(ops)
+        }
+        Greatest { lhs, rhs, greatest } if greatest.is_nullable() => {
+            let greatest_non_null = bp.named_buffer("greatest_non_null", greatest.tag.non_nullable());
+            let mut ops = vec![Greatest {
+                lhs: lhs.forget_nullability(),
+                rhs: rhs.forget_nullability(),
+                greatest: greatest_non_null,
+            }];
+            ops.extend(combine_nulls(bp, lhs, rhs, greatest_non_null, greatest));
+            Rewrite::ReplaceWith(ops)
+        }
+        Least { lhs, rhs, least } if least.is_nullable() => {
+            let least_non_null = bp.named_buffer("least_non_null", least.tag.non_nullable());
+            let mut ops = vec![Least {
+                lhs: lhs.forget_nullability(),
+                rhs: rhs.forget_nullability(),
+                least: least_non_null,
+            }];
+            ops.extend(combine_nulls(bp, lhs, rhs, least_non_null, least));
+            Rewrite::ReplaceWith(ops)
+        }
         Divide { lhs, rhs, division } if division.is_nullable() => {
             let division_non_null = bp.named_buffer("division_non_null", division.tag.non_nullable());
             let mut ops = vec![Divide {
@@ -234,6 +273,96 @@ fn propagate_nullability(operation: &QueryPlan, bp: &mut BufferProvider) -> Rewr
                 Rewrite::None
             }
         }
+        ToYear { timestamp, per_second, year } if year.is_nullable() => {
+            let year_non_null = bp.named_buffer("year_non_null", year.tag.non_nullable());
+            Rewrite::ReplaceWith(vec![
+                ToYear {
+                    timestamp: timestamp.forget_nullability(),
+                    per_second,
+                    year: year_non_null,
+                },
+                PropagateNullability {
+                    nullable: timestamp,
+                    data: year_non_null,
+                    nullable_data: year,
+                },
+            ])
+        }
+        ToMonth { timestamp, per_second, month } if month.is_nullable() => {
+            let month_non_null = bp.named_buffer("month_non_null", month.tag.non_nullable());
+            Rewrite::ReplaceWith(vec![
+                ToMonth {
+                    timestamp: timestamp.forget_nullability(),
+                    per_second,
+                    month: month_non_null,
+                },
+                PropagateNullability {
+                    nullable: timestamp,
+                    data: month_non_null,
+                    nullable_data: month,
+                },
+            ])
+        }
+        ToDayOfWeek { timestamp, per_second, day_of_week } if day_of_week.is_nullable() => {
+            let day_of_week_non_null = bp.named_buffer("day_of_week_non_null", day_of_week.tag.non_nullable());
+            Rewrite::ReplaceWith(vec![
+                ToDayOfWeek {
+                    timestamp: timestamp.forget_nullability(),
+                    per_second,
+                    day_of_week: day_of_week_non_null,
+                },
+                PropagateNullability {
+                    nullable: timestamp,
+                    data: day_of_week_non_null,
+                    nullable_data: day_of_week,
+                },
+            ])
+        }
+        ToHour { timestamp, per_second, hour } if hour.is_nullable() => {
+            let hour_non_null = bp.named_buffer("hour_non_null", hour.tag.non_nullable());
+            Rewrite::ReplaceWith(vec![
+                ToHour {
+                    timestamp: timestamp.forget_nullability(),
+                    per_second,
+                    hour: hour_non_null,
+                },
+                PropagateNullability {
+                    nullable: timestamp,
+                    data: hour_non_null,
+                    nullable_data: hour,
+                },
+            ])
+        }
+        TimeBucket { timestamp, width, bucket } if bucket.is_nullable() => {
+            let bucket_non_null = bp.named_buffer("bucket_non_null", bucket.tag.non_nullable());
+            Rewrite::ReplaceWith(vec![
+                TimeBucket {
+                    timestamp: timestamp.forget_nullability(),
+                    width,
+                    bucket: bucket_non_null,
+                },
+                PropagateNullability {
+                    nullable: timestamp,
+                    data: bucket_non_null,
+                    nullable_data: bucket,
+                },
+            ])
+        }
+        DateTrunc { timestamp, granularity, per_second, truncated } if truncated.is_nullable() => {
+            let truncated_non_null = bp.named_buffer("truncated_non_null", truncated.tag.non_nullable());
+            Rewrite::ReplaceWith(vec![
+                DateTrunc {
+                    timestamp: timestamp.forget_nullability(),
+                    granularity, per_second,
+                    truncated: truncated_non_null,
+                },
+                PropagateNullability {
+                    nullable: timestamp,
+                    data: truncated_non_null,
+                    nullable_data: truncated,
+                },
+            ])
+        }
         And { lhs, rhs, and } if and.is_nullable() => {
             let and_non_null = bp.named_buffer("and_non_null", and.tag.non_nullable());
             let mut ops = vec![And {
@@ -438,6 +567,10 @@ impl BufferProvider {
         self.named_buffer(name, EncodingType::I64).i64().unwrap()
     }
 
+    pub fn buffer_u64(&mut self, name: &'static str) -> BufferRef<u64> {
+        self.named_buffer(name, EncodingType::U64).u64().unwrap()
+    }
+
     pub fn buffer_u32(&mut self, name: &'static str) -> BufferRef<u32> {
         self.named_buffer(name, EncodingType::U32).u32().unwrap()
     }
@@ -458,6 +591,22 @@ impl BufferProvider {
         self.named_buffer(name, EncodingType::ValRows).val_rows().unwrap()
     }
 
+    pub fn buffer_val_sets<'a>(&mut self, name: &'static str) -> BufferRef<ValSets<'a>> {
+        self.named_buffer(name, EncodingType::ValSets).val_sets().unwrap()
+    }
+
+    pub fn buffer_sketches(&mut self, name: &'static str) -> BufferRef<Sketches> {
+        self.named_buffer(name, EncodingType::Sketches).sketches().unwrap()
+    }
+
+    pub fn buffer_float_lists(&mut self, name: &'static str) -> BufferRef<FloatLists> {
+        self.named_buffer(name, EncodingType::FloatLists).float_lists().unwrap()
+    }
+
+    pub fn buffer_welford_states(&mut self, name: &'static str) -> BufferRef<WelfordStates> {
+        self.named_buffer(name, EncodingType::WelfordStates).welford_states().unwrap()
+    }
+
     pub fn buffer_scalar_i64(&mut self, name: &'static str) -> BufferRef<Scalar<i64>> {
         self.named_buffer(name, EncodingType::ScalarI64).scalar_i64().unwrap()
     }
@@ -14,6 +14,17 @@ pub struct ColumnInfo {
     pub name: String,
 }
 
+/// Statement that removes data from a table.
+#[derive(Debug, Clone)]
+pub enum Mutation {
+    /// Removes the table with all its data and column names
+    DropTable { table: String, if_exists: bool },
+    /// Removes all rows from the table
+    Truncate { table: String },
+    /// Removes the rows of the table for which `filter` is true
+    Delete { table: String, filter: Expr },
+}
+
 /// NormalFormQuery observes the following invariants:
 /// - none of the expressions contain aggregation functions
 /// - if aggregate.len() > 0 then order_by.len() == 0 and vice versa
@@ -26,15 +37,163 @@ pub struct NormalFormQuery {
     pub filter: Expr,
     pub order_by: Vec<(Expr, bool)>,
     pub limit: LimitClause,
+    pub join: Option<Join>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Query {
     pub select: Vec<ColumnInfo>,
+    pub distinct: bool,
     pub table: String,
+    // Queries whose concatenated results make up the queried table, for subqueries in FROM, common table expressions
+    // and the branches of UNION ALL
+    pub subqueries: Vec<Query>,
     pub filter: Expr,
+    // Explicit grouping keys, if empty results are implicitly grouped by all non-aggregate projections
+    pub group_by: Vec<Expr>,
+    pub having: Option<Expr>,
     pub order_by: Vec<(Expr, bool)>,
     pub limit: LimitClause,
+    pub downsample: Option<Downsample>,
+    pub windows: Vec<Window>,
+    pub join: Option<Join>,
+}
+
+/// Joins the queried table with `table` on rows where `left_key` is equal to the `right_key` column of `table`.
+/// Columns of `table` are referenced as `alias.column`.
+#[derive(Debug, Clone)]
+pub struct Join {
+    pub kind: JoinKind,
+    pub table: String,
+    pub alias: String,
+    pub left_key: Expr,
+    pub right_key: String,
+    /// Contents of `table`, materialized before the query is executed
+    pub materialized: Option<Arc<JoinTable>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinKind {
+    Inner,
+    // Rows without a match in the joined table are retained, with all columns of the joined table set to null
+    Left,
+}
+
+impl Join {
+    pub fn qualified_name(&self, column: &str) -> String {
+        format!("{}.{}", self.alias, column)
+    }
+
+    /// Returns the column of the joined table referenced by `name`, if any.
+    pub fn joined_column<'b>(&self, name: &'b str) -> Option<&'b str> {
+        name.strip_prefix(self.alias.as_str())?.strip_prefix('.')
+    }
+
+    /// Adds the columns of the materialized joined table to `columns` under their qualified names.
+    pub fn add_columns(&self, columns: &mut HashMap<String, Arc<dyn DataSource>>) {
+        if let Some(table) = &self.materialized {
+            for (name, column) in &table.columns {
+                columns.insert(self.qualified_name(name), column.clone());
+            }
+        }
+    }
+}
+
+/// Reduces the query result to at most `max_points` rows that are representative of the plot of select column
+/// `column` over `x`.
+#[derive(Debug, Clone)]
+pub struct Downsample {
+    pub method: DownsampleMethod,
+    pub x: Expr,
+    pub column: usize,
+    pub max_points: usize,
+}
+
+/// Window function whose values replace those of select column `column`, which evaluates the argument of the
+/// function. Windows are evaluated over the full query result after grouping, so arguments and keys may contain
+/// aggregates.
+#[derive(Debug, Clone)]
+pub struct Window {
+    pub function: WindowFunction,
+    pub column: usize,
+    pub partition_by: Vec<Expr>,
+    pub order_by: Vec<(Expr, bool)>,
+    pub frame: WindowFrame,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WindowFunction {
+    RowNumber,
+    // Value `offset` rows before the current row in the same partition, or `default` if there is no such row
+    Lag(usize, RawVal),
+    // Value `offset` rows after the current row in the same partition, or `default` if there is no such row
+    Lead(usize, RawVal),
+    Sum,
+    Avg,
+    Min,
+    Max,
+    Count,
+    // `COUNT(*)`, number of rows in the frame
+    CountRows,
+}
+
+impl WindowFunction {
+    pub fn has_argument(&self) -> bool {
+        !matches!(self, WindowFunction::RowNumber | WindowFunction::CountRows)
+    }
+
+    /// Whether the values of the function have the same logical type as its argument
+    pub fn preserves_logical_type(&self) -> bool {
+        matches!(
+            self,
+            WindowFunction::Lag(..) | WindowFunction::Lead(..) | WindowFunction::Min | WindowFunction::Max
+        )
+    }
+}
+
+/// Rows of the partition that aggregate window functions are evaluated over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowFrame {
+    // If true, `CurrentRow` bounds include all rows with the same order keys as the current row (`RANGE` frames)
+    pub peers: bool,
+    pub start: FrameBound,
+    pub end: FrameBound,
+}
+
+impl WindowFrame {
+    /// Frame used without an explicit frame clause
+    pub fn implicit(ordered: bool) -> WindowFrame {
+        if ordered {
+            WindowFrame {
+                peers: true,
+                start: FrameBound::UnboundedPreceding,
+                end: FrameBound::CurrentRow,
+            }
+        } else {
+            WindowFrame {
+                peers: false,
+                start: FrameBound::UnboundedPreceding,
+                end: FrameBound::UnboundedFollowing,
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameBound {
+    UnboundedPreceding,
+    Preceding(usize),
+    CurrentRow,
+    Following(usize),
+    UnboundedFollowing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownsampleMethod {
+    // Largest-Triangle-Three-Buckets
+    Lttb,
+    // Minimum and maximum point of every bucket
+    MinMax,
 }
 
 #[derive(Debug, Clone)]
@@ -45,6 +204,7 @@ pub enum ResultColumn {
 
 impl NormalFormQuery {
     #[inline(never)] // produces more useful profiles
+    #[allow(clippy::too_many_arguments)]
     pub fn run<'a>(
         &self,
         columns: &'a HashMap<String, Arc<dyn DataSource>>,
@@ -53,9 +213,11 @@ impl NormalFormQuery {
         partition: usize,
         partition_range: Range<usize>,
         batch_size: usize,
+        cancellation: &CancellationToken,
     ) -> Result<(BatchResult<'a>, Option<String>), QueryError> {
         let limit = (self.limit.limit + self.limit.offset) as usize;
         let mut planner = QueryPlanner::default();
+        self.probe_join(columns, partition_range.len(), &mut planner)?;
 
         let (filter_plan, _) = QueryPlan::compile_expr(
             &self.filter,
@@ -170,7 +332,7 @@ impl NormalFormQuery {
         let mut executor = planner.prepare(vec![], batch_size, show)?;
         let mut results = executor.prepare(NormalFormQuery::column_data(columns));
         debug!("{:#}", &executor);
-        executor.run(partition_range.len(), &mut results, show)?;
+        executor.run(partition_range.len(), &mut results, show, Some(cancellation))?;
         let (columns, projection, _, order_by) = results.collect_aliased(&select, &[], &order_by);
 
         Ok((
@@ -194,6 +356,7 @@ impl NormalFormQuery {
     }
 
     #[inline(never)] // produces more useful profiles
+    #[allow(clippy::too_many_arguments)]
     pub fn run_aggregate<'a>(
         &self,
         columns: &'a HashMap<String, Arc<dyn DataSource>>,
@@ -202,8 +365,10 @@ impl NormalFormQuery {
         partition: usize,
         partition_range: Range<usize>,
         batch_size: usize,
+        cancellation: &CancellationToken,
     ) -> Result<(BatchResult<'a>, Option<String>), QueryError> {
         let mut qp = QueryPlanner::default();
+        self.probe_join(columns, partition_range.len(), &mut qp)?;
 
         // Filter
         let (filter_plan, _) = QueryPlan::compile_expr(
@@ -259,21 +424,37 @@ impl NormalFormQuery {
         let mut selector = None;
         let mut selector_index = None;
         for (i, &(aggregator, ref col_info)) in self.aggregate.iter().enumerate() {
-            let (plan, plan_type) = QueryPlan::compile_expr(
-                &col_info.expr,
-                filter,
-                columns,
-                partition_range.len(),
-                &mut qp,
-            )?;
-            let (aggregate, t) = query_plan::prepare_aggregation(
-                plan,
-                plan_type,
-                grouping_key,
-                aggregation_cardinality,
-                aggregator,
-                &mut qp,
-            )?;
+            let (plan, aggregate, t) = if let Expr::Func2(Func2Type::Pair, x, y) = &col_info.expr {
+                let x = QueryPlan::compile_expr(x, filter, columns, partition_range.len(), &mut qp)?;
+                let y = QueryPlan::compile_expr(y, filter, columns, partition_range.len(), &mut qp)?;
+                let plan = x.0;
+                let (aggregate, t) = query_plan::prepare_bivariate_aggregation(
+                    x,
+                    y,
+                    grouping_key,
+                    aggregation_cardinality,
+                    aggregator,
+                    &mut qp,
+                )?;
+                (plan, aggregate, t)
+            } else {
+                let (plan, plan_type) = QueryPlan::compile_expr(
+                    &col_info.expr,
+                    filter,
+                    columns,
+                    partition_range.len(),
+                    &mut qp,
+                )?;
+                let (aggregate, t) = query_plan::prepare_aggregation(
+                    plan,
+                    plan_type,
+                    grouping_key,
+                    aggregation_cardinality,
+                    aggregator,
+                    &mut qp,
+                )?;
+                (plan, aggregate, t)
+            };
             // PERF: if summation column is strictly positive, can use sum as well
             if aggregator == Aggregator::Count && !plan.is_nullable() {
                 selector = Some((aggregate, t.encoding_type()));
@@ -309,7 +490,16 @@ impl NormalFormQuery {
                     | Aggregator::MinI64
                     | Aggregator::SumF64
                     | Aggregator::MaxF64
-                    | Aggregator::MinF64 => {
+                    | Aggregator::MinF64
+                    | Aggregator::CountDistinct
+                    | Aggregator::ApproxCountDistinct
+                    | Aggregator::ApproxQuantile(_)
+                    | Aggregator::PercentileCont(_)
+                    | Aggregator::Variance
+                    | Aggregator::VariancePop
+                    | Aggregator::StdDev
+                    | Aggregator::StdDevPop
+                    | Aggregator::Corr => {
                         qp.compact(aggregate, selector)
                     }
                     Aggregator::Count => {
@@ -421,7 +611,7 @@ impl NormalFormQuery {
         let mut executor = qp.prepare(vec![], batch_size, show)?;
         let mut results = executor.prepare(NormalFormQuery::column_data(columns));
         debug!("{:#}", &executor);
-        executor.run(partition_range.len(), &mut results, show)?;
+        executor.run(partition_range.len(), &mut results, show, Some(cancellation))?;
         let (columns, projection, aggregations, _) = results.collect_aliased(
             &grouping_columns.iter().map(|s| s.any()).collect::<Vec<_>>(),
             &aggregation_cols
@@ -458,6 +648,31 @@ impl NormalFormQuery {
         }
     }
 
+    // Looks up the rows of the joined table that match each row of the partition.
+    // Columns of the joined table are then read at these rows (see `QueryPlan::compile_expr`).
+    fn probe_join(
+        &self,
+        columns: &HashMap<String, Arc<dyn DataSource>>,
+        partition_len: usize,
+        planner: &mut QueryPlanner,
+    ) -> Result<(), QueryError> {
+        let join = match &self.join {
+            Some(join) => join,
+            None => return Ok(()),
+        };
+        let table = join
+            .materialized
+            .as_ref()
+            .ok_or_else(|| fatal!("Joined table {} has not been materialized", join.table))?;
+        let (keys, keys_type) =
+            QueryPlan::compile_expr(&join.left_key, Filter::None, columns, partition_len, planner)?;
+        let keys = keys_type.codec.decode(keys, planner);
+        let keys = planner.cast(keys, EncodingType::Val).val()?;
+        let rows = planner.join_probe(keys, table.index.clone(), table.len);
+        planner.join_rows = Some((join.alias.clone(), rows));
+        Ok(())
+    }
+
     fn column_data(
         columns: &HashMap<String, Arc<dyn DataSource>>,
     ) -> HashMap<String, Vec<&dyn Data>> {
@@ -472,6 +687,12 @@ impl Query {
     pub fn normalize(
         &self,
     ) -> Result<(NormalFormQuery, Option<NormalFormQuery>, Vec<ResultColumn>), QueryError> {
+        if self.distinct {
+            return self.normalize_distinct();
+        }
+        if !self.group_by.is_empty() || self.having.is_some() {
+            return self.normalize_explicit_grouping();
+        }
         let mut final_projection = Vec::<ColumnInfo>::new();
         let mut select = Vec::<ColumnInfo>::new();
         let mut aggregate = Vec::new();
@@ -541,6 +762,7 @@ impl Query {
                         limit: u64::MAX,
                         offset: 0,
                     },
+                    join: self.join.clone(),
                 },
                 Some(NormalFormQuery {
                     projection: final_projection,
@@ -548,6 +770,7 @@ impl Query {
                     filter: Expr::Const(RawVal::Int(1)),
                     order_by: final_order_by,
                     limit: self.limit.clone(),
+                    join: None,
                 }),
                 (0..final_projection_len).map(ResultColumn::Proj).collect(),
             )
@@ -559,6 +782,7 @@ impl Query {
                     filter: self.filter.clone(),
                     order_by: self.order_by.clone(),
                     limit: self.limit.clone(),
+                    join: self.join.clone(),
                 },
                 None,
                 final_select_ordering,
@@ -566,6 +790,164 @@ impl Query {
         })
     }
 
+    // SELECT DISTINCT is rewritten into a grouping on all projections.
+    fn normalize_distinct(
+        &self,
+    ) -> Result<(NormalFormQuery, Option<NormalFormQuery>, Vec<ResultColumn>), QueryError> {
+        if !self.group_by.is_empty() || self.having.is_some() {
+            bail!(QueryError::NotImplemented, "DISTINCT combined with GROUP BY or HAVING")
+        }
+        let mut query = self.clone();
+        query.distinct = false;
+        for col_info in &self.select {
+            if Query::ensure_no_aggregates(&col_info.expr).is_err() {
+                bail!(QueryError::NotImplemented, "DISTINCT combined with aggregate functions")
+            }
+            query.group_by.push(col_info.expr.clone());
+        }
+        query.normalize_explicit_grouping()
+    }
+
+    // With explicit GROUP BY and/or HAVING clauses, the main phase aggregates over all grouping keys
+    // and the final pass evaluates projections, HAVING predicate and ORDER BY over the grouped results.
+    fn normalize_explicit_grouping(
+        &self,
+    ) -> Result<(NormalFormQuery, Option<NormalFormQuery>, Vec<ResultColumn>), QueryError> {
+        let mut grouping_keys = Vec::with_capacity(self.group_by.len());
+        for expr in &self.group_by {
+            // GROUP BY 1 refers to the first column in the SELECT clause
+            let expr = match *expr {
+                Expr::Const(RawVal::Int(i)) => match self.select.get((i as usize).wrapping_sub(1)) {
+                    Some(col_info) => col_info.expr.clone(),
+                    None => bail!(
+                        QueryError::TypeError,
+                        "GROUP BY position {} is not in select list",
+                        i
+                    ),
+                },
+                _ => expr.clone(),
+            };
+            if let Err(err) = Query::ensure_no_aggregates(&expr) {
+                bail!(QueryError::TypeError, "Aggregate functions are not allowed in GROUP BY: {}", err)
+            }
+            if !grouping_keys.contains(&expr) {
+                grouping_keys.push(expr);
+            }
+        }
+        let select = grouping_keys
+            .iter()
+            .enumerate()
+            .map(|(i, expr)| ColumnInfo {
+                expr: expr.clone(),
+                name: format!("_cs{}", i),
+            })
+            .collect::<Vec<_>>();
+
+        let mut aggregate = Vec::new();
+        let mut aggregate_colnames = Vec::new();
+        let mut final_projection = Vec::new();
+        // Grouping keys are replaced before extracting aggregates, so that user columns are never confused with the
+        // generated aggregate columns
+        for col_info in &self.select {
+            let (full_expr, aggregates) = Query::extract_aggregators(
+                &Query::replace_grouping_keys(&col_info.expr, &grouping_keys)?,
+                &mut aggregate_colnames,
+                &col_info.name,
+            )?;
+            aggregate.extend(aggregates);
+            final_projection.push(ColumnInfo {
+                expr: full_expr,
+                name: col_info.name.clone(),
+            });
+        }
+        let final_filter = match &self.having {
+            Some(having) => {
+                let (full_expr, aggregates) = Query::extract_aggregators(
+                    &Query::replace_grouping_keys(having, &grouping_keys)?,
+                    &mut aggregate_colnames,
+                    "HAVING",
+                )?;
+                aggregate.extend(aggregates);
+                full_expr
+            }
+            None => Expr::Const(RawVal::Int(1)),
+        };
+        let mut final_order_by = Vec::new();
+        for (expr, desc) in &self.order_by {
+            let (full_expr, aggregates) = Query::extract_aggregators(
+                &Query::replace_grouping_keys(expr, &grouping_keys)?,
+                &mut aggregate_colnames,
+                "INTERMEDIARY_COL",
+            )?;
+            aggregate.extend(aggregates);
+            final_order_by.push((full_expr, *desc));
+        }
+        // Grouping is only performed by aggregation queries
+        if aggregate.is_empty() {
+            aggregate.push((
+                Aggregator::Count,
+                ColumnInfo {
+                    expr: Expr::Const(RawVal::Int(1)),
+                    name: "_group_count".to_string(),
+                },
+            ));
+        }
+
+        let final_projection_len = final_projection.len();
+        Ok((
+            NormalFormQuery {
+                projection: select,
+                aggregate,
+                filter: self.filter.clone(),
+                order_by: vec![],
+                limit: LimitClause {
+                    limit: u64::MAX,
+                    offset: 0,
+                },
+                join: self.join.clone(),
+            },
+            Some(NormalFormQuery {
+                projection: final_projection,
+                aggregate: vec![],
+                filter: final_filter,
+                order_by: final_order_by,
+                limit: self.limit.clone(),
+                join: None,
+            }),
+            (0..final_projection_len).map(ResultColumn::Proj).collect(),
+        ))
+    }
+
+    // Replaces all occurrences of grouping keys with references to the corresponding grouping column
+    // and ensures that the resulting expression does not reference any other columns outside of aggregates
+    // For example in SELECT a + 1, SUM(b) FROM ... GROUP BY a, the expression a + 1 becomes _cs0 + 1
+    fn replace_grouping_keys(expr: &Expr, grouping_keys: &[Expr]) -> Result<Expr, QueryError> {
+        if let Some(i) = grouping_keys.iter().position(|key| key == expr) {
+            return Ok(Expr::ColName(format!("_cs{}", i)));
+        }
+        Ok(match expr {
+            Expr::ColName(name) => bail!(
+                QueryError::TypeError,
+                "Column `{}` must appear in the GROUP BY clause or be used in an aggregate function",
+                name
+            ),
+            Expr::Func1(t, expr) => Expr::Func1(*t, Box::new(Query::replace_grouping_keys(expr, grouping_keys)?)),
+            Expr::Func2(t, expr1, expr2) => Expr::Func2(
+                *t,
+                Box::new(Query::replace_grouping_keys(expr1, grouping_keys)?),
+                Box::new(Query::replace_grouping_keys(expr2, grouping_keys)?),
+            ),
+            Expr::FuncN(t, exprs) => Expr::FuncN(
+                *t,
+                exprs
+                    .iter()
+                    .map(|expr| Query::replace_grouping_keys(expr, grouping_keys))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Expr::Const(_) | Expr::Aggregate(_, _) => expr.clone(),
+        })
+    }
+
     // Finds any aggregator expressions contained within this expression and returns them
     // For example in SELECT a + SUM(b) + COUNT(1) FROM ..., the SUM(b) and COUNT(1) are returned
     pub fn extract_aggregators(
@@ -603,6 +985,16 @@ impl Query {
                     aggregates1,
                 )
             }
+            Expr::FuncN(t, exprs) => {
+                let mut args = Vec::with_capacity(exprs.len());
+                let mut aggregates = vec![];
+                for expr in exprs {
+                    let (expr, aggs) = Query::extract_aggregators(expr, column_names, alias)?;
+                    args.push(expr);
+                    aggregates.extend(aggs);
+                }
+                (Expr::FuncN(*t, args), aggregates)
+            }
             Expr::Const(_) | Expr::ColName(_) => (expr.clone(), vec![]),
         })
     }
@@ -619,6 +1011,11 @@ impl Query {
                 Query::ensure_no_aggregates(expr1)?;
                 Query::ensure_no_aggregates(expr2)?;
             }
+            Expr::FuncN(_, exprs) => {
+                for expr in exprs {
+                    Query::ensure_no_aggregates(expr)?;
+                }
+            }
             Expr::Const(_) | Expr::ColName(_) => (),
         };
         Ok(())
@@ -637,26 +1034,136 @@ impl Query {
         for col_info in &self.select {
             col_info.expr.add_colnames(&mut colnames);
         }
+        for expr in &self.group_by {
+            expr.add_colnames(&mut colnames);
+        }
+        if let Some(having) = &self.having {
+            having.add_colnames(&mut colnames);
+        }
         for expr in &self.order_by {
             expr.0.add_colnames(&mut colnames);
         }
         self.filter.add_colnames(&mut colnames);
+        for window in &self.windows {
+            for expr in &window.partition_by {
+                expr.add_colnames(&mut colnames);
+            }
+            for (expr, _) in &window.order_by {
+                expr.add_colnames(&mut colnames);
+            }
+        }
+        if let Some(join) = &self.join {
+            join.left_key.add_colnames(&mut colnames);
+            // Columns of the joined table are materialized separately
+            colnames.retain(|name| join.joined_column(name).is_none());
+        }
         colnames
     }
 
+    /// Attaches the materialized contents of the joined table, and resolves references to its columns that are not
+    /// qualified with the table name or alias. `columns` are the names of all columns in the queried table.
+    pub fn resolve_join(&mut self, table: JoinTable, columns: &[String]) -> Result<(), QueryError> {
+        let (alias, right_key, using) = match &self.join {
+            Some(join) => (
+                join.alias.clone(),
+                join.right_key.clone(),
+                // A join key that has the same name in both tables (e.g. in a `USING` clause) refers to the queried table
+                join.left_key == Expr::ColName(join.right_key.clone()),
+            ),
+            None => return Ok(()),
+        };
+        let left_columns = columns.iter().map(String::as_str).collect::<HashSet<_>>();
+        for name in self.find_referenced_cols() {
+            if table.columns.contains_key(&name)
+                && left_columns.contains(name.as_str())
+                && !(using && name == right_key)
+            {
+                bail!(
+                    QueryError::ParseError,
+                    "Column reference {} is ambiguous, qualify it with the name or alias of the table",
+                    name
+                )
+            }
+        }
+        self.rename_columns(&|name| {
+            if table.columns.contains_key(name) && !left_columns.contains(name) {
+                Some(format!("{}.{}", alias, name))
+            } else {
+                None
+            }
+        });
+        let join = self.join.as_mut().unwrap();
+        if join.kind == JoinKind::Inner {
+            // Rows without a match are the ones for which the join key of the joined table is null
+            let matched = Expr::func1(
+                Func1Type::IsNotNull,
+                Expr::ColName(join.qualified_name(&right_key)),
+            );
+            self.filter = match self.filter {
+                Expr::Const(RawVal::Int(1)) => matched,
+                _ => Expr::func(Func2Type::And, self.filter.clone(), matched),
+            };
+        }
+        join.materialized = Some(Arc::new(table));
+        Ok(())
+    }
+
+    /// Replaces every column name referenced by the query for which `rename` returns a new name.
+    pub fn rename_columns(&mut self, rename: &dyn Fn(&str) -> Option<String>) {
+        for col_info in &mut self.select {
+            col_info.expr.rename_columns(rename);
+        }
+        for expr in &mut self.group_by {
+            expr.rename_columns(rename);
+        }
+        if let Some(having) = &mut self.having {
+            having.rename_columns(rename);
+        }
+        for (expr, _) in &mut self.order_by {
+            expr.rename_columns(rename);
+        }
+        self.filter.rename_columns(rename);
+        if let Some(downsample) = &mut self.downsample {
+            downsample.x.rename_columns(rename);
+        }
+        for window in &mut self.windows {
+            for expr in &mut window.partition_by {
+                expr.rename_columns(rename);
+            }
+            for (expr, _) in &mut window.order_by {
+                expr.rename_columns(rename);
+            }
+        }
+        if let Some(join) = &mut self.join {
+            join.left_key.rename_columns(rename);
+        }
+    }
+
     pub fn read_column(table: &str, column: &str) -> Query {
+        Query::project(table, Expr::ColName(column.to_string()))
+    }
+
+    /// Query that evaluates `expr` for every row of `table`
+    pub fn project(table: &str, expr: Expr) -> Query {
         Query {
             select: vec![ColumnInfo {
-                expr: Expr::ColName(column.to_string()),
+                expr,
                 name: "col".to_string(),
             }],
+            distinct: false,
             table: table.to_string(),
+            subqueries: vec![],
             filter: Expr::Const(RawVal::Int(1)),
+            group_by: vec![],
+            having: None,
             order_by: vec![],
             limit: LimitClause {
                 limit: u64::MAX,
                 offset: 0,
             },
+            downsample: None,
+            windows: vec![],
+            join: None,
         }
     }
 }
@@ -2,10 +2,12 @@
 #![allow(clippy::nonstandard_macro_braces, clippy::unused_unit)]
 use chrono::{DateTime, Datelike};
 use locustdb_derive::ASTBuilder;
+use locustdb_serialization::logical_type::{LogicalType, TimeUnit};
+use ordered_float::OrderedFloat;
 use regex::Regex;
 
 use crate::engine::*;
-use crate::ingest::raw_val::RawVal;
+use crate::ingest::raw_val::{parse_timestamp, RawVal};
 use crate::mem_store::*;
 use crate::syntax::expression::*;
 use std::collections::HashMap;
@@ -40,6 +42,29 @@ pub enum QueryPlan {
         #[output(t = "base=data;null=_always")]
         nullable_data: TypedBufferRef,
     },
+    /// Selects the elements of `lhs` where `cond` is nonzero and the elements of `rhs` otherwise.
+    Blend {
+        cond: BufferRef<u8>,
+        lhs: TypedBufferRef,
+        rhs: TypedBufferRef,
+        #[output(t = "base=lhs")]
+        blended: TypedBufferRef,
+    },
+    /// Selects the null map entries of `lhs` where `cond` is nonzero and those of `rhs` otherwise.
+    BlendNullMaps {
+        cond: BufferRef<u8>,
+        lhs: TypedBufferRef,
+        rhs: TypedBufferRef,
+        #[output]
+        present: BufferRef<u8>,
+    },
+    /// Outputs a vector with the same length as `like` with all values equal to `scalar`.
+    ExpandScalar {
+        scalar: TypedBufferRef,
+        like: TypedBufferRef,
+        #[output(t = "base=provided")]
+        expanded: TypedBufferRef,
+    },
     /// Combines the null maps of two vectors, setting the result to null if any if the inputs were null.
     CombineNullMaps {
         lhs: TypedBufferRef,
@@ -65,6 +90,13 @@ pub enum QueryPlan {
         #[output(t = "base=i64;null=input")]
         floor: TypedBufferRef,
     },
+    /// Applies `function` to all elements of `input`, which is either F64 or I64 (see `MathFunction`).
+    Math {
+        input: TypedBufferRef,
+        function: MathFunction,
+        #[output(t = "base=provided")]
+        output: TypedBufferRef,
+    },
     /// Converts NullableI64, NullableStr, or NullableF64 into a representation where nulls are encoded as part
     /// of the data (i64 with i64::MAX representing null for NullableI64, Option<&str> for NullableStr, and special NaN value F64_NULL representing null for NullableF64).
     FuseNulls {
@@ -269,6 +301,36 @@ pub enum QueryPlan {
         #[output(t = "base=provided")]
         aggregate: TypedBufferRef,
     },
+    AggregateDistinct {
+        plan: BufferRef<Val<'static>>,
+        grouping_key: TypedBufferRef,
+        max_index: BufferRef<Scalar<i64>>,
+        #[output]
+        distinct: BufferRef<ValSets<'static>>,
+    },
+    AggregateSketch {
+        plan: BufferRef<Val<'static>>,
+        grouping_key: TypedBufferRef,
+        max_index: BufferRef<Scalar<i64>>,
+        aggregator: Aggregator,
+        #[output]
+        sketches: BufferRef<Sketches>,
+    },
+    AggregateValues {
+        plan: BufferRef<Val<'static>>,
+        grouping_key: TypedBufferRef,
+        max_index: BufferRef<Scalar<i64>>,
+        #[output]
+        values: BufferRef<FloatLists>,
+    },
+    AggregateWelford {
+        x: BufferRef<Val<'static>>,
+        y: BufferRef<Val<'static>>,
+        grouping_key: TypedBufferRef,
+        max_index: BufferRef<Scalar<i64>>,
+        #[output]
+        moments: BufferRef<WelfordStates>,
+    },
     CheckedAggregate {
         plan: TypedBufferRef,
         grouping_key: TypedBufferRef,
@@ -396,6 +458,24 @@ pub enum QueryPlan {
         #[output]
         modulo: BufferRef<Nullable<i64>>,
     },
+    Power {
+        lhs: TypedBufferRef,
+        rhs: TypedBufferRef,
+        #[output(t = "base=provided;null=lhs,rhs")]
+        power: TypedBufferRef,
+    },
+    Greatest {
+        lhs: TypedBufferRef,
+        rhs: TypedBufferRef,
+        #[output(t = "base=provided;null=lhs,rhs")]
+        greatest: TypedBufferRef,
+    },
+    Least {
+        lhs: TypedBufferRef,
+        rhs: TypedBufferRef,
+        #[output(t = "base=provided;null=lhs,rhs")]
+        least: TypedBufferRef,
+    },
     And {
         lhs: TypedBufferRef,
         rhs: TypedBufferRef,
@@ -413,11 +493,44 @@ pub enum QueryPlan {
         #[output]
         not: BufferRef<u8>,
     },
+    /// Date functions take timestamps with `per_second` ticks per second.
     ToYear {
         timestamp: TypedBufferRef,
+        per_second: i64,
         #[output(t = "base=i64;null=timestamp")]
         year: TypedBufferRef,
     },
+    ToMonth {
+        timestamp: TypedBufferRef,
+        per_second: i64,
+        #[output(t = "base=i64;null=timestamp")]
+        month: TypedBufferRef,
+    },
+    ToDayOfWeek {
+        timestamp: TypedBufferRef,
+        per_second: i64,
+        #[output(t = "base=i64;null=timestamp")]
+        day_of_week: TypedBufferRef,
+    },
+    ToHour {
+        timestamp: TypedBufferRef,
+        per_second: i64,
+        #[output(t = "base=i64;null=timestamp")]
+        hour: TypedBufferRef,
+    },
+    TimeBucket {
+        timestamp: TypedBufferRef,
+        width: i64,
+        #[output(t = "base=i64;null=timestamp")]
+        bucket: TypedBufferRef,
+    },
+    DateTrunc {
+        timestamp: TypedBufferRef,
+        granularity: TimeGranularity,
+        per_second: i64,
+        #[output(t = "base=i64;null=timestamp")]
+        truncated: TypedBufferRef,
+    },
     Regex {
         plan: BufferRef<&'static str>,
         regex: String,
@@ -429,6 +542,75 @@ pub enum QueryPlan {
         #[output]
         length: BufferRef<i64>,
     },
+    StartsWith {
+        string: BufferRef<&'static str>,
+        prefix: String,
+        #[output]
+        starts_with: BufferRef<u8>,
+    },
+    /// Determines whether each of `strings` is contained in `set`.
+    IsInStrings {
+        strings: BufferRef<&'static str>,
+        set: Vec<String>,
+        #[output]
+        is_in: BufferRef<u8>,
+    },
+    /// Determines whether each of `values` is contained in `set`.
+    IsInI64 {
+        values: BufferRef<i64>,
+        set: Vec<i64>,
+        #[output]
+        is_in: BufferRef<u8>,
+    },
+    /// Determines whether the dictionary entries referenced by `indices` are contained in `set`.
+    DictIsIn {
+        indices: TypedBufferRef,
+        offset_len: BufferRef<u64>,
+        backing_store: BufferRef<u8>,
+        set: Vec<String>,
+        #[output]
+        is_in: BufferRef<u8>,
+    },
+    /// Applies `function` to all `strings`, producing dictionary sections that can be resolved with `DictLookup`.
+    StringMap {
+        strings: BufferRef<&'static str>,
+        function: StringFunction,
+        #[output]
+        offset_len: BufferRef<u64>,
+        #[output]
+        backing_store: BufferRef<u8>,
+    },
+    /// Converts `vals` to integers or floats, values that cannot be converted are null unless `strict` is set.
+    ParseVals {
+        vals: BufferRef<Val<'static>>,
+        strict: bool,
+        #[output(t = "base=provided;null=_always")]
+        parsed: TypedBufferRef,
+    },
+    /// Formats `vals` as strings, producing dictionary sections that can be resolved with `DictLookup`.
+    FormatVals {
+        vals: BufferRef<Val<'static>>,
+        #[output]
+        offset_len: BufferRef<u64>,
+        #[output]
+        backing_store: BufferRef<u8>,
+    },
+    /// Combines `strings` formatted by `FormatVals` with the null map of `vals`.
+    NullableFormattedVals {
+        vals: BufferRef<Val<'static>>,
+        strings: BufferRef<&'static str>,
+        #[output(t = "base=str;null=_always")]
+        nullable: TypedBufferRef,
+    },
+    /// Concatenates `lhs` and `rhs`, producing dictionary sections that can be resolved with `DictLookup`.
+    ConcatStrings {
+        lhs: BufferRef<&'static str>,
+        rhs: BufferRef<&'static str>,
+        #[output]
+        offset_len: BufferRef<u64>,
+        #[output]
+        backing_store: BufferRef<u8>,
+    },
     /// Outputs a vector of indices from `0..plan.len()`
     Indices {
         plan: TypedBufferRef,
@@ -454,6 +636,15 @@ pub enum QueryPlan {
         #[output]
         top_n: BufferRef<usize>,
     },
+    /// Outputs the row of the broadcast join table with a key matching each element in `keys`, or `unmatched`.
+    JoinProbe {
+        keys: BufferRef<Val<'static>>,
+        #[nohash]
+        index: Arc<JoinKeyIndex>,
+        unmatched: usize,
+        #[output]
+        rows: BufferRef<usize>,
+    },
     /// Outputs all elements in `plan` where the index corresponds to an entry in `indices`.
     Select {
         plan: TypedBufferRef,
@@ -747,11 +938,91 @@ pub fn prepare_aggregation(
                 Type::unencoded(aggregate_type),
             )
         }
+        Aggregator::CountDistinct => {
+            // Distinct values are collected into per-group sets which can be merged across batches.
+            // Sets are only reduced to counts once all partial results have been combined.
+            plan = plan_type.codec.decode(plan, planner);
+            let vals = planner.cast(plan, EncodingType::Val).val()?;
+            (
+                planner.aggregate_distinct(vals, grouping_key, max_index).into(),
+                Type::unencoded(BasicType::Integer),
+            )
+        }
+        Aggregator::ApproxCountDistinct | Aggregator::ApproxQuantile(_) => {
+            let aggregate_type = if aggregator == Aggregator::ApproxCountDistinct {
+                BasicType::Integer
+            } else if matches!(decoded_type, BasicType::String | BasicType::NullableString) {
+                bail!(QueryError::TypeError, "APPROX_QUANTILE is not defined for strings")
+            } else {
+                BasicType::NullableFloat
+            };
+            // Sketches are merged across batches and only converted into estimates once all partial results have been combined.
+            plan = plan_type.codec.decode(plan, planner);
+            let vals = planner.cast(plan, EncodingType::Val).val()?;
+            (
+                planner.aggregate_sketch(vals, grouping_key, max_index, aggregator).into(),
+                Type::unencoded(aggregate_type),
+            )
+        }
+        Aggregator::PercentileCont(_) => {
+            if matches!(decoded_type, BasicType::String | BasicType::NullableString) {
+                bail!(QueryError::TypeError, "PERCENTILE_CONT is not defined for strings")
+            }
+            // All values of each group are retained and only sorted once all partial results have been combined.
+            plan = plan_type.codec.decode(plan, planner);
+            let vals = planner.cast(plan, EncodingType::Val).val()?;
+            (
+                planner.aggregate_values(vals, grouping_key, max_index).into(),
+                Type::unencoded(BasicType::NullableFloat),
+            )
+        }
+        Aggregator::Variance | Aggregator::VariancePop | Aggregator::StdDev | Aggregator::StdDevPop => {
+            if matches!(decoded_type, BasicType::String | BasicType::NullableString) {
+                bail!(QueryError::TypeError, "{:?} is not defined for strings", aggregator)
+            }
+            // Univariate moments are tracked as the moments of the pair (x, x)
+            plan = plan_type.codec.decode(plan, planner);
+            let vals = planner.cast(plan, EncodingType::Val).val()?;
+            (
+                planner.aggregate_welford(vals, vals, grouping_key, max_index).into(),
+                Type::unencoded(BasicType::NullableFloat),
+            )
+        }
+        Aggregator::Corr => bail!(QueryError::NotImplemented, "CORR requires two arguments"),
         Aggregator::SumF64 => panic!("All sums are represented as SumI64 by the parser since it does not have access to type information"),
         Aggregator::MaxF64 | Aggregator::MinF64 => panic!("All max/min are represented as MaxI64/MaxF64 by the parser since it does not have access to type information"),
     })
 }
 
+/// Plans aggregators that take two arguments, e.g. CORR(x, y).
+pub fn prepare_bivariate_aggregation(
+    (plan_x, type_x): (TypedBufferRef, Type),
+    (plan_y, type_y): (TypedBufferRef, Type),
+    grouping_key: TypedBufferRef,
+    max_index: BufferRef<Scalar<i64>>,
+    aggregator: Aggregator,
+    planner: &mut QueryPlanner,
+) -> Result<(TypedBufferRef, Type), QueryError> {
+    match aggregator {
+        Aggregator::Corr => {
+            for t in [&type_x, &type_y] {
+                if matches!(t.decoded, BasicType::String | BasicType::NullableString) {
+                    bail!(QueryError::TypeError, "CORR is not defined for strings")
+                }
+            }
+            let plan_x = type_x.codec.decode(plan_x, planner);
+            let plan_y = type_y.codec.decode(plan_y, planner);
+            let x = planner.cast(plan_x, EncodingType::Val).val()?;
+            let y = planner.cast(plan_y, EncodingType::Val).val()?;
+            Ok((
+                planner.aggregate_welford(x, y, grouping_key, max_index).into(),
+                Type::unencoded(BasicType::NullableFloat),
+            ))
+        }
+        _ => bail!(QueryError::NotImplemented, "{:?} with two arguments", aggregator),
+    }
+}
+
 pub fn order_preserving(
     (plan, t): (TypedBufferRef, Type),
     planner: &mut QueryPlanner,
@@ -799,6 +1070,32 @@ impl Function2 {
         }
     }
 
+    /// Function that returns an integer if both arguments are integers and `int_factory` is specified and a
+    /// float otherwise, or null if either argument is null.
+    pub fn numeric_ops(int_factory: Option<Factory>, float_factory: Factory) -> Vec<Function2> {
+        let mut float_signatures = vec![
+            (BasicType::Float, BasicType::Integer),
+            (BasicType::Integer, BasicType::Float),
+            (BasicType::Float, BasicType::Float),
+        ];
+        let mut ops = vec![];
+        match int_factory {
+            Some(factory) => ops.push(Function2::integer_op(factory)),
+            None => float_signatures.push((BasicType::Integer, BasicType::Integer)),
+        }
+        ops.push(Function2 {
+            factory: float_factory,
+            input_type_signatures: float_signatures,
+            type_out: Type::unencoded(BasicType::Float).mutable(),
+            encoding_invariance: false,
+        });
+        for t in [BasicType::Float, BasicType::Integer, BasicType::Null] {
+            ops.push(Function2::forward_left_null(t));
+            ops.push(Function2::forward_right_null(t));
+        }
+        ops
+    }
+
     pub fn forward_left_null(t: BasicType) -> Function2 {
         Function2 {
             factory: Box::new(|_, lhs, _| lhs),
@@ -885,6 +1182,24 @@ fn function2_registry() -> HashMap<Func2Type, Vec<Function2>> {
                 qp.checked_modulo(lhs, rhs)
             }))],
         ),
+        (
+            Func2Type::Pow,
+            Function2::numeric_ops(None, Box::new(|qp, lhs, rhs| qp.power(lhs, rhs, EncodingType::F64))),
+        ),
+        (
+            Func2Type::Greatest,
+            Function2::numeric_ops(
+                Some(Box::new(|qp, lhs, rhs| qp.greatest(lhs, rhs, EncodingType::I64))),
+                Box::new(|qp, lhs, rhs| qp.greatest(lhs, rhs, EncodingType::F64)),
+            ),
+        ),
+        (
+            Func2Type::Least,
+            Function2::numeric_ops(
+                Some(Box::new(|qp, lhs, rhs| qp.least(lhs, rhs, EncodingType::I64))),
+                Box::new(|qp, lhs, rhs| qp.least(lhs, rhs, EncodingType::F64)),
+            ),
+        ),
         (
             Func2Type::LT,
             vec![
@@ -1160,25 +1475,14 @@ impl QueryPlan {
                         t.decoded = decoded;
                         plan = fixed_width;
                     }
+                    if let Some(rows) = planner.join_rows(name) {
+                        // Aligns column of joined table with the rows of the partition
+                        plan = planner.select(plan, rows);
+                    }
                     plan = filter.apply_filter(planner, plan);
                     (plan, t)
                 }
-                None => {
-                    let plan = match filter {
-                        Filter::None => planner.null_vec(column_len, EncodingType::Null),
-                        Filter::U8(filter) => {
-                            planner.null_vec_like(filter.into(), 1, EncodingType::Null)
-                        }
-                        Filter::NullableU8(filter) => {
-                            planner.null_vec_like(filter.into(), 2, EncodingType::Null)
-                        }
-                        Filter::Null => planner.null_vec(0, EncodingType::Null),
-                        Filter::Indices(filter) => {
-                            planner.null_vec_like(filter.into(), 0, EncodingType::Null)
-                        }
-                    };
-                    (plan, Type::unencoded(BasicType::Null))
-                }
+                None => (null_vec(filter, column_len, planner), Type::unencoded(BasicType::Null)),
             },
             Func2(Or, ref lhs, ref rhs) => {
                 let (plan_lhs, type_lhs) =
@@ -1312,12 +1616,86 @@ impl QueryPlan {
                     regex
                 ),
             },
+            Func2(StartsWith, box Const(RawVal::Str(ref string)), box Const(RawVal::Str(ref prefix))) => {
+                QueryPlan::compile_expr(
+                    &Const(RawVal::Int(string.starts_with(prefix.as_str()) as i64)),
+                    filter,
+                    columns,
+                    column_len,
+                    planner,
+                )?
+            }
+            Func2(StartsWith, ref expr, ref prefix) => match prefix {
+                box Const(RawVal::Str(prefix)) => {
+                    let (plan, t) =
+                        QueryPlan::compile_expr(expr, filter, columns, column_len, planner)?;
+                    match t.decoded {
+                        BasicType::String => {}
+                        BasicType::Null => return Ok((plan, t)),
+                        _ => bail!(QueryError::TypeError, "Expected expression of type `String` as first argument to starts_with. Actual: {:?}", t),
+                    }
+                    let plan = t.codec.decode(plan, planner);
+                    (planner.starts_with(plan.str()?, prefix).into(), Type::bit_vec())
+                }
+                _ => bail!(
+                    QueryError::TypeError,
+                    "Expected string constant as second argument to `starts_with`, actual: {:?}",
+                    prefix
+                ),
+            },
+            FuncN(FuncNType::Concat, ref args) => {
+                QueryPlan::compile_concat(args, filter, columns, column_len, planner)?
+            }
+            FuncN(FuncNType::If, ref args) => {
+                QueryPlan::compile_if(&args[0], &args[1], &args[2], filter, columns, column_len, planner)?
+            }
+            FuncN(FuncNType::Coalesce, ref args) => {
+                QueryPlan::compile_coalesce(args, filter, columns, column_len, planner)?
+            }
+            FuncN(FuncNType::In, ref args) => {
+                QueryPlan::compile_in(&args[0], &args[1..], filter, columns, column_len, planner)?
+            }
+            FuncN(ftype, ref args) => {
+                let function = string_function(ftype, &args[1..])?;
+                QueryPlan::compile_string_map(&args[0], function, filter, columns, column_len, planner)?
+            }
+            Func1(Func1Type::Cast(cast_type), ref inner) => {
+                QueryPlan::compile_cast(inner, cast_type, true, filter, columns, column_len, planner)?
+            }
+            Func1(Func1Type::TryCast(cast_type), ref inner) => {
+                QueryPlan::compile_cast(inner, cast_type, false, filter, columns, column_len, planner)?
+            }
+            Func1(
+                ftype @ (Func1Type::Lower
+                | Func1Type::Upper
+                | Func1Type::Trim
+                | Func1Type::TrimStart
+                | Func1Type::TrimEnd),
+                ref inner,
+            ) => {
+                let function = match ftype {
+                    Func1Type::Lower => StringFunction::Lower,
+                    Func1Type::Upper => StringFunction::Upper,
+                    Func1Type::Trim => StringFunction::Trim,
+                    Func1Type::TrimStart => StringFunction::TrimStart,
+                    _ => StringFunction::TrimEnd,
+                };
+                QueryPlan::compile_string_map(inner, function, filter, columns, column_len, planner)?
+            }
             Func2(function, ref lhs, ref rhs) => {
+                if let Some(expr) = resolve_timestamp_literals(function, lhs, rhs, columns)? {
+                    return QueryPlan::compile_expr(&expr, filter, columns, column_len, planner);
+                }
                 let (mut plan_lhs, type_lhs) =
                     QueryPlan::compile_expr(lhs, filter, columns, column_len, planner)?;
                 let (mut plan_rhs, type_rhs) =
                     QueryPlan::compile_expr(rhs, filter, columns, column_len, planner)?;
 
+                if matches!(function, Pow | Greatest | Least) && type_lhs.is_scalar && type_rhs.is_scalar {
+                    let value = fold_numeric_constants(function, planner.resolve(&plan_lhs), planner.resolve(&plan_rhs))?;
+                    return QueryPlan::compile_expr(&Const(value), filter, columns, column_len, planner);
+                }
+
                 let declarations = match FUNCTION2_REGISTRY.get(&function) {
                     Some(patterns) => patterns,
                     None => bail!(QueryError::NotImplemented, "function {:?}", function),
@@ -1415,16 +1793,59 @@ impl QueryPlan {
                 let (plan, t) =
                     QueryPlan::compile_expr(inner, filter, columns, column_len, planner)?;
                 match ftype {
-                    Func1Type::ToYear => {
+                    Func1Type::ToYear
+                    | Func1Type::ToMonth
+                    | Func1Type::ToDayOfWeek
+                    | Func1Type::ToHour
+                    | Func1Type::DateTrunc(_)
+                    | Func1Type::TimeBucket(_) => {
                         let decoded = t.codec.decode(plan, planner);
-                        if t.decoded != BasicType::Integer {
-                            bail!(
+                        match t.decoded {
+                            BasicType::Integer | BasicType::NullableInteger => {}
+                            BasicType::Null => return Ok((decoded, t)),
+                            _ => bail!(
                                 QueryError::TypeError,
-                                "Found to_year({:?}), expected to_year(integer)",
+                                "Found {:?}({:?}), expected integer or timestamp argument",
+                                ftype,
                                 &t
-                            )
+                            ),
                         }
-                        (planner.to_year(decoded), Type::integer())
+                        let per_second = match inner.logical_type(&logical_types(columns)) {
+                            LogicalType::Timestamp(unit) => unit.per_second(),
+                            _ => 1,
+                        };
+                        let plan = match ftype {
+                            Func1Type::ToYear => planner.to_year(decoded, per_second),
+                            Func1Type::ToMonth => planner.to_month(decoded, per_second),
+                            Func1Type::ToDayOfWeek => planner.to_day_of_week(decoded, per_second),
+                            Func1Type::ToHour => planner.to_hour(decoded, per_second),
+                            Func1Type::DateTrunc(granularity) => {
+                                planner.date_trunc(decoded, granularity, per_second)
+                            }
+                            Func1Type::TimeBucket(width) => {
+                                let width = match width {
+                                    BucketWidth::Ticks(ticks) => ticks,
+                                    BucketWidth::Seconds(seconds) => seconds.saturating_mul(per_second),
+                                };
+                                if width <= 0 {
+                                    bail!(QueryError::TypeError, "time_bucket width must be positive, found {}", width)
+                                }
+                                planner.time_bucket(decoded, width)
+                            }
+                            _ => unreachable!(),
+                        };
+                        (plan, Type::integer())
+                    }
+                    Func1Type::FromUnixtime => {
+                        match t.decoded {
+                            BasicType::Integer | BasicType::NullableInteger | BasicType::Null => {}
+                            _ => bail!(
+                                QueryError::TypeError,
+                                "Found from_unixtime({:?}), expected from_unixtime(integer)",
+                                &t
+                            ),
+                        }
+                        (plan, t)
                     }
                     Func1Type::Floor => {
                         let decoded = t.codec.decode(plan, planner);
@@ -1442,6 +1863,59 @@ impl QueryPlan {
                             ),
                         }
                     }
+                    Func1Type::Abs
+                    | Func1Type::Sign
+                    | Func1Type::Ceil
+                    | Func1Type::Round(_)
+                    | Func1Type::Sqrt
+                    | Func1Type::Ln
+                    | Func1Type::Log10
+                    | Func1Type::Exp => {
+                        let function = math_function(ftype);
+                        let is_float = match t.decoded {
+                            BasicType::Integer | BasicType::NullableInteger => false,
+                            BasicType::Float | BasicType::NullableFloat => true,
+                            BasicType::Null => return Ok((plan, t)),
+                            _ => bail!(
+                                QueryError::TypeError,
+                                "Found {:?}({:?}), expected integer or float argument",
+                                ftype,
+                                &t
+                            ),
+                        };
+                        let returns_float = if is_float { !function.is_integral() } else { function.is_float_only() };
+                        if t.is_scalar {
+                            let value = match *planner.resolve(&plan) {
+                                QueryPlan::ScalarI64 { value, .. } if returns_float => {
+                                    RawVal::Float(OrderedFloat(function.apply_f64(value as f64)))
+                                }
+                                QueryPlan::ScalarI64 { value, .. } => RawVal::Int(function.apply_i64(value)),
+                                QueryPlan::ScalarF64 { value, .. } if returns_float => {
+                                    RawVal::Float(OrderedFloat(function.apply_f64(value)))
+                                }
+                                QueryPlan::ScalarF64 { value, .. } => RawVal::Int(function.apply_f64(value) as i64),
+                                ref plan => return Err(fatal!("Expected scalar, found {:?}", plan)),
+                            };
+                            return QueryPlan::compile_expr(&Const(value), filter, columns, column_len, planner);
+                        }
+                        let decoded = t.codec.decode(plan, planner);
+                        let input_type = if is_float || returns_float { EncodingType::F64 } else { EncodingType::I64 };
+                        let (output_type, basic_type, nullable_type) = if returns_float {
+                            (EncodingType::F64, BasicType::Float, BasicType::NullableFloat)
+                        } else {
+                            (EncodingType::I64, BasicType::Integer, BasicType::NullableInteger)
+                        };
+                        let input = planner.cast(decoded.forget_nullability(), input_type);
+                        let output = planner.math(input, function, output_type);
+                        if decoded.is_nullable() {
+                            (
+                                planner.propagate_nullability(decoded, output),
+                                Type::unencoded(nullable_type).mutable(),
+                            )
+                        } else {
+                            (output, Type::unencoded(basic_type).mutable())
+                        }
+                    }
                     Func1Type::Length => {
                         let decoded = t.codec.decode(plan, planner);
                         if t.decoded != BasicType::String {
@@ -1462,7 +1936,12 @@ impl QueryPlan {
                                 &t
                             )
                         }
-                        (planner.not(decoded.u8()?).into(), Type::bit_vec())
+                        if decoded.is_nullable() {
+                            let negated = planner.not(decoded.forget_nullability().u8()?);
+                            (planner.propagate_nullability(decoded, negated.into()), Type::bit_vec())
+                        } else {
+                            (planner.not(decoded.u8()?).into(), Type::bit_vec())
+                        }
                     }
                     Func1Type::IsNull => {
                         if plan.is_nullable() {
@@ -1504,6 +1983,13 @@ impl QueryPlan {
                             "Unary minus not implemented for arbitrary expressions."
                         )
                     }
+                    Func1Type::Lower
+                    | Func1Type::Upper
+                    | Func1Type::Trim
+                    | Func1Type::TrimStart
+                    | Func1Type::TrimEnd
+                    | Func1Type::Cast(_)
+                    | Func1Type::TryCast(_) => unreachable!(),
                 }
             }
             Const(RawVal::Int(i)) => (
@@ -1518,9 +2004,620 @@ impl QueryPlan {
                 planner.scalar_str(s).into(),
                 Type::scalar(BasicType::String),
             ),
+            Const(RawVal::Null) => (null_vec(filter, column_len, planner), Type::unencoded(BasicType::Null)),
             ref x => bail!(QueryError::NotImplemented, "{:?}.compile_vec()", x),
         })
     }
+
+    /// Selects the value of `then` where `cond` is true and the value of `otherwise` where `cond` is false or null.
+    #[allow(clippy::too_many_arguments)]
+    fn compile_if(
+        cond: &Expr,
+        then: &Expr,
+        otherwise: &Expr,
+        filter: Filter,
+        columns: &HashMap<String, Arc<dyn DataSource>>,
+        column_len: usize,
+        planner: &mut QueryPlanner,
+    ) -> Result<(TypedBufferRef, Type), QueryError> {
+        use self::Expr::*;
+        if let Const(RawVal::Int(c)) = *cond {
+            let branch = if c != 0 { then } else { otherwise };
+            return QueryPlan::compile_expr(branch, filter, columns, column_len, planner);
+        }
+        let (cond_plan, cond_type) = QueryPlan::compile_expr(cond, filter, columns, column_len, planner)?;
+        match cond_type.decoded {
+            BasicType::Boolean => {}
+            BasicType::Null => return QueryPlan::compile_expr(otherwise, filter, columns, column_len, planner),
+            _ => bail!(QueryError::TypeError, "Expected boolean condition in IF or CASE, found {:?}", cond_type),
+        }
+        let cond_plan = cond_type.codec.decode(cond_plan, planner);
+        let cond = if cond_plan.is_nullable() {
+            let is_not_null = planner.is_not_null(cond_plan.nullable_any()?);
+            planner.and(cond_plan.forget_nullability(), is_not_null.into()).u8()?
+        } else {
+            cond_plan.u8()?
+        };
+
+        let (then_plan, then_type) = QueryPlan::compile_expr(then, filter, columns, column_len, planner)?;
+        let (otherwise_plan, otherwise_type) =
+            QueryPlan::compile_expr(otherwise, filter, columns, column_len, planner)?;
+        let basic_type = match (then_type.decoded.non_nullable(), otherwise_type.decoded.non_nullable()) {
+            (BasicType::Null, BasicType::Null) => return Ok((then_plan, then_type)),
+            (BasicType::Null, t) | (t, BasicType::Null) => t,
+            (BasicType::Integer, BasicType::Float) | (BasicType::Float, BasicType::Integer) => BasicType::Float,
+            (t1, t2) if t1 == t2 => t1,
+            (t1, t2) => bail!(
+                QueryError::TypeError,
+                "Branches of IF or CASE have incompatible types {:?} and {:?}",
+                t1,
+                t2
+            ),
+        };
+        let (encoding_type, nullable_type) = match basic_type {
+            BasicType::Integer => (EncodingType::I64, BasicType::NullableInteger),
+            BasicType::Float => (EncodingType::F64, BasicType::NullableFloat),
+            BasicType::String => (EncodingType::Str, BasicType::NullableString),
+            BasicType::Boolean => (EncodingType::U8, BasicType::Boolean),
+            t => bail!(QueryError::NotImplemented, "IF or CASE with branches of type {:?}", t),
+        };
+        let then_plan = then_type.codec.decode(then_plan, planner);
+        let otherwise_plan = otherwise_type.codec.decode(otherwise_plan, planner);
+        let nullable = then_plan.is_nullable()
+            || otherwise_plan.is_nullable()
+            || then_type.decoded == BasicType::Null
+            || otherwise_type.decoded == BasicType::Null;
+        let then_plan = blend_input(then_plan, &then_type, encoding_type, nullable, cond, planner);
+        let otherwise_plan = blend_input(otherwise_plan, &otherwise_type, encoding_type, nullable, cond, planner);
+        let blended = planner.blend(cond, then_plan.forget_nullability(), otherwise_plan.forget_nullability());
+        Ok(if nullable {
+            let present = planner.blend_null_maps(cond, then_plan, otherwise_plan);
+            (planner.assemble_nullable(blended, present), Type::unencoded(nullable_type))
+        } else {
+            (blended, Type::unencoded(basic_type))
+        })
+    }
+
+    /// Determines whether `expr` is equal to any element of `list`.
+    /// Lists of string constants are checked only once per dictionary entry for dictionary encoded columns,
+    /// and lists of consecutive integer constants are compiled to a range check.
+    fn compile_in(
+        expr: &Expr,
+        list: &[Expr],
+        filter: Filter,
+        columns: &HashMap<String, Arc<dyn DataSource>>,
+        column_len: usize,
+        planner: &mut QueryPlanner,
+    ) -> Result<(TypedBufferRef, Type), QueryError> {
+        use self::Expr::*;
+        let strings = list
+            .iter()
+            .map(|item| match item {
+                Const(RawVal::Str(s)) => Some(s.clone()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>();
+        let mut integers = list
+            .iter()
+            .map(|item| match item {
+                Const(RawVal::Int(i)) => Some(*i),
+                Func1(Func1Type::Negate, box Const(RawVal::Int(i))) => Some(-i),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>();
+        if let Some(ref mut integers) = integers {
+            integers.sort_unstable();
+            integers.dedup();
+            let (min, max) = (integers[0], integers[integers.len() - 1]);
+            if max.checked_sub(min) == Some(integers.len() as i64 - 1) {
+                let range = Expr::func(
+                    Func2Type::And,
+                    Expr::func(Func2Type::GTE, expr.clone(), Const(RawVal::Int(min))),
+                    Expr::func(Func2Type::LTE, expr.clone(), Const(RawVal::Int(max))),
+                );
+                return QueryPlan::compile_expr(&range, filter, columns, column_len, planner);
+            }
+        }
+
+        let (plan, t) = QueryPlan::compile_expr(expr, filter, columns, column_len, planner)?;
+        let is_in = match (t.decoded.non_nullable(), strings, integers) {
+            (BasicType::Null, _, _) => return Ok((plan, t)),
+            (BasicType::String, Some(strings), _) if !t.is_scalar => {
+                if let Some((indices, offset_len, backing_store)) = t.codec.decode_dictionary(plan, planner) {
+                    let is_in = planner.dict_is_in(indices.forget_nullability(), offset_len, backing_store, strings);
+                    return Ok(if indices.is_nullable() {
+                        (planner.propagate_nullability(indices, is_in.into()), Type::bit_vec())
+                    } else {
+                        (is_in.into(), Type::bit_vec())
+                    });
+                }
+                let decoded = t.codec.decode(plan, planner);
+                (planner.is_in_strings(decoded.forget_nullability().str()?, strings), decoded)
+            }
+            (BasicType::Integer, _, Some(integers)) if !t.is_scalar => {
+                let decoded = t.codec.decode(plan, planner);
+                let values = planner.cast(decoded.forget_nullability(), EncodingType::I64);
+                (planner.is_in_i64(values.i64()?, integers), decoded)
+            }
+            // Floats, non-constant lists and constant expressions are compiled to a chain of equality checks
+            _ => {
+                let equals_any = list
+                    .iter()
+                    .map(|item| Expr::func(Func2Type::Equals, expr.clone(), item.clone()))
+                    .reduce(|lhs, rhs| Expr::func(Func2Type::Or, lhs, rhs))
+                    .unwrap();
+                return QueryPlan::compile_expr(&equals_any, filter, columns, column_len, planner);
+            }
+        };
+        Ok(match is_in {
+            (is_in, decoded) if decoded.is_nullable() => {
+                (planner.propagate_nullability(decoded, is_in.into()), Type::bit_vec())
+            }
+            (is_in, _) => (is_in.into(), Type::bit_vec()),
+        })
+    }
+
+    /// Converts `expr` to `cast_type`.
+    /// Values that cannot be converted are null, or cause an error during query execution if `strict` is set.
+    #[allow(clippy::too_many_arguments)]
+    fn compile_cast(
+        expr: &Expr,
+        cast_type: CastType,
+        strict: bool,
+        filter: Filter,
+        columns: &HashMap<String, Arc<dyn DataSource>>,
+        column_len: usize,
+        planner: &mut QueryPlanner,
+    ) -> Result<(TypedBufferRef, Type), QueryError> {
+        use self::Expr::*;
+        let (plan, t) = QueryPlan::compile_expr(expr, filter, columns, column_len, planner)?;
+        if t.is_scalar {
+            let value = match *planner.resolve(&plan) {
+                QueryPlan::ScalarI64 { value, .. } => Val::Integer(value),
+                QueryPlan::ScalarF64 { value, .. } => Val::Float(OrderedFloat(value)),
+                QueryPlan::ScalarStr { ref value, .. } => Val::Str(value),
+                _ => Val::Null,
+            };
+            let casted = match cast_type {
+                CastType::Integer => CastType::to_i64(value).map(RawVal::Int),
+                CastType::Float => CastType::to_f64(value).map(|f| RawVal::Float(OrderedFloat(f))),
+                CastType::String => {
+                    let mut string = String::new();
+                    CastType::format(value, &mut string).then_some(RawVal::Str(string))
+                }
+            };
+            let casted = match casted {
+                Some(casted) => casted,
+                None if strict && value != Val::Null => {
+                    bail!(QueryError::TypeError, "Cannot cast {} to {:?}", value, cast_type)
+                }
+                None => RawVal::Null,
+            };
+            return QueryPlan::compile_expr(&Const(casted), filter, columns, column_len, planner);
+        }
+
+        let basic_type = t.decoded.non_nullable();
+        match (basic_type, cast_type) {
+            (BasicType::Null, _)
+            | (BasicType::Integer, CastType::Integer)
+            | (BasicType::Float, CastType::Float)
+            | (BasicType::String, CastType::String) => return Ok((plan, t)),
+            (BasicType::Integer, CastType::Float) => {
+                let decoded = t.codec.decode(plan, planner);
+                let nullable_type = if decoded.is_nullable() { BasicType::NullableFloat } else { BasicType::Float };
+                return Ok((int_to_float_cast(planner, decoded)?, Type::unencoded(nullable_type)));
+            }
+            _ => {}
+        }
+        let decoded = t.codec.decode(plan, planner);
+        let vals = planner.cast(decoded, EncodingType::Val).val()?;
+        Ok(match cast_type {
+            CastType::Integer => (
+                planner.parse_vals(vals, strict, EncodingType::NullableI64),
+                Type::unencoded(BasicType::NullableInteger),
+            ),
+            CastType::Float => (
+                planner.parse_vals(vals, strict, EncodingType::NullableF64),
+                Type::unencoded(BasicType::NullableFloat),
+            ),
+            CastType::String => {
+                let (offset_len, backing_store) = planner.format_vals(vals);
+                let strings = lookup_all(vals.into(), offset_len, backing_store, planner);
+                (
+                    planner.nullable_formatted_vals(vals, strings.str()?),
+                    Type::unencoded(BasicType::NullableString),
+                )
+            }
+        })
+    }
+
+    /// Returns the first argument that is not null.
+    fn compile_coalesce(
+        args: &[Expr],
+        filter: Filter,
+        columns: &HashMap<String, Arc<dyn DataSource>>,
+        column_len: usize,
+        planner: &mut QueryPlanner,
+    ) -> Result<(TypedBufferRef, Type), QueryError> {
+        use self::Expr::*;
+        let (plan, t) = QueryPlan::compile_expr(&args[0], filter, columns, column_len, planner)?;
+        if args.len() == 1 || !(plan.is_nullable() || t.decoded == BasicType::Null) {
+            // Remaining arguments are never used
+            Ok((plan, t))
+        } else if t.decoded == BasicType::Null {
+            QueryPlan::compile_coalesce(&args[1..], filter, columns, column_len, planner)
+        } else {
+            QueryPlan::compile_if(
+                &Func1(Func1Type::IsNotNull, Box::new(args[0].clone())),
+                &args[0],
+                &FuncN(FuncNType::Coalesce, args[1..].to_vec()),
+                filter,
+                columns,
+                column_len,
+                planner,
+            )
+        }
+    }
+
+    /// Applies `function` to the strings produced by `expr`.
+    /// For dictionary encoded columns, only the dictionary is transformed and then indexed by the original dictionary indices.
+    fn compile_string_map(
+        expr: &Expr,
+        function: StringFunction,
+        filter: Filter,
+        columns: &HashMap<String, Arc<dyn DataSource>>,
+        column_len: usize,
+        planner: &mut QueryPlanner,
+    ) -> Result<(TypedBufferRef, Type), QueryError> {
+        use self::Expr::*;
+        let regex = match function {
+            StringFunction::RegexpExtract { ref regex, group } => {
+                let regex = Regex::new(regex).map_err(|e| {
+                    QueryError::TypeError(format!("`{}` is not a valid regex: {}", regex, e))
+                })?;
+                if group >= regex.captures_len() {
+                    bail!(QueryError::TypeError, "Regex `{}` has no capture group {}", regex, group)
+                }
+                Some(regex)
+            }
+            _ => None,
+        };
+        if let Const(RawVal::Str(ref string)) = *expr {
+            let mut result = String::new();
+            function.apply(string, regex.as_ref(), &mut result);
+            return QueryPlan::compile_expr(&Const(RawVal::Str(result)), filter, columns, column_len, planner);
+        }
+        let (plan, t) = QueryPlan::compile_expr(expr, filter, columns, column_len, planner)?;
+        match t.decoded {
+            BasicType::String | BasicType::NullableString => {}
+            BasicType::Null => return Ok((plan, t)),
+            _ => bail!(QueryError::TypeError, "Found {:?}({:?}), expected string argument", function, t),
+        }
+        if let Some((indices, offset_len, backing_store)) = t.codec.decode_dictionary(plan, planner) {
+            let dictionary_indices = planner.indices(offset_len.into());
+            let dictionary = planner.dict_lookup(dictionary_indices.into(), offset_len, backing_store);
+            let (offset_len, backing_store) = planner.string_map(dictionary.str()?, function);
+            return Ok((
+                planner.dict_lookup(indices, offset_len, backing_store),
+                Type::unencoded(BasicType::String),
+            ));
+        }
+        let plan = t.codec.decode(plan, planner);
+        let strings = plan.forget_nullability();
+        let (offset_len, backing_store) = planner.string_map(strings.str()?, function);
+        let mapped = lookup_all(strings, offset_len, backing_store, planner);
+        Ok(if plan.is_nullable() {
+            (planner.propagate_nullability(plan, mapped), Type::unencoded(BasicType::NullableString))
+        } else {
+            (mapped, Type::unencoded(BasicType::String))
+        })
+    }
+
+    /// Concatenates string expressions and constants, the result is null if any argument is null.
+    fn compile_concat(
+        args: &[Expr],
+        filter: Filter,
+        columns: &HashMap<String, Arc<dyn DataSource>>,
+        column_len: usize,
+        planner: &mut QueryPlanner,
+    ) -> Result<(TypedBufferRef, Type), QueryError> {
+        use self::Expr::*;
+        // Adjacent constants are merged and applied to the preceding or following expression with `StringMap`
+        let mut result: Option<Expr> = None;
+        let mut exprs = vec![];
+        let mut constant = String::new();
+        for arg in args {
+            match *arg {
+                Const(RawVal::Null) => {
+                    return QueryPlan::compile_expr(&Const(RawVal::Null), filter, columns, column_len, planner)
+                }
+                Const(RawVal::Str(ref s)) => constant.push_str(s),
+                Const(RawVal::Int(i)) => constant.push_str(&i.to_string()),
+                Const(RawVal::Float(f)) => constant.push_str(&f.to_string()),
+                Const(ref c) => bail!(QueryError::TypeError, "Unsupported argument to concat: {:?}", c),
+                ref expr => {
+                    let prefix = std::mem::take(&mut constant);
+                    match result.take() {
+                        None => {
+                            result = Some(if prefix.is_empty() {
+                                expr.clone()
+                            } else {
+                                FuncN(FuncNType::Concat, vec![Const(RawVal::Str(prefix)), expr.clone()])
+                            })
+                        }
+                        Some(previous) => {
+                            exprs.push(if prefix.is_empty() {
+                                previous
+                            } else {
+                                FuncN(FuncNType::Concat, vec![previous, Const(RawVal::Str(prefix))])
+                            });
+                            result = Some(expr.clone());
+                        }
+                    }
+                }
+            }
+        }
+        let last = match result {
+            None => {
+                return QueryPlan::compile_expr(&Const(RawVal::Str(constant)), filter, columns, column_len, planner)
+            }
+            Some(last) => last,
+        };
+        if exprs.is_empty() {
+            // Single expression with constant prefix and/or suffix
+            return match last {
+                FuncN(FuncNType::Concat, ref prefixed) => {
+                    let prefix = match prefixed[0] {
+                        Const(RawVal::Str(ref prefix)) => prefix.clone(),
+                        _ => unreachable!(),
+                    };
+                    let function = StringFunction::Concat { prefix, suffix: constant };
+                    QueryPlan::compile_string_map(&prefixed[1], function, filter, columns, column_len, planner)
+                }
+                ref expr => {
+                    let function = StringFunction::Concat { prefix: String::new(), suffix: constant };
+                    QueryPlan::compile_string_map(expr, function, filter, columns, column_len, planner)
+                }
+            };
+        }
+        exprs.push(if constant.is_empty() {
+            last
+        } else {
+            FuncN(FuncNType::Concat, vec![last, Const(RawVal::Str(constant))])
+        });
+        let mut plans = vec![];
+        for expr in &exprs {
+            let (plan, t) = QueryPlan::compile_expr(expr, filter, columns, column_len, planner)?;
+            match t.decoded {
+                BasicType::String | BasicType::NullableString => {}
+                BasicType::Null => return Ok((plan, t)),
+                _ => bail!(QueryError::TypeError, "Found concat({:?}), expected string arguments", t),
+            }
+            plans.push(t.codec.decode(plan, planner));
+        }
+        let mut lhs = plans[0];
+        for &rhs in &plans[1..] {
+            let (offset_len, backing_store) = planner.concat_strings(
+                lhs.forget_nullability().str()?,
+                rhs.forget_nullability().str()?,
+            );
+            let concatenated = lookup_all(lhs.forget_nullability(), offset_len, backing_store, planner);
+            lhs = match (lhs.is_nullable(), rhs.is_nullable()) {
+                (false, false) => concatenated,
+                (true, false) => planner.propagate_nullability(lhs, concatenated),
+                (false, true) => planner.propagate_nullability(rhs, concatenated),
+                (true, true) => {
+                    let present = planner.combine_null_maps(lhs, rhs);
+                    planner.assemble_nullable(concatenated, present)
+                }
+            };
+        }
+        let t = if lhs.is_nullable() { BasicType::NullableString } else { BasicType::String };
+        Ok((lhs, Type::unencoded(t)))
+    }
+}
+
+/// Evaluates `pow`, `greatest` or `least` on two constants.
+fn fold_numeric_constants(function: Func2Type, lhs: &QueryPlan, rhs: &QueryPlan) -> Result<RawVal, QueryError> {
+    let value = |plan: &QueryPlan| match *plan {
+        QueryPlan::ScalarI64 { value, .. } => Ok(RawVal::Int(value)),
+        QueryPlan::ScalarF64 { value, .. } => Ok(RawVal::Float(OrderedFloat(value))),
+        QueryPlan::NullVec { .. } => Ok(RawVal::Null),
+        ref plan => Err(QueryError::TypeError(format!("Expected numeric constant, found {:?}", plan))),
+    };
+    Ok(match (function, value(lhs)?, value(rhs)?) {
+        (_, RawVal::Null, _) | (_, _, RawVal::Null) => RawVal::Null,
+        (Func2Type::Greatest, RawVal::Int(l), RawVal::Int(r)) => RawVal::Int(l.max(r)),
+        (Func2Type::Least, RawVal::Int(l), RawVal::Int(r)) => RawVal::Int(l.min(r)),
+        (function, l, r) => {
+            let float = |v: RawVal| match v {
+                RawVal::Int(i) => i as f64,
+                RawVal::Float(f) => f.0,
+                _ => unreachable!(),
+            };
+            let (l, r) = (float(l), float(r));
+            RawVal::Float(OrderedFloat(match function {
+                Func2Type::Greatest => l.max(r),
+                Func2Type::Least => l.min(r),
+                _ => l.powf(r),
+            }))
+        }
+    })
+}
+
+/// Converts an argument of `IF` to a (nullable) vector of `encoding_type` with the same length as `cond`.
+fn blend_input(
+    plan: TypedBufferRef,
+    t: &Type,
+    encoding_type: EncodingType,
+    nullable: bool,
+    cond: BufferRef<u8>,
+    planner: &mut QueryPlanner,
+) -> TypedBufferRef {
+    if t.decoded == BasicType::Null {
+        return planner.cast(plan, encoding_type.nullable());
+    }
+    let plan = if t.is_scalar {
+        let vector_type = match plan.tag {
+            EncodingType::ScalarF64 => EncodingType::F64,
+            EncodingType::ScalarStr => EncodingType::Str,
+            _ => EncodingType::I64,
+        };
+        planner.expand_scalar(plan, cond.into(), vector_type)
+    } else {
+        plan
+    };
+    let data = planner.cast(plan.forget_nullability(), encoding_type);
+    match (plan.is_nullable(), nullable) {
+        (true, _) => planner.propagate_nullability(plan, data),
+        (false, true) => planner.make_nullable(data),
+        (false, false) => data,
+    }
+}
+
+/// Vector of nulls with the same length as columns after applying `filter`.
+fn null_vec(filter: Filter, column_len: usize, planner: &mut QueryPlanner) -> TypedBufferRef {
+    match filter {
+        Filter::None => planner.null_vec(column_len, EncodingType::Null),
+        Filter::U8(filter) => planner.null_vec_like(filter.into(), 1, EncodingType::Null),
+        Filter::NullableU8(filter) => planner.null_vec_like(filter.into(), 2, EncodingType::Null),
+        Filter::Null => planner.null_vec(0, EncodingType::Null),
+        Filter::Indices(filter) => planner.null_vec_like(filter.into(), 0, EncodingType::Null),
+    }
+}
+
+fn math_function(ftype: Func1Type) -> MathFunction {
+    match ftype {
+        Func1Type::Abs => MathFunction::Abs,
+        Func1Type::Sign => MathFunction::Sign,
+        Func1Type::Ceil => MathFunction::Ceil,
+        Func1Type::Round(digits) => MathFunction::Round(digits),
+        Func1Type::Sqrt => MathFunction::Sqrt,
+        Func1Type::Ln => MathFunction::Ln,
+        Func1Type::Log10 => MathFunction::Log10,
+        Func1Type::Exp => MathFunction::Exp,
+        _ => unreachable!("{:?} is not a math function", ftype),
+    }
+}
+
+/// Resolves the `StringMap` function of `substr`, `split_part`, `replace` and `regexp_extract` from its constant
+/// arguments (excluding the string argument).
+fn string_function(ftype: FuncNType, args: &[Expr]) -> Result<StringFunction, QueryError> {
+    let int_arg = |i: usize| match args.get(i) {
+        Some(Expr::Const(RawVal::Int(x))) => Ok(*x),
+        Some(Expr::Func1(Func1Type::Negate, box Expr::Const(RawVal::Int(x)))) => Ok(-x),
+        arg => Err(QueryError::TypeError(format!(
+            "Expected integer constant as argument {} to {:?}, actual: {:?}",
+            i + 2,
+            ftype,
+            arg
+        ))),
+    };
+    let str_arg = |i: usize| match args.get(i) {
+        Some(Expr::Const(RawVal::Str(s))) => Ok(s.clone()),
+        arg => Err(QueryError::TypeError(format!(
+            "Expected string constant as argument {} to {:?}, actual: {:?}",
+            i + 2,
+            ftype,
+            arg
+        ))),
+    };
+    Ok(match ftype {
+        FuncNType::Substr => {
+            let length = if args.len() > 1 { Some(int_arg(1)?) } else { None };
+            if length.is_some_and(|length| length < 0) {
+                bail!(QueryError::TypeError, "Negative substr length {:?}", length)
+            }
+            StringFunction::Substr { start: int_arg(0)?, length }
+        }
+        FuncNType::SplitPart => {
+            let index = int_arg(1)?;
+            if index == 0 {
+                bail!(QueryError::TypeError, "split_part field index must not be zero")
+            }
+            StringFunction::SplitPart { delimiter: str_arg(0)?, index }
+        }
+        FuncNType::Replace => StringFunction::Replace { from: str_arg(0)?, to: str_arg(1)? },
+        FuncNType::RegexpExtract => {
+            let group = if args.len() > 1 { int_arg(1)? } else { 0 };
+            if group < 0 {
+                bail!(QueryError::TypeError, "Negative regexp_extract group {}", group)
+            }
+            StringFunction::RegexpExtract { regex: str_arg(0)?, group: group as usize }
+        }
+        FuncNType::Concat | FuncNType::If | FuncNType::Coalesce | FuncNType::In => unreachable!(),
+    })
+}
+
+/// Resolves each element of `strings` to the string at the same position in the `offset_len` and `backing_store` dictionary.
+fn lookup_all(
+    strings: TypedBufferRef,
+    offset_len: BufferRef<u64>,
+    backing_store: BufferRef<u8>,
+    planner: &mut QueryPlanner,
+) -> TypedBufferRef {
+    let indices = planner.indices(strings);
+    planner.dict_lookup(indices.into(), offset_len, backing_store)
+}
+
+/// Rewrites comparisons of timestamps with string literals (e.g. `ts > '2024-01-01'`) or `now()` to compare
+/// against the timestamp in the unit of the other operand instead.
+/// String literals are also resolved against integer columns without logical type if they parse as timestamp.
+fn resolve_timestamp_literals(
+    function: Func2Type,
+    lhs: &Expr,
+    rhs: &Expr,
+    columns: &HashMap<String, Arc<dyn DataSource>>,
+) -> Result<Option<Expr>, QueryError> {
+    use self::Func2Type::*;
+    if !matches!(function, Equals | NotEquals | LT | LTE | GT | GTE) {
+        return Ok(None);
+    }
+    let timestamp_literal = |literal: &Expr, other: &Expr| -> Result<Option<Expr>, QueryError> {
+        let unit = match other.logical_type(&logical_types(columns)) {
+            LogicalType::Timestamp(unit) => unit,
+            _ => {
+                let is_integer_column = match other {
+                    Expr::ColName(name) => columns
+                        .get(name)
+                        .is_some_and(|c| c.full_type().decoded.non_nullable() == BasicType::Integer),
+                    _ => false,
+                };
+                return Ok(match literal {
+                    Expr::Const(RawVal::Str(s)) if is_integer_column => {
+                        parse_timestamp(s, TimeUnit::Second).map(|ts| Expr::Const(RawVal::Int(ts)))
+                    }
+                    _ => None,
+                });
+            }
+        };
+        match literal {
+            Expr::Const(RawVal::Str(s)) => match parse_timestamp(s, unit) {
+                Some(timestamp) => Ok(Some(Expr::Const(RawVal::Int(timestamp)))),
+                None => bail!(QueryError::TypeError, "Cannot parse '{}' as timestamp", s),
+            },
+            Expr::Func1(Func1Type::FromUnixtime, box Expr::Const(RawVal::Int(unix_ts)))
+                if unit != TimeUnit::Second =>
+            {
+                Ok(Some(Expr::Const(RawVal::Int(unix_ts.saturating_mul(unit.per_second())))))
+            }
+            _ => Ok(None),
+        }
+    };
+    Ok(
+        match (timestamp_literal(lhs, rhs)?, timestamp_literal(rhs, lhs)?) {
+            (Some(lhs), _) => Some(Expr::Func2(function, Box::new(lhs), Box::new(rhs.clone()))),
+            (_, Some(rhs)) => Some(Expr::Func2(function, Box::new(lhs.clone()), Box::new(rhs))),
+            (None, None) => None,
+        },
+    )
+}
+
+fn logical_types(columns: &HashMap<String, Arc<dyn DataSource>>) -> HashMap<String, LogicalType> {
+    columns
+        .iter()
+        .map(|(name, column)| (name.clone(), column.logical_type()))
+        .collect()
 }
 
 fn encoding_range(plan: &TypedBufferRef, qp: &QueryPlanner) -> Option<(i64, i64)> {
@@ -1530,10 +2627,23 @@ fn encoding_range(plan: &TypedBufferRef, qp: &QueryPlanner) -> Option<(i64, i64)
     use self::QueryPlan::*;
     match *qp.resolve(plan) {
         ColumnSection { range, .. } => range,
-        ToYear { timestamp, .. } => encoding_range(&timestamp, qp).map(|(min, max)| {
+        ToYear { timestamp, per_second, .. } => encoding_range(&timestamp, qp).map(|(min, max)| {
             (
-                i64::from(DateTime::from_timestamp(min, 0).unwrap().year()),
-                i64::from(DateTime::from_timestamp(max, 0).unwrap().year()),
+                i64::from(DateTime::from_timestamp(min.div_euclid(per_second), 0).unwrap_or_default().year()),
+                i64::from(DateTime::from_timestamp(max.div_euclid(per_second), 0).unwrap_or_default().year()),
+            )
+        }),
+        TimeBucket { timestamp, width, .. } => encoding_range(&timestamp, qp)
+            .map(|(min, max)| (min - min.rem_euclid(width), max - max.rem_euclid(width))),
+        DateTrunc {
+            timestamp,
+            granularity,
+            per_second,
+            ..
+        } => encoding_range(&timestamp, qp).map(|(min, max)| {
+            (
+                granularity.truncate(min.div_euclid(per_second)).saturating_mul(per_second),
+                granularity.truncate(max.div_euclid(per_second)).saturating_mul(per_second),
             )
         }),
         Filter { ref plan, .. } => encoding_range(plan, qp),
@@ -1581,7 +2691,8 @@ fn encoding_range(plan: &TypedBufferRef, qp: &QueryPlanner) -> Option<(i64, i64)
         PcoDecode { bytes, .. } => encoding_range(&bytes.into(), qp),
         DeltaDecode { ref plan, .. } => encoding_range(plan, qp),
         AssembleNullable { ref data, .. } => encoding_range(data, qp),
-        UnpackStrings { .. } | UnhexpackStrings { .. } | Length { .. } => None,
+        UnpackStrings { .. } | UnhexpackStrings { .. } | Length { .. } | StartsWith { .. } => None,
+        DictLookup { .. } => None,
         NullVec { .. } => Some((0, 0)),
         NullVecLike { .. } => Some((0, 0)),
         CheckedMultiply {
@@ -1613,6 +2724,29 @@ fn encoding_range(plan: &TypedBufferRef, qp: &QueryPlanner) -> Option<(i64, i64)
             Some((min, max))
         }
         Floor { input, .. } => encoding_range(&input, qp),
+        Greatest {
+            ref lhs, ref rhs, ..
+        } => {
+            let (min_lhs, max_lhs) = encoding_range(lhs, qp)?;
+            let (min_rhs, max_rhs) = encoding_range(rhs, qp)?;
+            Some((min_lhs.max(min_rhs), max_lhs.max(max_rhs)))
+        }
+        Least {
+            ref lhs, ref rhs, ..
+        } => {
+            let (min_lhs, max_lhs) = encoding_range(lhs, qp)?;
+            let (min_rhs, max_rhs) = encoding_range(rhs, qp)?;
+            Some((min_lhs.min(min_rhs), max_lhs.min(max_rhs)))
+        }
+        Math { .. } | Power { .. } => None,
+        Blend {
+            ref lhs, ref rhs, ..
+        } => {
+            let (min_lhs, max_lhs) = encoding_range(lhs, qp)?;
+            let (min_rhs, max_rhs) = encoding_range(rhs, qp)?;
+            Some((min_lhs.min(min_rhs), max_lhs.max(max_rhs)))
+        }
+        ExpandScalar { ref scalar, .. } => encoding_range(scalar, qp),
         ScalarI64 { value, .. } => Some((value, value)),
         ScalarF64 { value, .. } => Some((value.floor() as i64, value.ceil() as i64)),
         NullableFilter { ref plan, .. } => encoding_range(plan, qp),
@@ -1967,6 +3101,10 @@ pub(super) fn prepare<'a>(
             operator::get_null_map(nullable.nullable_any()?, present)
         }
         QueryPlan::Floor { input, floor } => operator::floor(input.f64()?, floor.i64()?),
+        QueryPlan::Math { input, function, output } => operator::math(input, function, output)?,
+        QueryPlan::Blend { cond, lhs, rhs, blended } => operator::blend(cond, lhs, rhs, blended)?,
+        QueryPlan::BlendNullMaps { cond, lhs, rhs, present } => operator::blend_null_maps(cond, lhs, rhs, present)?,
+        QueryPlan::ExpandScalar { scalar, like, expanded } => operator::expand_scalar(scalar, like.any(), expanded)?,
         QueryPlan::FuseNulls { nullable, fused } => operator::fuse_nulls(nullable, fused)?,
         QueryPlan::FuseIntNulls {
             offset,
@@ -2119,6 +3257,32 @@ pub(super) fn prepare<'a>(
                 operator::aggregate(plan, grouping_key, max_index, aggregator, aggregate)?
             }
         }
+        QueryPlan::AggregateDistinct {
+            plan,
+            grouping_key,
+            max_index,
+            distinct,
+        } => operator::aggregate_distinct(plan, grouping_key, max_index, distinct)?,
+        QueryPlan::AggregateSketch {
+            plan,
+            grouping_key,
+            max_index,
+            aggregator,
+            sketches,
+        } => operator::aggregate_sketch(plan, grouping_key, max_index, aggregator, sketches)?,
+        QueryPlan::AggregateValues {
+            plan,
+            grouping_key,
+            max_index,
+            values,
+        } => operator::aggregate_values(plan, grouping_key, max_index, values)?,
+        QueryPlan::AggregateWelford {
+            x,
+            y,
+            grouping_key,
+            max_index,
+            moments,
+        } => operator::aggregate_welford(x, y, grouping_key, max_index, moments)?,
         QueryPlan::CheckedAggregate {
             plan,
             grouping_key,
@@ -2251,17 +3415,78 @@ pub(super) fn prepare<'a>(
             present,
             modulo,
         } => operator::nullable_checked_modulo(lhs, rhs, present, modulo)?,
+        QueryPlan::Power { lhs, rhs, power } => operator::power(lhs, rhs, power.f64()?)?,
+        QueryPlan::Greatest { lhs, rhs, greatest } => operator::greatest(lhs, rhs, greatest)?,
+        QueryPlan::Least { lhs, rhs, least } => operator::least(lhs, rhs, least)?,
         QueryPlan::Or { lhs, rhs, or } => operator::or(lhs.u8()?, rhs.u8()?, or.u8()?),
         QueryPlan::And { lhs, rhs, and } => operator::and(lhs.u8()?, rhs.u8()?, and.u8()?),
         QueryPlan::Not { input, not } => operator::not(input, not),
-        QueryPlan::ToYear { timestamp, year } => operator::to_year(timestamp.i64()?, year.i64()?),
+        QueryPlan::ToYear { timestamp, per_second, year } => {
+            operator::to_year(timestamp.i64()?, per_second, year.i64()?)
+        }
+        QueryPlan::ToMonth { timestamp, per_second, month } => {
+            operator::to_month(timestamp.i64()?, per_second, month.i64()?)
+        }
+        QueryPlan::ToDayOfWeek { timestamp, per_second, day_of_week } => {
+            operator::to_day_of_week(timestamp.i64()?, per_second, day_of_week.i64()?)
+        }
+        QueryPlan::ToHour { timestamp, per_second, hour } => {
+            operator::to_hour(timestamp.i64()?, per_second, hour.i64()?)
+        }
+        QueryPlan::TimeBucket { timestamp, width, bucket } => {
+            operator::time_bucket(timestamp.i64()?, width, bucket.i64()?)
+        }
+        QueryPlan::DateTrunc { timestamp, granularity, per_second, truncated } => {
+            operator::date_trunc(timestamp.i64()?, granularity, per_second, truncated.i64()?)
+        }
         QueryPlan::Regex {
             plan,
             regex,
             matches,
         } => operator::regex(plan, &regex, matches),
         QueryPlan::Length { string, length } => operator::length(string, length),
+        QueryPlan::StartsWith {
+            string,
+            prefix,
+            starts_with,
+        } => operator::starts_with(string, &prefix, starts_with),
+        QueryPlan::ParseVals { vals, strict, parsed } => operator::parse_vals(vals, strict, parsed)?,
+        QueryPlan::FormatVals {
+            vals,
+            offset_len,
+            backing_store,
+        } => operator::format_vals(vals, offset_len, backing_store),
+        QueryPlan::NullableFormattedVals { vals, strings, nullable } => {
+            operator::nullable_formatted_vals(vals, strings, nullable)?
+        }
+        QueryPlan::IsInStrings { strings, set, is_in } => operator::is_in_strings(strings, set, is_in),
+        QueryPlan::IsInI64 { values, set, is_in } => operator::is_in_i64(values, set, is_in),
+        QueryPlan::DictIsIn {
+            indices,
+            offset_len,
+            backing_store,
+            set,
+            is_in,
+        } => operator::dict_is_in(indices, offset_len, backing_store, set, is_in)?,
+        QueryPlan::StringMap {
+            strings,
+            function,
+            offset_len,
+            backing_store,
+        } => operator::string_map(strings, function, offset_len, backing_store)?,
+        QueryPlan::ConcatStrings {
+            lhs,
+            rhs,
+            offset_len,
+            backing_store,
+        } => operator::concat_strings(lhs, rhs, offset_len, backing_store),
         QueryPlan::Indices { plan, indices } => operator::indices(plan, indices),
+        QueryPlan::JoinProbe {
+            keys,
+            index,
+            unmatched,
+            rows,
+        } => operator::join_probe(keys, index, unmatched, rows),
         QueryPlan::SortBy {
             ranking,
             indices,
@@ -18,6 +18,8 @@ pub enum QueryError {
     TypeError(String),
     #[error("Overflow or division by zero")]
     Overflow,
+    #[error("Query was aborted: {}", _0)]
+    Aborted(String),
     #[error("Query execution was canceled")]
     Canceled {
         #[from]
@@ -1,4 +1,5 @@
 use datasize::DataSize;
+use locustdb_serialization::logical_type::LogicalType;
 use ordered_float::OrderedFloat;
 
 use crate::ingest::input_column::InputColumn;
@@ -36,13 +37,26 @@ impl Buffer {
                 .entry(name)
                 .or_insert_with(|| ColumnBuffer::null(len));
             match input_col {
-                InputColumn::Int(vec) => buffered_col.push_ints(vec, None),
+                InputColumn::Int(vec) => {
+                    buffered_col.push_logical_type(LogicalType::Plain);
+                    buffered_col.push_ints(vec, None)
+                }
                 InputColumn::Str(vec) => {
+                    buffered_col.push_logical_type(LogicalType::Plain);
                     buffered_col.push_strings(vec.iter().map(|s| s.as_str()), None)
                 }
                 InputColumn::Float(vec) => {
+                    buffered_col.push_logical_type(LogicalType::Plain);
                     buffered_col.push_floats(vec.into_iter().map(OrderedFloat), None)
                 }
+                InputColumn::Bool(vec) => {
+                    buffered_col.push_logical_type(LogicalType::Bool);
+                    buffered_col.push_ints(vec.into_iter().map(i64::from), None)
+                }
+                InputColumn::Timestamp(unit, vec) => {
+                    buffered_col.push_logical_type(LogicalType::Timestamp(unit));
+                    buffered_col.push_ints(vec, None)
+                }
                 InputColumn::Null(c) => buffered_col.push_nulls(c),
                 InputColumn::Mixed(vec) => {
                     for val in vec {
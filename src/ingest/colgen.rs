@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use crate::ingest::alias_method_fork::*;
 use crate::ingest::raw_val::RawVal;
 use locustdb_serialization::event_buffer::{ColumnBuffer, ColumnData, EventBuffer, TableBuffer};
+use locustdb_serialization::logical_type::LogicalType;
 use rand::distr::{Alphanumeric, StandardUniform};
 use rand::Rng;
 use rand::SeedableRng;
@@ -279,10 +280,21 @@ pub fn event_buffer_from_raw_vals(
     let mut event_buffer = EventBuffer::default();
     let mut table_buffer = TableBuffer::default();
     for (colname, values) in columns {
+        let mut logical_types = values
+            .iter()
+            .filter(|v| **v != RawVal::Null)
+            .map(RawVal::logical_type);
+        let first = logical_types.next().unwrap_or_default();
+        let logical_type = if logical_types.all(|t| t == first) {
+            first
+        } else {
+            LogicalType::Plain
+        };
         table_buffer.insert(
             colname,
             ColumnBuffer {
                 data: ColumnData::Mixed(values.into_iter().map(RawVal::into).collect()),
+                logical_type,
             },
         );
     }
@@ -1,10 +1,11 @@
 extern crate csv;
 extern crate flate2;
 
+use locustdb_serialization::logical_type::LogicalType;
 use ordered_float::OrderedFloat;
 
 use crate::bitvec::*;
-use crate::ingest::raw_val::RawVal;
+use crate::ingest::raw_val::{self, RawVal};
 use crate::ingest::schema::*;
 use crate::scheduler::*;
 use crate::stringpack::*;
@@ -29,6 +30,7 @@ pub struct Options {
     partition_size: usize,
     colnames: Option<Vec<String>>,
     extractors: IngestionTransform,
+    logical_types: HashMap<usize, LogicalType>,
     ignore_cols: HashSet<usize>,
     always_string: HashSet<usize>,
     allow_nulls: HashSet<usize>,
@@ -44,6 +46,7 @@ impl Options {
             partition_size: 1 << 16,
             colnames: None,
             extractors: HashMap::new(),
+            logical_types: HashMap::new(),
             ignore_cols: HashSet::new(),
             always_string: HashSet::new(),
             allow_nulls: HashSet::new(),
@@ -57,6 +60,7 @@ impl Options {
         let schema = Schema::parse(schema).unwrap();
         self.colnames = schema.column_names;
         let mut extractors = HashMap::new();
+        let mut logical_types = HashMap::new();
         let mut always_string = HashSet::new();
         let mut allow_nulls = HashSet::new();
         let mut ignore_cols = HashSet::new();
@@ -73,6 +77,15 @@ impl Options {
             {
                 extractors.insert(i, extractor::int);
             }
+            match colschema.types {
+                ColumnType::Boolean => {
+                    logical_types.insert(i, LogicalType::Bool);
+                }
+                ColumnType::Timestamp(unit) => {
+                    logical_types.insert(i, LogicalType::Timestamp(unit));
+                }
+                _ => {}
+            }
             if colschema.types == ColumnType::String
                 || colschema.types == ColumnType::NullableString
             {
@@ -88,6 +101,7 @@ impl Options {
             }
         }
         self.extractors = extractors;
+        self.logical_types = logical_types;
         self.always_string = always_string;
         self.allow_nulls = allow_nulls;
         self.ignore_cols = ignore_cols;
@@ -192,6 +206,7 @@ where
         .map(|x| RawCol::new(opts.allow_nulls_all_columns || opts.allow_nulls.contains(&x)))
         .collect::<Vec<_>>();
     let mut row_num = 0usize;
+    let mut batch_start = 0usize;
     for row in records {
         for (i, val) in row.iter().enumerate() {
             if !ignore[i] {
@@ -200,7 +215,8 @@ where
         }
 
         if row_num % opts.partition_size == opts.partition_size - 1 {
-            let cols = create_batch(&mut raw_cols, colnames, &opts.extractors, &ignore, &string);
+            let cols = create_batch(&mut raw_cols, colnames, opts, &ignore, &string, batch_start)?;
+            batch_start = row_num + 1;
             let event_buffer = event_buffer_from_raw_vals(&opts.tablename, cols);
             ldb.ingest_efficient(event_buffer);
             ldb.trigger_wal_flush();
@@ -209,7 +225,7 @@ where
     }
 
     if row_num % opts.partition_size != 0 {
-        let cols = create_batch(&mut raw_cols, colnames, &opts.extractors, &ignore, &string);
+        let cols = create_batch(&mut raw_cols, colnames, opts, &ignore, &string, batch_start)?;
         let event_buffer = event_buffer_from_raw_vals(&opts.tablename, cols);
         ldb.ingest_efficient(event_buffer);
     }
@@ -221,21 +237,25 @@ where
 fn create_batch(
     cols: &mut [RawCol],
     colnames: &[String],
-    extractors: &IngestionTransform,
+    opts: &Options,
     ignore: &[bool],
     string: &[bool],
-) -> HashMap<String, Vec<RawVal>> {
+    first_row: usize,
+) -> Result<HashMap<String, Vec<RawVal>>, String> {
     let mut mem_store = HashMap::new();
     for (i, col) in cols.iter_mut().enumerate() {
         if !ignore[i] {
-            let new_column = match extractors.get(&i) {
-                Some(extractor) => col.extract(*extractor),
-                None => col.finalize(&colnames[i], string[i]),
+            let new_column = match (opts.extractors.get(&i), opts.logical_types.get(&i)) {
+                (Some(extractor), _) => col.extract(*extractor),
+                (None, Some(&logical_type)) => {
+                    col.extract_logical(&colnames[i], logical_type, first_row)?
+                }
+                (None, None) => col.finalize(&colnames[i], string[i]),
             };
             mem_store.insert(colnames[i].to_string(), new_column);
         }
     }
-    mem_store
+    Ok(mem_store)
 }
 
 pub struct CSVIngestionTask {
@@ -388,6 +408,64 @@ impl RawCol {
         builder
     }
 
+    /// Parses values of a boolean or timestamp column, `first_row` is the index of the first value in the file.
+    fn extract_logical(
+        &mut self,
+        name: &str,
+        logical_type: LogicalType,
+        first_row: usize,
+    ) -> Result<Vec<RawVal>, String> {
+        let mut builder = Vec::with_capacity(self.values.len());
+        let mut error = None;
+        for (row, s) in self.values.iter().enumerate() {
+            if s.is_empty() && self.allow_null {
+                self.any_null = true;
+                builder.push(RawVal::Null);
+                continue;
+            }
+            let value = match logical_type {
+                LogicalType::Bool => match s.to_lowercase().as_str() {
+                    "" | "false" | "f" | "0" => RawVal::Bool(false),
+                    "true" | "t" | "1" => RawVal::Bool(true),
+                    _ => {
+                        error = Some(format!(
+                            "can't parse {:?} as boolean (column {}, row {})",
+                            s,
+                            name,
+                            first_row + row
+                        ));
+                        break;
+                    }
+                },
+                LogicalType::Timestamp(unit) => {
+                    let timestamp = match s.parse::<i64>() {
+                        Ok(int) => Some(int),
+                        Err(_) => raw_val::parse_timestamp(s, unit),
+                    };
+                    match timestamp {
+                        Some(timestamp) => RawVal::Timestamp(timestamp, unit),
+                        None => {
+                            error = Some(format!(
+                                "can't parse {:?} as timestamp (column {}, row {})",
+                                s,
+                                name,
+                                first_row + row
+                            ));
+                            break;
+                        }
+                    }
+                }
+                LogicalType::Plain => unreachable!("plain columns don't have a logical type"),
+            };
+            builder.push(value);
+        }
+        self.clear();
+        match error {
+            Some(error) => Err(error),
+            None => Ok(builder),
+        }
+    }
+
     fn clear(&mut self) {
         self.types = ColType::nothing();
         self.values.clear();
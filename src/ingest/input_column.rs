@@ -1,6 +1,7 @@
 use locustdb_serialization::event_buffer::ColumnData;
+use locustdb_serialization::logical_type::{LogicalType, TimeUnit};
 
-use crate::Value;
+use crate::{BasicTypeColumn, Value};
 
 pub enum InputColumn {
     Int(Vec<i64>),
@@ -11,10 +12,43 @@ pub enum InputColumn {
     Str(Vec<String>),
     Null(usize),
     Mixed(Vec<Value>),
+    Bool(Vec<bool>),
+    Timestamp(TimeUnit, Vec<i64>),
 }
 
 impl InputColumn {
-    pub fn from_column_data(column_data: ColumnData, rows: u64) -> Self {
+    pub fn from_column_data(column_data: ColumnData, logical_type: LogicalType, rows: u64) -> Self {
+        match (logical_type, column_data) {
+            (LogicalType::Plain, column_data) => {
+                InputColumn::from_plain_column_data(column_data, rows)
+            }
+            (LogicalType::Bool, ColumnData::I64(data)) if data.len() as u64 == rows => {
+                InputColumn::Bool(data.into_iter().map(|b| b != 0).collect())
+            }
+            (LogicalType::Timestamp(unit), ColumnData::I64(data)) if data.len() as u64 == rows => {
+                InputColumn::Timestamp(unit, data)
+            }
+            (logical_type, column_data) => {
+                match InputColumn::from_plain_column_data(column_data, rows) {
+                    InputColumn::Mixed(data) => InputColumn::Mixed(
+                        data.into_iter()
+                            .map(|v| v.with_logical_type(logical_type))
+                            .collect(),
+                    ),
+                    InputColumn::NullableInt(rows, data) => {
+                        let mut values = vec![Value::Null; rows as usize];
+                        for (i, v) in data {
+                            values[i as usize] = Value::Int(v).with_logical_type(logical_type);
+                        }
+                        InputColumn::Mixed(values)
+                    }
+                    column => column,
+                }
+            }
+        }
+    }
+
+    fn from_plain_column_data(column_data: ColumnData, rows: u64) -> Self {
         match column_data {
             ColumnData::Dense(data) => {
                 if (data.len() as u64) < rows {
@@ -69,6 +103,22 @@ impl InputColumn {
             InputColumn::NullableInt(rows, _) => *rows as usize,
             InputColumn::Mixed(data) => data.len(),
             InputColumn::Null(rows) => *rows,
+            InputColumn::Bool(data) => data.len(),
+            InputColumn::Timestamp(_, data) => data.len(),
+        }
+    }
+}
+
+impl From<BasicTypeColumn> for InputColumn {
+    fn from(column: BasicTypeColumn) -> Self {
+        match column {
+            BasicTypeColumn::Int(data) => InputColumn::Int(data),
+            BasicTypeColumn::Float(data) => InputColumn::Float(data),
+            BasicTypeColumn::String(data) => InputColumn::Str(data),
+            BasicTypeColumn::Null(rows) => InputColumn::Null(rows),
+            BasicTypeColumn::Mixed(data) => InputColumn::Mixed(data),
+            BasicTypeColumn::Bool(data) => InputColumn::Bool(data),
+            BasicTypeColumn::Timestamp(unit, data) => InputColumn::Timestamp(unit, data),
         }
     }
 }
@@ -1,8 +1,10 @@
 use std::fmt;
 use std::mem;
 
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat};
 use datasize::DataSize;
 use locustdb_serialization::api::AnyVal;
+use locustdb_serialization::logical_type::{LogicalType, TimeUnit};
 use ordered_float::OrderedFloat;
 use serde::{Deserialize, Serialize};
 
@@ -14,6 +16,8 @@ pub enum RawVal {
     Float(OrderedFloat<f64>),
     Str(String),
     Null,
+    Bool(bool),
+    Timestamp(i64, TimeUnit),
 }
 
 impl RawVal {
@@ -23,6 +27,8 @@ impl RawVal {
             RawVal::Str(_) => BasicType::String,
             RawVal::Null => BasicType::Null,
             RawVal::Float(_) => BasicType::Float,
+            RawVal::Bool(_) => BasicType::Boolean,
+            RawVal::Timestamp(..) => BasicType::Integer,
         }
     }
 
@@ -32,10 +38,56 @@ impl RawVal {
             RawVal::Str(ref s) => s.capacity() * mem::size_of::<u8>(),
             RawVal::Null => 0,
             RawVal::Float(_) => 0,
+            RawVal::Bool(_) => 0,
+            RawVal::Timestamp(..) => 0,
+        }
+    }
+
+    /// Reinterprets an integer as a value of the given logical type.
+    pub fn with_logical_type(self, logical_type: LogicalType) -> RawVal {
+        match (self, logical_type) {
+            (RawVal::Int(i), LogicalType::Bool) => RawVal::Bool(i != 0),
+            (RawVal::Int(i), LogicalType::Timestamp(unit)) => RawVal::Timestamp(i, unit),
+            (val, _) => val,
+        }
+    }
+
+    pub fn logical_type(&self) -> LogicalType {
+        match *self {
+            RawVal::Bool(_) => LogicalType::Bool,
+            RawVal::Timestamp(_, unit) => LogicalType::Timestamp(unit),
+            _ => LogicalType::Plain,
         }
     }
 }
 
+/// Parses RFC 3339 timestamps, `YYYY-MM-DD HH:MM:SS[.fff]` and `YYYY-MM-DD` (both UTC) into the given unit.
+pub fn parse_timestamp(timestamp: &str, unit: TimeUnit) -> Option<i64> {
+    let datetime = DateTime::parse_from_rfc3339(timestamp)
+        .map(|datetime| datetime.naive_utc())
+        .or_else(|_| NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S%.f"))
+        .or_else(|_| NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S%.f"))
+        .or_else(|_| {
+            NaiveDate::parse_from_str(timestamp, "%Y-%m-%d")
+                .map(|date| date.and_hms_opt(0, 0, 0).unwrap())
+        })
+        .ok()?
+        .and_utc();
+    let seconds = datetime.timestamp().checked_mul(unit.per_second())?;
+    let subsecond = datetime.timestamp_subsec_nanos() as i64 / (1_000_000_000 / unit.per_second());
+    seconds.checked_add(subsecond)
+}
+
+/// Formats a timestamp as RFC 3339 string in UTC, with only as many fractional digits as required.
+pub fn format_timestamp(timestamp: i64, unit: TimeUnit) -> String {
+    let per_second = unit.per_second();
+    let nanos = timestamp.rem_euclid(per_second) * (1_000_000_000 / per_second);
+    match DateTime::from_timestamp(timestamp.div_euclid(per_second), nanos as u32) {
+        Some(datetime) => datetime.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        None => timestamp.to_string(),
+    }
+}
+
 impl fmt::Display for RawVal {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -43,12 +95,14 @@ impl fmt::Display for RawVal {
             RawVal::Int(i) => write!(f, "{}", i),
             RawVal::Str(ref s) => write!(f, "\"{}\"", s),
             RawVal::Float(x) => write!(f, "{:e}", x),
+            RawVal::Bool(b) => write!(f, "{}", b),
+            RawVal::Timestamp(t, unit) => write!(f, "{}", format_timestamp(t, unit)),
         }
     }
 }
 
 pub mod syntax {
-    pub use super::RawVal::{Int, Null};
+    pub use super::RawVal::{Bool, Int, Null, Timestamp};
 
     #[allow(non_snake_case)]
     pub fn Str(s: &str) -> super::RawVal {
@@ -67,6 +121,12 @@ impl From<f64> for RawVal {
     }
 }
 
+impl From<bool> for RawVal {
+    fn from(val: bool) -> Self {
+        RawVal::Bool(val)
+    }
+}
+
 impl From<String> for RawVal {
     fn from(val: String) -> Self {
         RawVal::Str(val)
@@ -118,6 +178,8 @@ impl From<RawVal> for AnyVal {
             RawVal::Float(f) => AnyVal::Float(f.0),
             RawVal::Str(s) => AnyVal::Str(s),
             RawVal::Null => AnyVal::Null,
+            RawVal::Bool(b) => AnyVal::Int(b as i64),
+            RawVal::Timestamp(t, _) => AnyVal::Int(t),
         }
     }
 }
@@ -128,7 +190,11 @@ impl DataSize for RawVal {
     fn estimate_heap_size(&self) -> usize {
         match *self {
             RawVal::Str(ref s) => s.capacity() * mem::size_of::<u8>(),
-            RawVal::Int(_) | RawVal::Float(_) | RawVal::Null => 0,
+            RawVal::Int(_)
+            | RawVal::Float(_)
+            | RawVal::Null
+            | RawVal::Bool(_)
+            | RawVal::Timestamp(..) => 0,
         }
     }
 }
@@ -1,3 +1,5 @@
+use locustdb_serialization::logical_type::TimeUnit;
+
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub struct Schema {
     pub column_names: Option<Vec<String>>,
@@ -16,6 +18,8 @@ pub enum ColumnType {
     Integer,
     NullableString,
     NullableInteger,
+    Boolean,
+    Timestamp(TimeUnit),
     Drop,
 }
 
@@ -73,6 +77,11 @@ impl ColumnSchema {
             "ninteger" | "nint" | "ni" => ColumnType::NullableInteger,
             "string" | "s" => ColumnType::String,
             "nstring" | "ns" => ColumnType::NullableString,
+            "boolean" | "bool" | "b" => ColumnType::Boolean,
+            "timestamp" | "ts" => ColumnType::Timestamp(TimeUnit::Second),
+            "timestamp_ms" | "ts_ms" => ColumnType::Timestamp(TimeUnit::Millisecond),
+            "timestamp_us" | "ts_us" => ColumnType::Timestamp(TimeUnit::Microsecond),
+            "timestamp_ns" | "ts_ns" => ColumnType::Timestamp(TimeUnit::Nanosecond),
             "" => ColumnType::Drop,
             _ => return Err(format!("Unrecognized type {}.", s)),
         };
@@ -127,6 +136,19 @@ mod tests {
         });
         let actual = Schema::parse("i,ns,string,nint");
         assert_eq!(expected, actual);
+        assert_eq!(
+            Schema::parse("flag:bool,time:ts_ms").map(|s| s.column_schemas),
+            Ok(vec![
+                ColumnSchema {
+                    types: ColumnType::Boolean,
+                    transformation: None,
+                },
+                ColumnSchema {
+                    types: ColumnType::Timestamp(TimeUnit::Millisecond),
+                    transformation: None,
+                },
+            ])
+        );
         assert!(
             Schema::parse(&nyc_schema()).is_ok(),
             "{:?}",
@@ -18,6 +18,8 @@
 extern crate lazy_static;
 #[macro_use]
 extern crate log;
+pub use crate::disk_store::file_writer::BlobWriter;
+pub use crate::disk_store::meta_store::{MaxAge, RetentionPolicy};
 pub use crate::disk_store::noop_storage::NoopStorage;
 
 pub use crate::engine::query_task::{BasicTypeColumn, QueryOutput};
@@ -31,6 +33,7 @@ pub use crate::ingest::raw_val::RawVal as Value;
 pub use crate::locustdb::LocustDB;
 pub use crate::locustdb::Options;
 pub use crate::mem_store::table::TableStats;
+pub use locustdb_serialization::logical_type::{LogicalType, TimeUnit};
 
 #[macro_use]
 mod errors;
@@ -44,6 +47,7 @@ mod mem_store;
 pub mod observability;
 mod scheduler;
 pub mod server;
+mod sketch;
 mod stringpack;
 mod syntax;
 pub mod unit_fmt;
@@ -1,24 +1,41 @@
+use std::collections::HashMap;
 use std::error::Error;
 use std::path::PathBuf;
+use std::mem;
 use std::str;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use futures::channel::oneshot;
-use locustdb_serialization::event_buffer::EventBuffer;
+use futures::future;
+use itertools::Itertools;
+use locustdb_serialization::event_buffer::{ColumnBuffer, ColumnData, EventBuffer, TableBuffer};
+use regex::Regex;
 
+use crate::disk_store::file_writer::BlobWriter;
+use crate::disk_store::meta_store::RetentionPolicy;
+use crate::disk_store::PartitionID;
+use crate::engine::data_types::BasicType;
 use crate::engine::query_task::QueryTask;
+use crate::engine::{JoinTable, Mutation, Query, MAX_JOIN_TABLE_ROWS};
+use crate::ingest::buffer::Buffer;
 use crate::ingest::colgen::GenTable;
 use crate::ingest::csv_loader::{CSVIngestionTask, Options as LoadOptions};
-use crate::observability::{metrics, PerfCounter};
+use crate::ingest::input_column::InputColumn;
+use crate::mem_store::partition::Partition;
+use crate::ingest::raw_val::RawVal;
+use crate::observability::{metrics, PerfCounter, QueryProgress, QueryRegistry, TrackedQuery, QUERIES_TABLE};
 use crate::scheduler::*;
-use crate::syntax::parser;
+use crate::syntax::parser::{self, ParsedStatement};
 use crate::QueryError;
+use crate::QueryOutput;
 use crate::QueryResult;
-use crate::{mem_store::*, BasicTypeColumn};
+use crate::{mem_store::*, BasicTypeColumn, TimeUnit};
 
 // Cannot implement Clone on LocustDB without changing Drop implementation.
 pub struct LocustDB {
     inner_locustdb: Arc<InnerLocustDB>,
+    queries: QueryRegistry,
 }
 
 impl LocustDB {
@@ -30,6 +47,7 @@ impl LocustDB {
         opts.validate().expect("Invalid options");
         LocustDB {
             inner_locustdb: InnerLocustDB::new(opts),
+            queries: QueryRegistry::default(),
         }
     }
 
@@ -40,37 +58,124 @@ impl LocustDB {
         rowformat: bool,
         show: Vec<usize>,
     ) -> QueryResult {
-        let (sender, receiver) = oneshot::channel();
+        self.run_query_with_timeout(query, explain, rowformat, show, None).await
+    }
+
+    /// Runs `query` and aborts it with `QueryError::Aborted` once `timeout` (or `Options::query_timeout` if
+    /// `None`) has elapsed, or when it is canceled with `cancel_query`.
+    pub async fn run_query_with_timeout(
+        &self,
+        query: &str,
+        explain: bool,
+        rowformat: bool,
+        show: Vec<usize>,
+        timeout: Option<Duration>,
+    ) -> QueryResult {
         metrics::QUERY_COUNT.inc();
 
+        let tracked = self
+            .queries
+            .register(query, timeout.or(self.inner_locustdb.opts().query_timeout));
+        let _cancel_on_drop = CancelOnDrop {
+            queries: &self.queries,
+            tracked: &tracked,
+        };
+        log::debug!("Running query {}: {}", tracked.id, query);
         // PERF: perform compilation and table snapshot in asynchronous task?
-        let query = match parser::parse_query(query) {
-            Ok(query) => query,
-            Err(err) => return Err(err),
+        let (result, log) = match parser::parse_statement(query) {
+            Ok(ParsedStatement::Query(parsed_query)) => {
+                // Queries that inspect the query log or registry are not logged themselves
+                let log = !self.reads_query_tables(&parsed_query);
+                let result = self
+                    .run_parsed_query(*parsed_query, explain, rowformat, show, &tracked)
+                    .await;
+                (result, log)
+            }
+            Ok(ParsedStatement::Mutation(mutation)) => (self.run_mutation(mutation, rowformat).await, true),
+            Err(err) => (Err(err), true),
         };
+        self.queries.complete(&tracked, Some(&result));
+        if log {
+            self.log_query(&tracked);
+        }
+        result
+    }
+
+    /// Records `query` in the query log table, if enabled.
+    fn log_query(&self, query: &TrackedQuery) {
+        if let Some(table) = &self.inner_locustdb.opts().query_log_table_name {
+            self.inner_locustdb
+                .ingest_efficient(query_log_events(table, &query.progress()));
+        }
+    }
+
+    /// Whether `query` reads from the query log table or the `_queries` table.
+    fn reads_query_tables(&self, query: &Query) -> bool {
+        let log_table = self.inner_locustdb.opts().query_log_table_name.as_deref();
+        let is_query_table = |table: &str| table == QUERIES_TABLE || Some(table) == log_table;
+        is_query_table(&query.table)
+            || query.join.as_ref().is_some_and(|join| is_query_table(&join.table))
+            || query.subqueries.iter().any(|subquery| self.reads_query_tables(subquery))
+    }
+
+    /// Aborts the running query with the given id. Returns `false` if there is no such query.
+    pub fn cancel_query(&self, id: u64) -> bool {
+        self.queries.cancel(id)
+    }
+
+    /// Returns the progress of all running and recently completed queries, which are also listed by the `_queries`
+    /// table.
+    pub fn queries(&self) -> Vec<QueryProgress> {
+        self.queries.progress()
+    }
+
+    /// Runs a DELETE, TRUNCATE or DROP TABLE statement and returns the number of deleted rows.
+    async fn run_mutation(&self, mutation: Mutation, rowformat: bool) -> QueryResult {
+        let rows_deleted = self.inner_locustdb.schedule_mutation(mutation).await?? as i64;
+        Ok(QueryOutput {
+            colnames: vec!["rows_deleted".to_string()],
+            rows: if rowformat { Some(vec![vec![RawVal::Int(rows_deleted)]]) } else { None },
+            columns: vec![("rows_deleted".to_string(), BasicTypeColumn::Int(vec![rows_deleted]))],
+            query_plans: Default::default(),
+            stats: Default::default(),
+        })
+    }
+
+    async fn run_parsed_query(
+        &self,
+        mut query: Query,
+        explain: bool,
+        rowformat: bool,
+        show: Vec<usize>,
+        tracked: &TrackedQuery,
+    ) -> QueryResult {
+        let (sender, receiver) = oneshot::channel();
+        let subquery_outputs = if !query.subqueries.is_empty() {
+            let subqueries = mem::take(&mut query.subqueries).into_iter().map(|subquery| {
+                Box::pin(self.run_parsed_query(subquery, false, false, vec![], tracked))
+            });
+            Some(union_outputs(future::try_join_all(subqueries).await?)?)
+        } else if query.table == QUERIES_TABLE {
+            Some(vec![queries_table(self.queries.progress())])
+        } else {
+            None
+        };
+        if query.join.is_some() {
+            let left_columns = match &subquery_outputs {
+                Some(outputs) => outputs[0].colnames.clone(),
+                None => self.column_names(&query.table).await?,
+            };
+            self.materialize_join(&mut query, &left_columns, tracked).await?;
+        }
 
         let referenced_cols = query.find_referenced_cols();
         let colsvec;
-        let all_cols = if referenced_cols.contains("*") {
-            let colnames = self
-                .inner_locustdb
-                .schedule_query_column_names(&query.table)?
-                .await?;
-            match colnames {
-                Ok(results) => match &results.columns[..] {
-                    [(_, BasicTypeColumn::String(names))] => Some(names.to_vec()),
-                    _ => {
-                        return Err(fatal!(
-                            "Expected string column when querying _meta_columns_{}, got {:?}",
-                            query.table,
-                            results
-                        ))
-                    }
-                },
-                Err(err) => return Err(err),
-            }
-        } else {
+        let all_cols = if !referenced_cols.contains("*") {
             None
+        } else if let Some(outputs) = &subquery_outputs {
+            Some(outputs[0].colnames.clone())
+        } else {
+            Some(self.column_names(&query.table).await?.into_iter().sorted().collect())
         };
         let column_filter = if referenced_cols.contains("*") {
             None
@@ -78,14 +183,9 @@ impl LocustDB {
             colsvec = referenced_cols.into_iter().collect::<Vec<_>>();
             Some(&colsvec[..])
         };
-        let data = match self.inner_locustdb.snapshot(&query.table, column_filter) {
-            Some(data) => data,
-            None => {
-                return Err(QueryError::NotImplemented(format!(
-                    "Table {} does not exist!",
-                    &query.table
-                )))
-            }
+        let data = match subquery_outputs {
+            Some(outputs) => subquery_partitions(&query.table, outputs)?,
+            None => self.snapshot(&query.table, column_filter)?,
         };
 
         let query_task = QueryTask::new(
@@ -98,6 +198,8 @@ impl LocustDB {
             SharedSender::new(sender),
             self.inner_locustdb.opts().batch_size,
             all_cols,
+            tracked.cancellation.clone(),
+            tracked.perf_counter.clone(),
         );
 
         match query_task {
@@ -114,6 +216,83 @@ impl LocustDB {
         }
     }
 
+    /// Returns the names of all columns of `table`, or of any table matching `table` if it is a pattern.
+    async fn column_names(&self, table: &str) -> Result<Vec<String>, QueryError> {
+        let mut names = Vec::new();
+        for table in self.matching_tables(table)? {
+            let results = self
+                .inner_locustdb
+                .schedule_query_column_names(&table)?
+                .await??;
+            match &results.columns[..] {
+                [(_, BasicTypeColumn::String(table_names))] => names.extend(table_names.iter().cloned()),
+                _ => {
+                    return Err(fatal!(
+                        "Expected string column when querying _meta_columns_{}, got {:?}",
+                        table,
+                        results
+                    ))
+                }
+            }
+        }
+        Ok(names.into_iter().unique().collect())
+    }
+
+    /// Snapshots the partitions of `table`, or of all tables matching `table` if it is a pattern such as `runs/*`.
+    fn snapshot(
+        &self,
+        table: &str,
+        column_filter: Option<&[String]>,
+    ) -> Result<Vec<Arc<Partition>>, QueryError> {
+        let mut partitions = Vec::new();
+        for table in self.matching_tables(table)? {
+            match self.inner_locustdb.snapshot(&table, column_filter) {
+                Some(data) => partitions.extend(data),
+                None => bail!(QueryError::NotImplemented, "Table {} does not exist!", table),
+            }
+        }
+        Ok(partitions)
+    }
+
+    fn matching_tables(&self, table: &str) -> Result<Vec<String>, QueryError> {
+        if !is_table_pattern(table) {
+            return Ok(vec![table.to_string()]);
+        }
+        let pattern = format!(
+            "^{}$",
+            regex::escape(table).replace(r"\*", ".*").replace(r"\?", ".")
+        );
+        let pattern = Regex::new(&pattern).map_err(|e| fatal!("{}", e))?;
+        let tables = self
+            .inner_locustdb
+            .table_names()
+            .into_iter()
+            .filter(|name| pattern.is_match(name))
+            .collect::<Vec<_>>();
+        if tables.is_empty() {
+            bail!(QueryError::NotImplemented, "No table matches {}!", table)
+        }
+        Ok(tables)
+    }
+
+    /// Reads the full contents of the table joined by `query` so it can be broadcast to all partitions.
+    async fn materialize_join(
+        &self,
+        query: &mut Query,
+        left_columns: &[String],
+        tracked: &TrackedQuery,
+    ) -> Result<(), QueryError> {
+        let join = query.join.as_ref().unwrap();
+        let rows_query = parser::parse_query(&format!(
+            "SELECT * FROM \"{}\" LIMIT {}",
+            join.table,
+            MAX_JOIN_TABLE_ROWS + 1
+        ))?;
+        let result = Box::pin(self.run_parsed_query(rows_query, false, true, vec![], tracked)).await?;
+        let table = JoinTable::new(join, &result.colnames, result.rows.unwrap_or_default())?;
+        query.resolve_join(table, left_columns)
+    }
+
     pub async fn load_csv(&self, options: LoadOptions) -> Result<(), Box<dyn Error>> {
         let (sender, receiver) = oneshot::channel();
         let task = CSVIngestionTask::new(
@@ -211,6 +390,21 @@ impl LocustDB {
     pub fn evict_cache(&self) -> usize {
         self.inner_locustdb.evict_cache()
     }
+
+    /// Sets the retention policy of `table`, or removes it if `policy` is `None`.
+    /// Policies are persisted and enforced in the background every `Options::retention_interval` seconds.
+    pub fn set_retention_policy(&self, table: &str, policy: Option<RetentionPolicy>) {
+        self.inner_locustdb.set_retention_policy(table, policy)
+    }
+
+    pub fn retention_policies(&self) -> HashMap<String, RetentionPolicy> {
+        self.inner_locustdb.retention_policies()
+    }
+
+    /// Immediately deletes partitions that exceed retention policies and returns the number of deleted partitions.
+    pub fn apply_retention_policies(&self) -> usize {
+        self.inner_locustdb.apply_retention_policies()
+    }
 }
 
 #[derive(Clone)]
@@ -241,6 +435,17 @@ pub struct Options {
     pub metrics_interval: u64,
     /// Internal metrics table name
     pub metrics_table_name: Option<String>,
+    /// Queries that run for longer than this are aborted, unless a different timeout is given for the query
+    pub query_timeout: Option<Duration>,
+    /// Internal table that records every query executed by `LocustDB::run_query`, disabled if `None`
+    pub query_log_table_name: Option<String>,
+    /// Interval in seconds at which partitions that exceed table retention policies are deleted
+    pub retention_interval: u64,
+    /// Storage backend for `db_path`, overrides the backend selected by the prefix of `db_path` (gs://, az://, s3://).
+    /// Requires `db_path` to be set.
+    pub blob_writer: Option<Arc<dyn BlobWriter>>,
+    /// Endpoint of an S3-compatible object store such as MinIO that is used for s3:// paths instead of AWS
+    pub s3_endpoint: Option<String>,
 }
 
 impl Default for Options {
@@ -262,6 +467,11 @@ impl Default for Options {
             io_threads: 1,
             metrics_interval: 15,
             metrics_table_name: Some("_metrics".to_string()),
+            query_timeout: None,
+            query_log_table_name: None,
+            retention_interval: 60,
+            blob_writer: None,
+            s3_endpoint: None,
         }
     }
 }
@@ -280,12 +490,198 @@ impl Options {
         if self.batch_size % 8 != 0 {
             return Err("batch_size must be a multiple of 8".to_string());
         }
+        if self.blob_writer.is_some() && self.db_path.is_none() {
+            return Err("blob_writer requires db_path to be set".to_string());
+        }
         Ok(())
     }
 }
 
+/// Aborts the tasks of a query whose future is dropped before the query completes, and records it as canceled.
+struct CancelOnDrop<'a> {
+    queries: &'a QueryRegistry,
+    tracked: &'a TrackedQuery,
+}
+
+impl Drop for CancelOnDrop<'_> {
+    fn drop(&mut self) {
+        if self.queries.complete(self.tracked, None) {
+            self.tracked.cancellation.cancel();
+        }
+    }
+}
+
 impl Drop for LocustDB {
     fn drop(&mut self) {
         self.inner_locustdb.stop();
     }
 }
+
+/// Table names containing `*` or `?` are patterns that query all matching tables at once.
+fn is_table_pattern(table: &str) -> bool {
+    table.contains(['*', '?'])
+}
+
+/// Renames the columns of all subquery outputs to the column names of the first output, which are matched up by position
+/// as in `UNION ALL`.
+fn union_outputs(mut outputs: Vec<QueryOutput>) -> Result<Vec<QueryOutput>, QueryError> {
+    let colnames = outputs[0].colnames.clone();
+    let mut types = outputs[0].columns.iter().map(|(_, column)| value_types(column)).collect::<Vec<_>>();
+    for output in &outputs[1..] {
+        if output.colnames.len() != colnames.len() {
+            bail!(
+                QueryError::TypeError,
+                "UNION ALL of queries with {} and {} columns",
+                colnames.len(),
+                output.colnames.len()
+            )
+        }
+        for ((i, (_, column)), colname) in output.columns.iter().enumerate().zip(&colnames) {
+            let column_types = value_types(column);
+            if types[i].is_empty() {
+                types[i] = column_types;
+            } else if !column_types.is_empty() && column_types != types[i] {
+                bail!(
+                    QueryError::TypeError,
+                    "UNION ALL of column {} with types {:?} and {:?}",
+                    colname,
+                    types[i],
+                    column_types
+                )
+            }
+        }
+    }
+    for output in &mut outputs[1..] {
+        output.colnames.clone_from(&colnames);
+        for ((name, _), colname) in output.columns.iter_mut().zip(&colnames) {
+            name.clone_from(colname);
+        }
+    }
+    Ok(outputs)
+}
+
+/// Types of the non-null values in `column`, timestamps count as integers.
+fn value_types(column: &BasicTypeColumn) -> Vec<BasicType> {
+    if column.is_empty() {
+        return vec![];
+    }
+    match column {
+        BasicTypeColumn::Int(_) | BasicTypeColumn::Timestamp(_, _) => vec![BasicType::Integer],
+        BasicTypeColumn::Float(_) => vec![BasicType::Float],
+        BasicTypeColumn::String(_) => vec![BasicType::String],
+        BasicTypeColumn::Bool(_) => vec![BasicType::Boolean],
+        BasicTypeColumn::Null(_) => vec![],
+        BasicTypeColumn::Mixed(values) => {
+            let mut types = vec![];
+            for t in values.iter().map(RawVal::get_type) {
+                if t != BasicType::Null && !types.contains(&t) {
+                    types.push(t);
+                }
+            }
+            types.sort_by_key(|t| *t as u8);
+            types
+        }
+    }
+}
+
+/// Row of the query log table that records `query`.
+fn query_log_events(table: &str, query: &QueryProgress) -> EventBuffer {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    let int_column = |value: i64| ColumnBuffer {
+        data: ColumnData::I64(vec![value]),
+        ..Default::default()
+    };
+    let string_column = |value: &str| ColumnBuffer {
+        data: ColumnData::String(vec![value.to_string()]),
+        ..Default::default()
+    };
+    let mut columns = HashMap::from([
+        ("timestamp".to_string(), int_column(timestamp)),
+        ("query".to_string(), string_column(&query.query)),
+        ("status".to_string(), string_column(query.status.as_str())),
+        ("runtime_ns".to_string(), int_column(query.runtime_ns as i64)),
+        ("rows_scanned".to_string(), int_column(query.rows_scanned as i64)),
+        ("files_opened".to_string(), int_column(query.files_opened as i64)),
+        ("disk_read_bytes".to_string(), int_column(query.disk_read_bytes as i64)),
+    ]);
+    if let Some(error) = &query.error {
+        columns.insert("error".to_string(), string_column(error));
+    }
+    EventBuffer {
+        tables: HashMap::from([(table.to_string(), TableBuffer::new(columns))]),
+    }
+}
+
+/// Contents of the `_queries` table.
+fn queries_table(queries: Vec<QueryProgress>) -> QueryOutput {
+    let columns = vec![
+        ("id".to_string(), BasicTypeColumn::Int(queries.iter().map(|q| q.id as i64).collect())),
+        ("query".to_string(), BasicTypeColumn::String(queries.iter().map(|q| q.query.clone()).collect())),
+        (
+            "start_time".to_string(),
+            BasicTypeColumn::Timestamp(TimeUnit::Nanosecond, queries.iter().map(|q| q.start_time_ns).collect()),
+        ),
+        ("runtime_ns".to_string(), BasicTypeColumn::Int(queries.iter().map(|q| q.runtime_ns as i64).collect())),
+        (
+            "partitions_scanned".to_string(),
+            BasicTypeColumn::Int(queries.iter().map(|q| q.partitions_scanned as i64).collect()),
+        ),
+        ("rows_scanned".to_string(), BasicTypeColumn::Int(queries.iter().map(|q| q.rows_scanned as i64).collect())),
+        ("files_opened".to_string(), BasicTypeColumn::Int(queries.iter().map(|q| q.files_opened as i64).collect())),
+        (
+            "disk_read_bytes".to_string(),
+            BasicTypeColumn::Int(queries.iter().map(|q| q.disk_read_bytes as i64).collect()),
+        ),
+        (
+            "status".to_string(),
+            BasicTypeColumn::String(queries.iter().map(|q| q.status.as_str().to_string()).collect()),
+        ),
+        (
+            "error".to_string(),
+            BasicTypeColumn::Mixed(
+                queries
+                    .iter()
+                    .map(|q| q.error.clone().map_or(RawVal::Null, RawVal::Str))
+                    .collect(),
+            ),
+        ),
+    ];
+    QueryOutput {
+        colnames: columns.iter().map(|(name, _)| name.clone()).collect(),
+        rows: None,
+        columns,
+        query_plans: Default::default(),
+        stats: Default::default(),
+    }
+}
+
+/// Converts the outputs of subqueries into in-memory partitions of the table queried by the outer query.
+fn subquery_partitions(table: &str, outputs: Vec<QueryOutput>) -> Result<Vec<Arc<Partition>>, QueryError> {
+    if let Some(name) = outputs.first().and_then(|output| output.colnames.iter().duplicates().next()) {
+        bail!(QueryError::NotImplemented, "Subquery returns multiple columns named {}", name)
+    }
+    let mut partitions = Vec::with_capacity(outputs.len());
+    let mut offset = 0;
+    for output in outputs {
+        let len = output.columns.first().map_or(0, |(_, column)| column.len());
+        if len == 0 {
+            continue;
+        }
+        let mut buffer = Buffer::default();
+        buffer.push_typed_cols(
+            output
+                .columns
+                .into_iter()
+                .map(|(name, column)| (name, InputColumn::from(column)))
+                .collect(),
+        );
+        let id = partitions.len() as PartitionID;
+        let (partition, _) = Partition::from_buffer(table, id, buffer, Lru::default(), offset);
+        partitions.push(Arc::new(partition));
+        offset += len;
+    }
+    Ok(partitions)
+}
@@ -100,6 +100,7 @@ impl LoggingClient {
                 mantissa: None,
                 full_precision_cols: Default::default(),
             }),
+            timeout_ms: None,
         };
         let response = self
             .query_client
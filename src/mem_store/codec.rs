@@ -267,6 +267,28 @@ impl Codec {
         }
     }
 
+    /// For dictionary encoded strings, returns the dictionary indices together with the offsets and data of the dictionary.
+    pub fn decode_dictionary(
+        &self,
+        plan: TypedBufferRef,
+        planner: &mut QueryPlanner,
+    ) -> Option<(TypedBufferRef, BufferRef<u64>, BufferRef<u8>)> {
+        match self.ops[..] {
+            [CodecOp::PushDataSection(1), CodecOp::PushDataSection(2), CodecOp::DictLookup(_)] => {
+                let offset_len = planner
+                    .column_section(&self.column_name, 1, None, EncodingType::U64)
+                    .u64()
+                    .unwrap();
+                let backing_store = planner
+                    .column_section(&self.column_name, 2, None, EncodingType::U8)
+                    .u8()
+                    .unwrap();
+                Some((plan, offset_len, backing_store))
+            }
+            _ => None,
+        }
+    }
+
     pub fn encode_int(&self, x: i64) -> i64 {
         if let CodecOp::Add(_, y) = self.ops[0] {
             assert_eq!(self.ops.len(), 1);
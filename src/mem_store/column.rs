@@ -2,14 +2,18 @@ use pco::standalone::{simple_decompress, simpler_compress};
 use pco::DEFAULT_COMPRESSION_LEVEL;
 use std::fmt;
 use std::mem;
+use std::ops::Deref;
 use std::sync::Arc;
 
+use locustdb_serialization::logical_type::LogicalType;
 use ordered_float::OrderedFloat;
 use serde::{Deserialize, Serialize};
 
+use crate::disk_store::meta_store::ColumnRange;
 use crate::engine::data_types::*;
+use crate::ingest::raw_val::RawVal;
 use crate::mem_store::*;
-use crate::stringpack::StringPackerIterator;
+use crate::stringpack::{PackedBytesIterator, StringPackerIterator};
 
 #[derive(Serialize, Deserialize)]
 pub struct Column {
@@ -18,6 +22,11 @@ pub struct Column {
     range: Option<(i64, i64)>,
     codec: Codec,
     data: Vec<DataSection>,
+    #[serde(default)]
+    logical_type: LogicalType,
+    // Smallest and largest non-null value, determined when the column is built from a `ColumnBuffer`
+    #[serde(skip)]
+    value_range: Option<(RawVal, RawVal)>,
 }
 
 pub trait DataSource: fmt::Debug + Sync + Send {
@@ -28,11 +37,29 @@ pub trait DataSource: fmt::Debug + Sync + Send {
     fn data_sections<'a>(&'a self) -> Vec<&'a dyn Data<'a>>;
     fn full_type(&self) -> Type;
 
-    fn decode<'a>(&'a self) -> BoxedData<'a> {
+    fn logical_type(&self) -> LogicalType {
+        LogicalType::Plain
+    }
+
+    fn decode<'a>(&'a self) -> DecodedData<'a> {
         decode(&self.codec(), &self.data_sections())
     }
 }
 
+/// Fully decoded column data together with any intermediate buffers that the decoded data borrows from.
+pub struct DecodedData<'a> {
+    data: BoxedData<'a>,
+    _buffers: Vec<BoxedData<'a>>,
+}
+
+impl<'a> Deref for DecodedData<'a> {
+    type Target = dyn Data<'a> + 'a;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.data
+    }
+}
+
 impl<T: DataSource> DataSource for Arc<T> {
     fn encoding_type(&self) -> EncodingType {
         (**self).encoding_type()
@@ -52,6 +79,9 @@ impl<T: DataSource> DataSource for Arc<T> {
     fn full_type(&self) -> Type {
         (**self).full_type()
     }
+    fn logical_type(&self) -> LogicalType {
+        (**self).logical_type()
+    }
 }
 
 impl DataSource for Column {
@@ -78,6 +108,9 @@ impl DataSource for Column {
     fn full_type(&self) -> Type {
         Type::new(self.basic_type(), self.codec())
     }
+    fn logical_type(&self) -> LogicalType {
+        self.logical_type
+    }
 }
 
 impl Column {
@@ -100,6 +133,8 @@ impl Column {
             range,
             codec,
             data,
+            logical_type: LogicalType::Plain,
+            value_range: None,
         }
     }
 
@@ -110,6 +145,8 @@ impl Column {
             range: None,
             codec: Codec::identity(BasicType::Null),
             data: vec![DataSection::Null(len)],
+            logical_type: LogicalType::Plain,
+            value_range: None,
         }
     }
 
@@ -146,6 +183,9 @@ impl Column {
     pub fn data(&self) -> &[DataSection] {
         &self.data
     }
+    pub fn set_logical_type(&mut self, logical_type: LogicalType) {
+        self.logical_type = logical_type;
+    }
     pub fn basic_type(&self) -> BasicType {
         self.codec.decoded_type()
     }
@@ -160,6 +200,21 @@ impl Column {
             .sum()
     }
 
+    pub fn set_value_range(&mut self, min: RawVal, max: RawVal) {
+        self.value_range = Some((min, max));
+    }
+
+    /// Smallest and largest non-null value of the column, `None` if the column has no non-null values, is not of
+    /// integer, float or string type, or was not built from a `ColumnBuffer`.
+    pub fn value_range(&self) -> Option<ColumnRange> {
+        let (min, max) = self.value_range.clone()?;
+        let timestamp_unit = match self.logical_type {
+            LogicalType::Timestamp(unit) => Some(unit),
+            _ => None,
+        };
+        Some(ColumnRange { min, max, timestamp_unit })
+    }
+
     pub fn mem_tree(&self, tree: &mut MemTreeColumn, depth: usize) {
         if depth == 0 {
             return;
@@ -581,7 +636,8 @@ impl From<Vec<OrderedFloat<f64>>> for DataSection {
     }
 }
 
-fn decode<'a>(codec: &Codec, sections: &[&'a dyn Data<'a>]) -> BoxedData<'a> {
+fn decode<'a>(codec: &Codec, sections: &[&'a dyn Data<'a>]) -> DecodedData<'a> {
+    let mut buffers = Vec::new();
     let mut section_stack: Vec<BoxedData<'a>> = vec![sections[0].slice_box(0, sections[0].len())];
     for codec_op in codec.ops() {
         let arg0 = section_stack.first().unwrap();
@@ -748,6 +804,16 @@ fn decode<'a>(codec: &Codec, sections: &[&'a dyn Data<'a>]) -> BoxedData<'a> {
                     lz4::decode::<u8>(&mut lz4::decoder(arg0.cast_ref_u8()), &mut decoded);
                     Box::new(decoded) as BoxedData
                 }
+                EncodingType::U16 => {
+                    let mut decoded = vec![0; *count];
+                    lz4::decode::<u16>(&mut lz4::decoder(arg0.cast_ref_u8()), &mut decoded);
+                    Box::new(decoded)
+                }
+                EncodingType::U32 => {
+                    let mut decoded = vec![0; *count];
+                    lz4::decode::<u32>(&mut lz4::decoder(arg0.cast_ref_u8()), &mut decoded);
+                    Box::new(decoded)
+                }
                 EncodingType::I64 => {
                     let mut decoded = vec![0; *count];
                     lz4::decode::<i64>(&mut lz4::decoder(arg0.cast_ref_u8()), &mut decoded);
@@ -804,20 +870,50 @@ fn decode<'a>(codec: &Codec, sections: &[&'a dyn Data<'a>]) -> BoxedData<'a> {
                 }
             }
             CodecOp::UnpackStrings => {
-                let mut output = Vec::new();
-                let packed: &'a [u8] = sections[0].cast_ref_u8();
-                let iterator = unsafe { StringPackerIterator::from_slice(packed) };
-                for str in iterator {
-                    output.push(str);
+                // Packed strings may have been decompressed by a previous op, so the unpacked strings borrow from the
+                // top of the stack which is kept alive in `buffers` for as long as the decoded data
+                let packed = section_stack.pop().unwrap();
+                let bytes = unsafe { mem::transmute::<&[u8], &'a [u8]>(packed.cast_ref_u8()) };
+                let output = unsafe { StringPackerIterator::from_slice(bytes) }.collect::<Vec<_>>();
+                buffers.push(packed);
+                section_stack.push(Box::new(output));
+                continue;
+            }
+            CodecOp::UnhexpackStrings(uppercase, total_bytes) => {
+                let packed = section_stack.pop().unwrap();
+                let bytes = unsafe { mem::transmute::<&[u8], &'a [u8]>(packed.cast_ref_u8()) };
+                let mut stringstore = Vec::with_capacity(*total_bytes);
+                let mut lengths = Vec::new();
+                for elem in PackedBytesIterator::from_slice(bytes) {
+                    let string = if *uppercase {
+                        hex::encode_upper(elem)
+                    } else {
+                        hex::encode(elem)
+                    };
+                    stringstore.extend_from_slice(string.as_bytes());
+                    lengths.push(string.len());
                 }
-                Box::new(output) as BoxedData
+                let stringstore: BoxedData<'a> = Box::new(stringstore);
+                let mut remaining = unsafe { mem::transmute::<&[u8], &'a [u8]>(stringstore.cast_ref_u8()) };
+                let mut output = Vec::with_capacity(lengths.len());
+                for len in lengths {
+                    let (string, rest) = remaining.split_at(len);
+                    output.push(unsafe { str::from_utf8_unchecked(string) });
+                    remaining = rest;
+                }
+                buffers.push(packed);
+                buffers.push(stringstore);
+                section_stack.push(Box::new(output));
+                continue;
             }
-            CodecOp::UnhexpackStrings(_, _) => todo!(),
             CodecOp::Unknown => todo!(),
         };
         section_stack.pop();
         section_stack.push(decoded);
     }
 
-    section_stack.pop().unwrap()
+    DecodedData {
+        data: section_stack.pop().unwrap(),
+        _buffers: buffers,
+    }
 }
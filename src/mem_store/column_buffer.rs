@@ -2,6 +2,8 @@ use std::cmp;
 use std::sync::Arc;
 
 use datasize::DataSize;
+use itertools::Itertools;
+use locustdb_serialization::logical_type::LogicalType;
 use ordered_float::OrderedFloat;
 
 use crate::bitvec::*;
@@ -18,6 +20,8 @@ pub struct ColumnBuffer {
     buffer: TypedBuffer,
     length: usize,
     present: Option<Vec<u8>>,
+    #[data_size(skip)]
+    logical_type: Option<LogicalType>,
 }
 
 impl ColumnBuffer {
@@ -26,18 +30,33 @@ impl ColumnBuffer {
             buffer: TypedBuffer::Empty,
             length,
             present: None,
+            logical_type: None,
         }
     }
 
     pub fn push_val(&mut self, elem: RawVal) {
+        if elem != RawVal::Null {
+            self.push_logical_type(elem.logical_type());
+        }
         match elem {
-            RawVal::Int(elem) => self.push_ints([elem], None),
+            RawVal::Int(elem) | RawVal::Timestamp(elem, _) => self.push_ints([elem], None),
+            RawVal::Bool(elem) => self.push_ints([elem as i64], None),
             RawVal::Float(ordered_float) => self.push_floats([ordered_float], None),
             RawVal::Str(s) => self.push_strings([s.as_str()], None),
             RawVal::Null => self.push_nulls(1),
         }
     }
 
+    /// Records the logical type of (non-null) values pushed to the buffer.
+    /// Columns that receive values of different logical types fall back to `LogicalType::Plain`.
+    pub fn push_logical_type(&mut self, logical_type: LogicalType) {
+        self.logical_type = match self.logical_type {
+            None => Some(logical_type),
+            Some(current) if current == logical_type => Some(current),
+            Some(_) => Some(LogicalType::Plain),
+        };
+    }
+
     pub fn push_ints<I: IntoIterator<Item = i64>>(&mut self, elems: I, present: Option<&[u8]>) {
         let mut count = 0;
         match &mut self.buffer {
@@ -296,13 +315,34 @@ impl ColumnBuffer {
     }
 
     pub fn finalize(self, name: &str) -> Arc<Column> {
-        match self.buffer {
-            TypedBuffer::Empty => Arc::new(Column::null(name, self.length)),
-            TypedBuffer::Int(buffer) => buffer.finalize(name, self.present),
-            TypedBuffer::Float(buffer) => buffer.finalize(name, self.present),
-            TypedBuffer::String(buffer) => buffer.finalize(name, self.present),
-            TypedBuffer::Mixed(buffer) => buffer.finalize(name, self.present),
+        let present = self.present.as_deref();
+        let is_int = matches!(self.buffer, TypedBuffer::Int(_));
+        let (mut column, range) = match self.buffer {
+            TypedBuffer::Empty => return Arc::new(Column::null(name, self.length)),
+            TypedBuffer::Int(buffer) => {
+                let range = buffer.range(present);
+                (buffer.finalize(name, self.present), range)
+            }
+            TypedBuffer::Float(buffer) => {
+                let range = buffer.range(present);
+                (buffer.finalize(name, self.present), range)
+            }
+            TypedBuffer::String(buffer) => {
+                let range = buffer.range(present);
+                (buffer.finalize(name, self.present), range)
+            }
+            TypedBuffer::Mixed(buffer) => (buffer.finalize(name, self.present), None),
+        };
+        let column_mut = Arc::get_mut(&mut column).expect("newly created column is not shared");
+        if let Some((min, max)) = range {
+            column_mut.set_value_range(min, max);
         }
+        if is_int {
+            if let Some(logical_type) = self.logical_type {
+                column_mut.set_logical_type(logical_type);
+            }
+        }
+        column
     }
 
     pub fn len(&self) -> usize {
@@ -347,6 +387,11 @@ impl StringColBuffer {
         self.values.push(elem);
     }
 
+    fn range(&self, present: Option<&[u8]>) -> Option<(RawVal, RawVal)> {
+        let (min, max) = present_minmax(self.values.iter(), present)?;
+        Some((RawVal::Str(min.to_string()), RawVal::Str(max.to_string())))
+    }
+
     fn finalize(self, name: &str, present: Option<Vec<u8>>) -> Arc<Column> {
         fast_build_string_column(
             name,
@@ -397,6 +442,16 @@ impl IntColBuffer {
         self.data.push(elem);
     }
 
+    fn range(&self, present: Option<&[u8]>) -> Option<(RawVal, RawVal)> {
+        let (min, max) = match present {
+            // `min` and `max` also cover the placeholder values of null entries
+            Some(present) => present_minmax(self.data.iter().copied(), Some(present))?,
+            None if self.data.is_empty() => return None,
+            None => (self.min, self.max),
+        };
+        Some((RawVal::Int(min), RawVal::Int(max)))
+    }
+
     fn finalize(self, name: &str, present: Option<Vec<u8>>) -> Arc<Column> {
         // PERF: heuristic for deciding delta encoding could probably be improved
         let delta_encode =
@@ -416,6 +471,11 @@ impl FloatColBuffer {
         self.data.push(elem);
     }
 
+    fn range(&self, present: Option<&[u8]>) -> Option<(RawVal, RawVal)> {
+        let (min, max) = present_minmax(self.data.iter().map(|&f| OrderedFloat(f)), present)?;
+        Some((RawVal::Float(min), RawVal::Float(max)))
+    }
+
     fn finalize(self, name: &str, present: Option<Vec<u8>>) -> Arc<Column> {
         FloatColumn::new_boxed(
             name,
@@ -443,6 +503,8 @@ impl MixedColBuffer {
                 RawVal::Str(s) => string_col.push(&s),
                 RawVal::Int(i) => string_col.push(&i.to_string()),
                 RawVal::Float(f) => string_col.push(&f.to_string()),
+                RawVal::Bool(b) => string_col.push(&b.to_string()),
+                RawVal::Timestamp(..) => string_col.push(&elem.to_string()),
                 RawVal::Null => {}
             }
         }
@@ -493,3 +555,16 @@ fn is_uppercase_hex(string: &str) -> bool {
                 || c == 'F'
         })
 }
+
+/// Smallest and largest of the `values` that are not null according to `present`.
+fn present_minmax<T: PartialOrd + Clone>(
+    values: impl Iterator<Item = T>,
+    present: Option<&[u8]>,
+) -> Option<(T, T)> {
+    values
+        .enumerate()
+        .filter(|&(i, _)| present.is_none_or(|present| present.is_set(i)))
+        .map(|(_, x)| x)
+        .minmax()
+        .into_option()
+}
@@ -8,6 +8,8 @@ impl RawVal {
             RawVal::Int(i) => Val::Integer(i),
             RawVal::Str(ref string) => Val::Str(string),
             RawVal::Float(f) => Val::Float(f),
+            RawVal::Bool(b) => Val::Integer(b as i64),
+            RawVal::Timestamp(t, _) => Val::Integer(t),
         }
     }
 
@@ -16,6 +18,8 @@ impl RawVal {
             RawVal::Null => Val::Null,
             RawVal::Int(i) => Val::Integer(i),
             RawVal::Float(f) => Val::Float(f),
+            RawVal::Bool(b) => Val::Integer(b as i64),
+            RawVal::Timestamp(t, _) => Val::Integer(t),
             RawVal::Str(_) => panic!("Can't convert RawVal::Str to Val::Str + 'static"),
         }
     }
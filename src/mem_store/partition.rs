@@ -10,7 +10,7 @@ use crate::mem_store::*;
 use crate::observability::QueryPerfCounter;
 use crate::scheduler::disk_read_scheduler::DiskReadScheduler;
 
-use self::meta_store::PartitionMetadata;
+use self::meta_store::{ColumnRange, PartitionMetadata};
 
 // Table, Partition, Column
 #[derive(Hash, Eq, PartialEq, Clone, Debug)]
@@ -30,6 +30,8 @@ pub struct Partition {
     ephemeral: bool,
     // Column name -> ColumnHandle
     cols: RwLock<HashMap<String, Arc<ColumnHandle>>>,
+    // Range of values of each column, available even if the column is not resident
+    column_ranges: HashMap<String, ColumnRange>,
     lru: Lru,
 }
 
@@ -70,6 +72,7 @@ impl Partition {
                 range: offset..(offset + len),
                 total_size_bytes,
                 cols: RwLock::new(columns),
+                column_ranges: HashMap::default(),
                 lru,
                 ephemeral,
             },
@@ -88,6 +91,7 @@ impl Partition {
             table_name: md.tablename.clone(),
             range,
             cols: RwLock::new(HashMap::new()),
+            column_ranges: md.column_ranges.clone(),
             lru,
             ephemeral: false,
             total_size_bytes,
@@ -194,6 +198,10 @@ impl Partition {
         mem_size
     }
 
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
     pub fn len(&self) -> usize {
         self.range.len()
     }
@@ -266,6 +274,24 @@ impl Partition {
         self.total_size_bytes
     }
 
+    /// Determines the range of values of all resident columns, only done for partitions that are persisted.
+    pub fn compute_column_ranges(&mut self) {
+        self.column_ranges = self
+            .cols
+            .get_mut()
+            .unwrap()
+            .values()
+            .filter_map(|handle| {
+                let col = handle.col.lock().unwrap().clone()?;
+                Some((handle.name().to_string(), col.value_range()?))
+            })
+            .collect();
+    }
+
+    pub fn column_ranges(&self) -> &HashMap<String, ColumnRange> {
+        &self.column_ranges
+    }
+
     pub fn clone_column_handles(&self) -> Vec<Arc<ColumnHandle>> {
         self.cols.read().unwrap().values().cloned().collect()
     }
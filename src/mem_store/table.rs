@@ -4,6 +4,7 @@ use std::str;
 use std::sync::atomic::{AtomicU64, AtomicUsize};
 use std::sync::Arc;
 use std::sync::{Mutex, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use datasize::DataSize;
 use itertools::Itertools;
@@ -16,7 +17,7 @@ use crate::ingest::raw_val::RawVal;
 use crate::mem_store::partition::{ColumnLocator, Partition};
 use crate::mem_store::*;
 
-use self::meta_store::PartitionMetadata;
+use self::meta_store::{ColumnRange, PartitionMetadata, RetentionPolicy};
 
 pub struct Table {
     name: String,
@@ -64,7 +65,10 @@ impl Table {
         let partitions = self.partitions.read().unwrap();
         let buffer = self.buffer.lock().unwrap();
         let mut partitions: Vec<_> = partitions.values().cloned().collect();
-        let mut offset = partitions.iter().map(|p| p.len()).sum::<usize>();
+        // Partitions deleted by retention policies leave gaps, so the offset is not necessarily the sum of partition lengths
+        let mut offset = self
+            .next_partition_offset
+            .load(std::sync::atomic::Ordering::SeqCst);
         if frozen_buffer.len() > 0 {
             let buffer = match column_filter {
                 Some(columns) => frozen_buffer.filter(columns),
@@ -222,13 +226,14 @@ impl Table {
         let partition_offset = self
             .next_partition_offset
             .fetch_add(buffer.len(), std::sync::atomic::Ordering::SeqCst);
-        let (new_partition, keys) = Partition::from_buffer(
+        let (mut new_partition, keys) = Partition::from_buffer(
             self.name(),
             part_id,
             buffer,
             self.lru.clone(),
             partition_offset,
         );
+        new_partition.compute_column_ranges();
         let arc_partition;
         {
             let mut partitions = self.partitions.write().unwrap();
@@ -268,7 +273,9 @@ impl Table {
 
         for (i, cum) in cumulative.iter().rev().enumerate() {
             if by_offset[i].total_size_bytes() as u64 * combine_factor < *cum {
-                let range = by_offset[i].range().start..by_offset.last().unwrap().range().end;
+                // Deleted rows leave gaps between partitions, the compacted partition covers only the remaining rows
+                let len = by_offset[i..].iter().map(|p| p.len()).sum::<usize>();
+                let range = by_offset[i].range().start..(by_offset[i].range().start + len);
                 return Some((range, by_offset[i..].iter().map(|p| p.id).collect()));
             }
         }
@@ -282,9 +289,11 @@ impl Table {
         offset: usize,
         columns: Vec<Arc<Column>>,
         old_partitions: &[PartitionID],
-    ) {
-        let (partition, keys) =
+    ) -> HashMap<String, ColumnRange> {
+        let (mut partition, keys) =
             Partition::new(self.name(), id, columns, self.lru.clone(), false, offset);
+        partition.compute_column_ranges();
+        let column_ranges = partition.column_ranges().clone();
         {
             let mut partitions = self.partitions.write().unwrap();
             for old_id in old_partitions {
@@ -295,6 +304,58 @@ impl Table {
         for (id, column) in keys {
             self.lru.put(ColumnLocator::new(self.name(), id, &column));
         }
+        column_ranges
+    }
+
+    /// Determines the partitions that exceed the limits of the retention policy.
+    /// Partitions are deleted from oldest to newest, so deletion stops at the first partition that is within the limits.
+    pub fn expired_partitions(&self, policy: &RetentionPolicy, now: SystemTime) -> Vec<PartitionID> {
+        let partitions = self.partitions.read().unwrap();
+        let by_offset = partitions
+            .values()
+            .sorted_by_key(|p| p.range().start)
+            .collect::<Vec<_>>();
+        let cutoff = policy.max_age.as_ref().map(|max_age| {
+            let age = now
+                .checked_sub(max_age.max_age)
+                .unwrap_or(UNIX_EPOCH)
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            (
+                &max_age.timestamp_column,
+                (age.as_nanos() as i128 * max_age.unit.per_second() as i128 / 1_000_000_000) as i64,
+            )
+        });
+        let mut total_size_bytes = by_offset
+            .iter()
+            .map(|p| p.total_size_bytes() as u64)
+            .sum::<u64>();
+        let mut expired = vec![];
+        for partition in by_offset {
+            let too_old = cutoff.as_ref().is_some_and(|(column, cutoff)| {
+                match partition.column_ranges().get(*column).map(|range| &range.max) {
+                    Some(RawVal::Int(max)) => max < cutoff,
+                    Some(RawVal::Float(max)) => max.0 < *cutoff as f64,
+                    _ => false,
+                }
+            });
+            let too_large = policy
+                .max_size_bytes
+                .is_some_and(|max_size_bytes| total_size_bytes > max_size_bytes);
+            if !too_old && !too_large {
+                break;
+            }
+            total_size_bytes -= partition.total_size_bytes() as u64;
+            expired.push(partition.id);
+        }
+        expired
+    }
+
+    pub fn delete_partitions(&self, ids: &[PartitionID]) {
+        let mut partitions = self.partitions.write().unwrap();
+        for id in ids {
+            partitions.remove(id);
+        }
     }
 
     pub fn mem_tree(&self, depth: usize) -> MemTreeTable {
@@ -381,6 +442,11 @@ impl Table {
             .expect("Column names have not been initialized")
     }
 
+    /// Forgets all column names, used when all rows of the table have been deleted.
+    pub fn clear_column_names(&self) {
+        *self.column_names.write().unwrap() = Some(HashSet::new());
+    }
+
     pub fn new_column_names<'a, I: Iterator<Item = &'a str>>(&self, columns: I) -> Vec<String> {
         let column_names = self.column_names();
         columns
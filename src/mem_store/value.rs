@@ -12,6 +12,17 @@ pub enum Val<'a> {
     Float(OrderedFloat<f64>),
 }
 
+impl<'a> Val<'a> {
+    /// Numeric value of integers, floats and booleans.
+    pub fn to_f64(self) -> Option<f64> {
+        match self {
+            Val::Bool(b) => Some(b as u8 as f64),
+            Val::Integer(i) => Some(i as f64),
+            Val::Float(f) => Some(f.0),
+            Val::Null | Val::Str(_) => None,
+        }
+    }
+}
 
 impl<'a> fmt::Display for Val<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
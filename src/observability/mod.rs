@@ -1,6 +1,8 @@
 mod simple_trace;
 mod perf_counter;
+mod query_registry;
 pub(crate) mod metrics;
 
 pub(crate) use simple_trace::SimpleTracer;
-pub use perf_counter::{PerfCounter, QueryPerfCounter};
\ No newline at end of file
+pub use perf_counter::{PerfCounter, QueryPerfCounter};
+pub use query_registry::{QueryProgress, QueryRegistry, QueryStatus, TrackedQuery, QUERIES_TABLE};
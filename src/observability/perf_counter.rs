@@ -25,6 +25,7 @@ pub struct PerfCounter {
 
 #[derive(Debug, Default)]
 pub struct QueryPerfCounter {
+    pub partitions_scanned: AtomicU64,
     pub rows_scanned: AtomicU64,
     pub files_opened: AtomicU64,
     pub disk_read_bytes: AtomicU64,
@@ -159,6 +160,10 @@ impl QueryPerfCounter {
         QueryPerfCounter::default()
     }
 
+    pub fn partitions_scanned(&self) -> u64 {
+        self.partitions_scanned.load(ORDERING)
+    }
+
     pub fn rows_scanned(&self) -> u64 {
         self.rows_scanned.load(ORDERING)
     }
@@ -171,7 +176,8 @@ impl QueryPerfCounter {
         self.disk_read_bytes.load(ORDERING)
     }
 
-    pub fn scanned(&self, rows: u64) {
+    pub fn scanned_partition(&self, rows: u64) {
+        self.partitions_scanned.fetch_add(1, ORDERING);
         self.rows_scanned.fetch_add(rows, ORDERING);
     }
 
@@ -0,0 +1,172 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::engine::CancellationToken;
+use crate::observability::QueryPerfCounter;
+use crate::{QueryError, QueryResult};
+
+/// Virtual table that lists running and recently completed queries
+pub const QUERIES_TABLE: &str = "_queries";
+
+/// Number of completed queries that are retained for introspection
+pub const MAX_COMPLETED_QUERIES: usize = 100;
+
+/// Tracks all running and recently completed queries.
+#[derive(Default)]
+pub struct QueryRegistry {
+    next_id: AtomicU64,
+    queries: Mutex<Queries>,
+}
+
+#[derive(Default)]
+struct Queries {
+    running: BTreeMap<u64, Arc<TrackedQuery>>,
+    completed: VecDeque<Arc<TrackedQuery>>,
+}
+
+/// Query that is registered with a `QueryRegistry` and shares its cancellation token and perf counter with the tasks
+/// executing the query.
+pub struct TrackedQuery {
+    pub id: u64,
+    pub query: String,
+    pub cancellation: Arc<CancellationToken>,
+    pub perf_counter: Arc<QueryPerfCounter>,
+    start_time: SystemTime,
+    start_instant: Instant,
+    // Status, error and runtime of the query once it has completed
+    completion: Mutex<Option<(QueryStatus, Option<String>, Duration)>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryStatus {
+    Running,
+    Completed,
+    Failed,
+    Canceled,
+}
+
+/// Snapshot of the progress of a query.
+#[derive(Clone, Debug, Serialize)]
+pub struct QueryProgress {
+    pub id: u64,
+    pub query: String,
+    /// Nanoseconds since the Unix epoch
+    pub start_time_ns: i64,
+    pub runtime_ns: u64,
+    pub partitions_scanned: u64,
+    pub rows_scanned: u64,
+    pub files_opened: u64,
+    pub disk_read_bytes: u64,
+    pub status: QueryStatus,
+    pub error: Option<String>,
+}
+
+impl QueryRegistry {
+    pub fn register(&self, query: &str, timeout: Option<Duration>) -> Arc<TrackedQuery> {
+        let query = Arc::new(TrackedQuery {
+            id: self.next_id.fetch_add(1, Ordering::SeqCst),
+            query: query.to_string(),
+            cancellation: Arc::new(CancellationToken::new(timeout)),
+            perf_counter: Arc::default(),
+            start_time: SystemTime::now(),
+            start_instant: Instant::now(),
+            completion: Mutex::new(None),
+        });
+        self.queries
+            .lock()
+            .unwrap()
+            .running
+            .insert(query.id, query.clone());
+        query
+    }
+
+    /// Records the result of `query` unless it has already completed, and returns whether it was recorded. Queries that
+    /// are dropped before producing a result are recorded as canceled.
+    pub fn complete(&self, query: &TrackedQuery, result: Option<&QueryResult>) -> bool {
+        {
+            let mut completion = query.completion.lock().unwrap();
+            if completion.is_some() {
+                return false;
+            }
+            let (status, error) = match result {
+                Some(Ok(_)) => (QueryStatus::Completed, None),
+                Some(Err(err @ QueryError::Aborted(_))) => (QueryStatus::Canceled, Some(err.to_string())),
+                Some(Err(err)) => (QueryStatus::Failed, Some(err.to_string())),
+                None => (QueryStatus::Canceled, None),
+            };
+            *completion = Some((status, error, query.start_instant.elapsed()));
+        }
+        let mut queries = self.queries.lock().unwrap();
+        if let Some(query) = queries.running.remove(&query.id) {
+            if queries.completed.len() == MAX_COMPLETED_QUERIES {
+                queries.completed.pop_front();
+            }
+            queries.completed.push_back(query);
+        }
+        true
+    }
+
+    /// Aborts the running query with the given id. Returns `false` if there is no such query.
+    pub fn cancel(&self, id: u64) -> bool {
+        match self.queries.lock().unwrap().running.get(&id) {
+            Some(query) => {
+                query.cancellation.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the progress of all running and recently completed queries, ordered by id.
+    pub fn progress(&self) -> Vec<QueryProgress> {
+        let queries = self.queries.lock().unwrap();
+        let mut progress = queries
+            .completed
+            .iter()
+            .chain(queries.running.values())
+            .map(|query| query.progress())
+            .collect::<Vec<_>>();
+        progress.sort_by_key(|query| query.id);
+        progress
+    }
+}
+
+impl TrackedQuery {
+    pub fn progress(&self) -> QueryProgress {
+        let (status, error, runtime) = match &*self.completion.lock().unwrap() {
+            Some((status, error, runtime)) => (*status, error.clone(), *runtime),
+            None => (QueryStatus::Running, None, self.start_instant.elapsed()),
+        };
+        QueryProgress {
+            id: self.id,
+            query: self.query.clone(),
+            start_time_ns: self
+                .start_time
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |t| t.as_nanos() as i64),
+            runtime_ns: runtime.as_nanos() as u64,
+            partitions_scanned: self.perf_counter.partitions_scanned(),
+            rows_scanned: self.perf_counter.rows_scanned(),
+            files_opened: self.perf_counter.files_opened(),
+            disk_read_bytes: self.perf_counter.disk_read_bytes(),
+            status,
+            error,
+        }
+    }
+}
+
+impl QueryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QueryStatus::Running => "running",
+            QueryStatus::Completed => "completed",
+            QueryStatus::Failed => "failed",
+            QueryStatus::Canceled => "canceled",
+        }
+    }
+}
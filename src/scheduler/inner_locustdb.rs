@@ -4,7 +4,7 @@ use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::ops::Range;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
-use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, RwLock};
 use std::thread;
 use std::time::{Duration, Instant};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -17,12 +17,15 @@ use inner_locustdb::meta_store::PartitionMetadata;
 use locustdb_serialization::event_buffer::{ColumnBuffer, ColumnData, EventBuffer, TableBuffer};
 use threadpool::ThreadPool;
 
-use crate::disk_store::storage::Storage;
+use crate::bitvec::{BitVec, BitVecMut};
+use crate::disk_store::storage::{blob_writer_for_path, Storage};
 use crate::engine::query_task::{BasicTypeColumn, QueryTask};
-use crate::engine::Query;
+use crate::engine::pruning;
+use crate::engine::{CancellationToken, Mutation, Query};
 use crate::ingest::colgen::GenTable;
 use crate::ingest::input_column::InputColumn;
 use crate::ingest::raw_val::RawVal;
+use crate::syntax::expression::{Expr, Func2Type};
 use crate::locustdb::Options;
 use crate::mem_store::partition::Partition;
 use crate::mem_store::table::*;
@@ -32,7 +35,7 @@ use crate::scheduler::*;
 use crate::{disk_store::*, QueryError, QueryOutput};
 use crate::{mem_store::*, NoopStorage};
 
-use self::meta_store::SubpartitionMetadata;
+use self::meta_store::{RetentionPolicy, SubpartitionMetadata};
 use self::wal_segment::WalSegment;
 
 // Table name + list of partitions
@@ -47,6 +50,10 @@ pub struct InnerLocustDB {
 
     wal_size: (Mutex<u64>, Condvar),
     pending_wal_flushes: (Mutex<Vec<mpsc::Sender<()>>>, Condvar),
+    // Held during WAL flushes and retention enforcement, which both delete partitions and persist the metastore
+    flush_lock: Mutex<()>,
+
+    retention_policies: RwLock<HashMap<String, RetentionPolicy>>,
 
     opts: Options,
 
@@ -57,6 +64,8 @@ pub struct InnerLocustDB {
     task_queue: Mutex<VecDeque<(Arc<dyn Task>, usize)>>,
 
     walflush_threadpool: ThreadPool,
+    // Runs DELETE, TRUNCATE and DROP TABLE statements one at a time
+    mutation_threadpool: ThreadPool,
 }
 
 impl InnerLocustDB {
@@ -68,9 +77,15 @@ impl InnerLocustDB {
                 let perf_counter = perf_counter.clone();
                 let lru = lru.clone();
                 let io_threads = opts.io_threads;
+                let blob_writer = opts.blob_writer.clone();
+                let s3_endpoint = opts.s3_endpoint.clone();
                 std::thread::spawn(move || {
+                    let (blob_writer, path) = match blob_writer {
+                        Some(blob_writer) => (blob_writer, path),
+                        None => blob_writer_for_path(&path, s3_endpoint),
+                    };
                     let (storage, wal, wal_size) =
-                        Storage::new(&path, perf_counter, false, io_threads);
+                        Storage::with_blob_writer(&path, blob_writer, perf_counter, false, io_threads);
                     let tables = Table::restore_tables_from_disk(&storage, &lru);
 
                     (Some(Arc::new(storage)), tables, wal, wal_size)
@@ -80,6 +95,10 @@ impl InnerLocustDB {
             }
             None => (None, HashMap::new(), vec![], 0),
         };
+        let retention_policies = match &storage {
+            Some(storage) => storage.meta_store().read().unwrap().retention_policies().clone(),
+            None => HashMap::new(),
+        };
         let disk_read_scheduler = Arc::new(DiskReadScheduler::new(
             storage
                 .clone()
@@ -100,6 +119,9 @@ impl InnerLocustDB {
 
             wal_size: (Mutex::new(wal_size), Condvar::new()),
             pending_wal_flushes: (Mutex::new(vec![]), Condvar::new()),
+            flush_lock: Mutex::new(()),
+
+            retention_policies: RwLock::new(retention_policies),
 
             opts: opts.clone(),
             perf_counter,
@@ -108,6 +130,7 @@ impl InnerLocustDB {
             task_queue: Mutex::new(VecDeque::new()),
 
             walflush_threadpool: ThreadPool::new(opts.wal_flush_compaction_threads),
+            mutation_threadpool: ThreadPool::new(1),
         });
 
         InnerLocustDB::start_worker_threads(&locustdb);
@@ -134,7 +157,12 @@ impl InnerLocustDB {
                 let columns: HashMap<String, InputColumn> = data
                     .into_columns()
                     .into_iter()
-                    .map(|(k, v)| (k, InputColumn::from_column_data(v.data, rows)))
+                    .map(|(k, v)| {
+                        (
+                            k,
+                            InputColumn::from_column_data(v.data, v.logical_type, rows),
+                        )
+                    })
                     .collect();
                 assert!(columns.iter().all(|(_, c)| c.len() == rows as usize));
                 table.ingest_homogeneous(columns);
@@ -160,6 +188,8 @@ impl InnerLocustDB {
         thread::spawn(move || ldb.enforce_wal_limit());
         let ldb = locustdb.clone();
         thread::spawn(move || ldb.log_metrics());
+        let ldb = locustdb.clone();
+        thread::spawn(move || ldb.enforce_retention_policies());
     }
 
     pub fn snapshot(
@@ -171,6 +201,13 @@ impl InnerLocustDB {
         tables.get(table).map(|t| t.snapshot(column_filter))
     }
 
+    pub fn table_names(&self) -> Vec<String> {
+        let tables = self.tables.read().unwrap();
+        let mut names = tables.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
     pub fn full_snapshot(&self) -> Vec<Vec<Arc<Partition>>> {
         let tables = self.tables.read().unwrap();
         tables.values().map(|t| t.snapshot(None)).collect()
@@ -274,12 +311,14 @@ impl InnerLocustDB {
                 "timestamp".to_string(),
                 ColumnBuffer {
                     data: ColumnData::I64(timestamps),
+                    ..Default::default()
                 },
             );
             columns.insert(
                 "name".to_string(),
                 ColumnBuffer {
                     data: ColumnData::String(names),
+                    ..Default::default()
                 },
             );
             let meta_tables_buffer = TableBuffer::new(columns);
@@ -292,6 +331,7 @@ impl InnerLocustDB {
                 "column_name".to_string(),
                 ColumnBuffer {
                     data: ColumnData::String(names),
+                    ..Default::default()
                 },
             )]);
             let meta_columns_buffer = TableBuffer::new(columns);
@@ -316,7 +356,12 @@ impl InnerLocustDB {
             let columns = data
                 .into_columns()
                 .into_iter()
-                .map(|(k, v)| (k, InputColumn::from_column_data(v.data, rows)))
+                .map(|(k, v)| {
+                    (
+                        k,
+                        InputColumn::from_column_data(v.data, v.logical_type, rows),
+                    )
+                })
                 .collect();
             table.ingest_homogeneous(columns);
         }
@@ -328,10 +373,14 @@ impl InnerLocustDB {
         wal_condvar.notify_all();
     }
 
-    /// Creates new partition from currently open buffer in each table, persists partitions to disk, and deletes WAL.
-    /// There is a single WAL flush thread that is responsible for flushing the WAL and creating new partitions, so
-    /// this function is never called concurrently.
     fn wal_flush(self: &Arc<InnerLocustDB>) {
+        let flush_lock = self.flush_lock.lock().unwrap();
+        self.wal_flush_locked(&flush_lock);
+    }
+
+    /// Creates new partition from currently open buffer in each table, persists partitions to disk, and deletes WAL.
+    /// Requires holding `flush_lock`, so this function is never called concurrently.
+    fn wal_flush_locked(self: &Arc<InnerLocustDB>, _flush_lock: &MutexGuard<()>) {
         log::info!("Commencing WAL flush");
         let mut tracer = SimpleTracer::default();
         let span_wal_flush = tracer.start_span("wal_flush");
@@ -471,6 +520,8 @@ impl InnerLocustDB {
             SharedSender::new(sender),
             self.opts.batch_size,
             None,
+            Arc::new(CancellationToken::new(None)),
+            Arc::default(),
         )
         .unwrap();
         self.schedule(query_task);
@@ -535,6 +586,7 @@ impl InnerLocustDB {
                 offset: partition.range().start,
                 subpartitions: metadata,
                 subpartitions_by_last_column,
+                column_ranges: partition.column_ranges().clone(),
             };
             new_partition = Some((partition_metadata, subpartitions));
         }
@@ -552,6 +604,19 @@ impl InnerLocustDB {
         id: PartitionID,
         range: Range<usize>,
         parts: &[u64],
+    ) -> (Option<PartitionList>, SimpleTracer) {
+        self.rewrite_partitions(table, id, range, parts, None)
+    }
+
+    /// Replaces `parts` with a single new partition that contains all their rows, or only the rows for which `keep` is
+    /// true if given. `range` is the range of rows of the new partition.
+    fn rewrite_partitions(
+        &self,
+        table: Arc<Table>,
+        id: PartitionID,
+        range: Range<usize>,
+        parts: &[u64],
+        keep: Option<&[bool]>,
     ) -> (Option<PartitionList>, SimpleTracer) {
         // get table, create new merged partition/sub-partitions (not registered with table)
         // - get names of all columns
@@ -579,7 +644,10 @@ impl InnerLocustDB {
         let query_perf_counter = QueryPerfCounter::new();
         for column in &colnames {
             let mut builder = crate::mem_store::column_buffer::ColumnBuffer::default();
+            let mut part_offset = 0;
             for part in &data {
+                let part_keep = keep.map(|keep| &keep[part_offset..part_offset + part.len()]);
+                part_offset += part.len();
                 let span_load_columns = tracer.start_span("load_column");
                 let cols = part.get_cols(
                     &[column.clone()].into(),
@@ -607,30 +675,36 @@ impl InnerLocustDB {
                 tracer.end_span(span_decode);
 
                 let span_push = tracer.start_span("push");
+                if decoded.get_type() != crate::engine::data_types::EncodingType::Null {
+                    builder.push_logical_type(col.logical_type());
+                }
                 match decoded.get_type() {
-                    crate::engine::data_types::EncodingType::F64 => {
-                        builder.push_floats(decoded.cast_ref_f64().iter().cloned(), None)
-                    }
-                    crate::engine::data_types::EncodingType::I64 => {
-                        builder.push_ints(decoded.cast_ref_i64().iter().cloned(), None)
-                    }
-                    crate::engine::data_types::EncodingType::Str => {
-                        builder.push_strings(decoded.cast_ref_str().iter().copied(), None)
-                    }
+                    crate::engine::data_types::EncodingType::F64 => builder.push_floats(
+                        retain(decoded.cast_ref_f64().iter().cloned(), part_keep),
+                        None,
+                    ),
+                    crate::engine::data_types::EncodingType::I64 => builder.push_ints(
+                        retain(decoded.cast_ref_i64().iter().cloned(), part_keep),
+                        None,
+                    ),
+                    crate::engine::data_types::EncodingType::Str => builder.push_strings(
+                        retain(decoded.cast_ref_str().iter().copied(), part_keep),
+                        None,
+                    ),
                     crate::engine::data_types::EncodingType::NullableF64 => builder.push_floats(
-                        decoded.cast_ref_f64().iter().cloned(),
-                        Some(decoded.cast_ref_null_map()),
+                        retain(decoded.cast_ref_f64().iter().cloned(), part_keep),
+                        Some(&retain_present(decoded.cast_ref_null_map(), part_keep)),
+                    ),
+                    crate::engine::data_types::EncodingType::Null => builder.push_nulls(
+                        part_keep.map_or(decoded.len(), |keep| keep.iter().filter(|k| **k).count()),
                     ),
-                    crate::engine::data_types::EncodingType::Null => {
-                        builder.push_nulls(decoded.len())
-                    }
                     crate::engine::data_types::EncodingType::NullableStr => builder.push_strings(
-                        decoded.cast_ref_str().iter().copied(),
-                        Some(decoded.cast_ref_null_map()),
+                        retain(decoded.cast_ref_str().iter().copied(), part_keep),
+                        Some(&retain_present(decoded.cast_ref_null_map(), part_keep)),
                     ),
                     crate::engine::data_types::EncodingType::NullableI64 => builder.push_ints(
-                        decoded.cast_ref_i64().iter().cloned(),
-                        Some(decoded.cast_ref_null_map()),
+                        retain(decoded.cast_ref_i64().iter().cloned(), part_keep),
+                        Some(&retain_present(decoded.cast_ref_null_map(), part_keep)),
                     ),
                     _ => panic!(
                         "Unsupported encoding type for add: {:?}",
@@ -661,7 +735,7 @@ impl InnerLocustDB {
 
         // replace old partitions with new partition
         let span_compact_partitions = tracer.start_span("compact_partitions");
-        table.compact(id, range.start, columns, parts);
+        let column_ranges = table.compact(id, range.start, columns, parts);
         tracer.end_span(span_compact_partitions);
 
         // write new subpartitions to disk and update in-memory metastore
@@ -674,6 +748,7 @@ impl InnerLocustDB {
                 subpartitions,
                 parts,
                 range.start,
+                column_ranges,
             );
             (table.name().to_string(), to_delete)
         });
@@ -818,7 +893,10 @@ impl InnerLocustDB {
                     match ldb.lru.evict() {
                         Some(victim) => {
                             let tables = ldb.tables.read().unwrap();
-                            total_mem_usage -= tables[&victim.table].evict(&victim);
+                            // Table may have been dropped since the column was accessed
+                            if let Some(table) = tables.get(&victim.table) {
+                                total_mem_usage -= table.evict(&victim);
+                            }
                         }
                         None => {
                             if ldb.opts.mem_size_limit_tables > 0 {
@@ -881,6 +959,275 @@ impl InnerLocustDB {
         }
     }
 
+    pub fn retention_policies(&self) -> HashMap<String, RetentionPolicy> {
+        self.retention_policies.read().unwrap().clone()
+    }
+
+    pub fn set_retention_policy(&self, table: &str, policy: Option<RetentionPolicy>) {
+        let _flush_lock = self.flush_lock.lock().unwrap();
+        if let Some(storage) = self.storage.as_ref() {
+            let mut tracer = SimpleTracer::default();
+            storage.persist_retention_policy(table, policy.clone(), &mut tracer);
+        }
+        let mut retention_policies = self.retention_policies.write().unwrap();
+        match policy {
+            Some(policy) => retention_policies.insert(table.to_string(), policy),
+            None => retention_policies.remove(table),
+        };
+    }
+
+    fn enforce_retention_policies(self: Arc<InnerLocustDB>) {
+        let mut last_run = Instant::now();
+        while self.running.load(Ordering::SeqCst) {
+            if last_run.elapsed() >= Duration::from_secs(self.opts.retention_interval) {
+                last_run = Instant::now();
+                self.apply_retention_policies();
+            }
+            thread::sleep(Duration::from_millis(250));
+        }
+    }
+
+    /// Deletes the oldest partitions of every table that exceeds the limits of its retention policy.
+    /// Returns the number of deleted partitions.
+    pub fn apply_retention_policies(&self) -> usize {
+        let _flush_lock = self.flush_lock.lock().unwrap();
+        let mut tracer = SimpleTracer::default();
+        let span_retention = tracer.start_span("retention");
+        let now = SystemTime::now();
+        let mut partition_count = 0;
+        let mut partitions_to_delete = vec![];
+        for (table_name, policy) in self.retention_policies() {
+            let table = match self.tables.read().unwrap().get(&table_name) {
+                Some(table) => table.clone(),
+                None => continue,
+            };
+            let expired = table.expired_partitions(&policy, now);
+            if expired.is_empty() {
+                continue;
+            }
+            log::info!(
+                "Deleting {} partitions of table {} that exceed retention policy",
+                expired.len(),
+                table_name
+            );
+            partition_count += expired.len();
+            partitions_to_delete.extend(self.delete_partitions(&table, &expired));
+        }
+        if let Some(storage) = self.storage.as_ref() {
+            if !partitions_to_delete.is_empty() {
+                storage.persist_metastore(storage.unflushed_wal_ids().start, &mut tracer);
+                storage.delete_orphaned_partitions(partitions_to_delete, &mut tracer);
+            }
+        }
+        tracer.end_span(span_retention);
+        if partition_count > 0 {
+            log::info!("Enforced retention policies\n{}", tracer.summary());
+        }
+        partition_count
+    }
+
+    /// Removes partitions from `table` and the metastore, returns the partition files that have to be deleted.
+    fn delete_partitions(&self, table: &Table, ids: &[PartitionID]) -> Option<PartitionList> {
+        table.delete_partitions(ids);
+        self.storage.as_ref().map(|storage| {
+            let to_delete = storage
+                .meta_store()
+                .write()
+                .unwrap()
+                .delete_partitions(table.name(), ids);
+            (table.name().to_string(), to_delete)
+        })
+    }
+
+    /// Runs `mutation` on the mutation thread. Mutations block on WAL flushes and on the query tasks that evaluate their
+    /// filters, so they cannot run on the worker threads.
+    pub fn schedule_mutation(
+        self: &Arc<InnerLocustDB>,
+        mutation: Mutation,
+    ) -> oneshot::Receiver<Result<usize, QueryError>> {
+        let (sender, receiver) = oneshot::channel();
+        let this = self.clone();
+        self.mutation_threadpool.execute(move || {
+            let _ = sender.send(this.execute_mutation(mutation));
+        });
+        receiver
+    }
+
+    /// Executes a statement that removes data and returns the number of deleted rows.
+    /// Partitions in which all rows are deleted are removed from the metastore, other partitions that contain deleted
+    /// rows are rewritten in the same way as partitions are compacted.
+    pub fn execute_mutation(self: &Arc<InnerLocustDB>, mutation: Mutation) -> Result<usize, QueryError> {
+        let table_name = match &mutation {
+            Mutation::DropTable { table, .. } | Mutation::Truncate { table } | Mutation::Delete { table, .. } => table,
+        };
+        if table_name.starts_with("_meta_") {
+            bail!(QueryError::NotImplemented, "Deleting from internal table {}", table_name);
+        }
+        // Buffered rows are flushed into partitions so that they are affected by the mutation and not restored from WAL.
+        // The flush lock is held until the mutation completes so that no other flush can run in between.
+        let flush_lock = self.flush_lock.lock().unwrap();
+        self.wal_flush_locked(&flush_lock);
+        let mut tracer = SimpleTracer::default();
+        let span_mutation = tracer.start_span("mutation");
+        let mut partitions_to_delete = vec![];
+        let result = match mutation {
+            Mutation::DropTable { table, if_exists } => {
+                let meta_columns_table = format!("_meta_columns_{}", table);
+                if !self.tables.read().unwrap().contains_key(&table) && if_exists {
+                    Ok(0)
+                } else {
+                    self.truncate(&table, &mut partitions_to_delete).and_then(|deleted| {
+                        {
+                            let mut tables = self.tables.write().unwrap();
+                            tables.remove(&table);
+                            tables.remove(&meta_columns_table);
+                        }
+                        self.retention_policies.write().unwrap().remove(&table);
+                        if let Some(storage) = self.storage.as_ref() {
+                            storage.meta_store().write().unwrap().set_retention_policy(&table, None);
+                        }
+                        let is_table = |name: String| {
+                            Expr::Func2(
+                                Func2Type::Equals,
+                                Box::new(Expr::ColName("name".to_string())),
+                                Box::new(Expr::Const(RawVal::Str(name))),
+                            )
+                        };
+                        let filter = Expr::Func2(
+                            Func2Type::Or,
+                            Box::new(is_table(table)),
+                            Box::new(is_table(meta_columns_table)),
+                        );
+                        self.delete_rows("_meta_tables", &filter, &mut partitions_to_delete)?;
+                        Ok(deleted)
+                    })
+                }
+            }
+            Mutation::Truncate { table } => self.truncate(&table, &mut partitions_to_delete),
+            Mutation::Delete { table, filter } => {
+                self.delete_rows(&table, &filter, &mut partitions_to_delete)
+            }
+        };
+
+        if let Some(storage) = self.storage.as_ref() {
+            storage.persist_metastore(storage.unflushed_wal_ids().start, &mut tracer);
+            storage.delete_orphaned_partitions(partitions_to_delete, &mut tracer);
+        }
+        tracer.end_span(span_mutation);
+        log::info!("Completed mutation\n{}", tracer.summary());
+        result
+    }
+
+    /// Deletes all rows and column names of `table`.
+    fn truncate(
+        &self,
+        table_name: &str,
+        partitions_to_delete: &mut Vec<PartitionList>,
+    ) -> Result<usize, QueryError> {
+        let meta_columns_table = format!("_meta_columns_{}", table_name);
+        let mut deleted = 0;
+        for name in [table_name, &meta_columns_table] {
+            let table = match self.tables.read().unwrap().get(name) {
+                Some(table) => table.clone(),
+                None if name == table_name => {
+                    bail!(QueryError::NotImplemented, "Table {} does not exist!", table_name)
+                }
+                None => continue,
+            };
+            let partitions = table.partitions.read().unwrap().values().cloned().collect::<Vec<_>>();
+            if name == table_name {
+                deleted = partitions.iter().map(|p| p.len()).sum();
+            }
+            let ids = partitions.iter().map(|p| p.id).collect::<Vec<_>>();
+            partitions_to_delete.extend(self.delete_partitions(&table, &ids));
+            table.clear_column_names();
+        }
+        Ok(deleted)
+    }
+
+    /// Deletes all rows of `table` for which `filter` is true.
+    fn delete_rows(
+        &self,
+        table_name: &str,
+        filter: &Expr,
+        partitions_to_delete: &mut Vec<PartitionList>,
+    ) -> Result<usize, QueryError> {
+        let table = match self.tables.read().unwrap().get(table_name) {
+            Some(table) => table.clone(),
+            None => bail!(QueryError::NotImplemented, "Table {} does not exist!", table_name),
+        };
+        if let Expr::Const(value) = filter {
+            return match value {
+                RawVal::Null | RawVal::Int(0) => Ok(0),
+                _ => self.truncate(table_name, partitions_to_delete),
+            };
+        }
+        let partitions = table.partitions.read().unwrap().values().cloned().collect::<Vec<_>>();
+        let mut deleted = 0;
+        let mut fully_deleted = vec![];
+        for partition in partitions {
+            if !pruning::may_match(filter, partition.column_ranges()) {
+                continue;
+            }
+            let keep = self.evaluate_keep_mask(&partition, filter)?;
+            let kept = keep.iter().filter(|k| **k).count();
+            deleted += partition.len() - kept;
+            if kept == 0 {
+                fully_deleted.push(partition.id);
+            } else if kept < partition.len() {
+                let start = partition.range().start;
+                let (to_delete, _) = self.rewrite_partitions(
+                    table.clone(),
+                    table.next_partition_id(),
+                    start..(start + kept),
+                    &[partition.id],
+                    Some(&keep),
+                );
+                partitions_to_delete.extend(to_delete);
+            }
+        }
+        partitions_to_delete.extend(self.delete_partitions(&table, &fully_deleted));
+        Ok(deleted)
+    }
+
+    /// Evaluates `filter` for every row of `partition`, rows for which it is not true are kept.
+    fn evaluate_keep_mask(&self, partition: &Arc<Partition>, filter: &Expr) -> Result<Vec<bool>, QueryError> {
+        let (sender, receiver) = oneshot::channel();
+        let query_task = QueryTask::new(
+            Query::project(partition.table_name(), filter.clone()),
+            false,
+            false,
+            vec![],
+            vec![partition.clone()],
+            self.disk_read_scheduler().clone(),
+            SharedSender::new(sender),
+            self.opts.batch_size,
+            None,
+            Arc::new(CancellationToken::new(None)),
+            Arc::default(),
+        )?;
+        self.schedule(query_task);
+        let mut result = block_on(receiver)??;
+        let keep: Vec<bool> = match result.columns.pop() {
+            Some((_, BasicTypeColumn::Int(values))) => values.into_iter().map(|v| v == 0).collect(),
+            Some((_, BasicTypeColumn::Bool(values))) => values.into_iter().map(|v| !v).collect(),
+            Some((_, BasicTypeColumn::Null(len))) => vec![true; len],
+            // Null is not true, so rows with a null filter value are kept
+            Some((_, BasicTypeColumn::Mixed(values))) => values
+                .into_iter()
+                .map(|v| !matches!(v, RawVal::Int(i) if i != 0) && v != RawVal::Bool(true))
+                .collect(),
+            column => bail!(QueryError::TypeError, "DELETE filter {:?} is not a boolean expression, got {:?}", filter, column),
+        };
+        ensure!(
+            keep.len() == partition.len(),
+            "DELETE filter returned {} values for partition with {} rows",
+            keep.len(),
+            partition.len()
+        );
+        Ok(keep)
+    }
+
     fn log_metrics(self: Arc<InnerLocustDB>) {
         let mut last_log_time = Instant::now() - Duration::from_secs(self.opts.metrics_interval);
         let mut last_value = HashMap::new();
@@ -906,6 +1253,7 @@ impl InnerLocustDB {
                     "timestamp".to_string(),
                     ColumnBuffer {
                         data: ColumnData::I64(vec![timestamp]),
+                        ..Default::default()
                     },
                 );
                 self.log_table_stats();
@@ -923,6 +1271,7 @@ impl InnerLocustDB {
                             metric_family.name().to_string(),
                             ColumnBuffer {
                                 data: ColumnData::Dense(vec![value]),
+                                ..Default::default()
                             },
                         );
                         match last_value.entry(metric_family.name().to_string()) {
@@ -933,6 +1282,7 @@ impl InnerLocustDB {
                                     format!("{}.rate", metric_family.name()),
                                     ColumnBuffer {
                                         data: ColumnData::Dense(vec![rate]),
+                                        ..Default::default()
                                     },
                                 );
                                 last_value.insert(value);
@@ -971,7 +1321,9 @@ impl InnerLocustDB {
         let tables = self.tables.read().unwrap();
         let mut bytes_evicted = 0;
         while let Some(victim) = self.lru.evict() {
-            bytes_evicted += tables[&victim.table].evict(&victim);
+            if let Some(table) = tables.get(&victim.table) {
+                bytes_evicted += table.evict(&victim);
+            }
         }
         bytes_evicted
     }
@@ -1088,3 +1440,29 @@ fn is_filesystem_safe(column_name: &str) -> bool {
             .chars()
             .all(|c| (c.is_alphanumeric() && c.is_lowercase()) || c == '_')
 }
+
+/// Values for which `keep` is true, or all values if `keep` is `None`.
+fn retain<'a, T>(
+    values: impl Iterator<Item = T> + 'a,
+    keep: Option<&'a [bool]>,
+) -> impl Iterator<Item = T> + 'a {
+    values
+        .enumerate()
+        .filter(move |(i, _)| keep.is_none_or(|keep| keep[*i]))
+        .map(|(_, value)| value)
+}
+
+fn retain_present<'a>(present: &'a [u8], keep: Option<&[bool]>) -> Cow<'a, [u8]> {
+    match keep {
+        None => Cow::Borrowed(present),
+        Some(keep) => {
+            let mut retained = vec![];
+            for (j, i) in (0..keep.len()).filter(|&i| keep[i]).enumerate() {
+                if present.is_set(i) {
+                    retained.set(j);
+                }
+            }
+            Cow::Owned(retained)
+        }
+    }
+}
@@ -2,6 +2,7 @@ use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 use std::sync::Arc;
 use std::thread;
+use std::time::Duration;
 
 use actix_cors::Cors;
 use actix_web::dev::ServerHandle;
@@ -19,6 +20,7 @@ use serde_json::json;
 use tera::{Context, Tera};
 use tokio::sync::oneshot;
 
+use crate::ingest::raw_val::format_timestamp;
 use crate::{BasicTypeColumn, LocustDB};
 use crate::{QueryError, QueryOutput, Value};
 
@@ -193,9 +195,12 @@ async fn query(data: web::Data<AppState>, req_body: web::Json<QueryRequest>) ->
     log::debug!("Query: {:?}", req_body);
     let result = data
         .db
-        .run_query(&req_body.query, false, true, vec![])
-        .await
-        .unwrap();
+        .run_query_with_timeout(&req_body.query, false, true, vec![], timeout(req_body.timeout_ms))
+        .await;
+    let result = match map_err_response(result) {
+        Ok(result) => result,
+        Err(err) => return err,
+    };
 
     let response = json!({
         "colnames": result.colnames,
@@ -204,6 +209,8 @@ async fn query(data: web::Data<AppState>, req_body: web::Json<QueryRequest>) ->
             Value::Str(str) => json!(str),
             Value::Null => json!(null),
             Value::Float(float) => json!(float.0),
+            Value::Bool(b) => json!(b),
+            Value::Timestamp(..) => json!(val.to_string()),
         }).collect::<Vec<_>>()).collect::<Vec<_>>(),
         "stats": result.stats,
     });
@@ -218,7 +225,7 @@ async fn query_cols(
     log::debug!("Query: {:?}", req_body);
     let x = data
         .db
-        .run_query(&req_body.query, false, false, vec![])
+        .run_query_with_timeout(&req_body.query, false, false, vec![], timeout(req_body.timeout_ms))
         .await;
     match map_err_response(x) {
         Ok(result) => {
@@ -238,7 +245,9 @@ async fn multi_query_cols(
     let mut futures = vec![];
     for q in &req_body.queries {
         // Run query starts executing immediately even without awaiting future
-        let result = data.db.run_query(q, false, false, vec![]);
+        let result = data
+            .db
+            .run_query_with_timeout(q, false, false, vec![], timeout(req_body.timeout_ms));
         futures.push(result);
     }
     let mut results = vec![];
@@ -295,6 +304,25 @@ async fn multi_query_cols(
     }
 }
 
+#[get("/queries")]
+async fn queries(data: web::Data<AppState>) -> impl Responder {
+    HttpResponse::Ok().json(data.db.queries())
+}
+
+#[post("/cancel/{id}")]
+async fn cancel(path: web::Path<u64>, data: web::Data<AppState>) -> impl Responder {
+    let id = path.into_inner();
+    if data.db.cancel_query(id) {
+        HttpResponse::Ok().json(json!({ "canceled": id }))
+    } else {
+        HttpResponse::NotFound().json(format!("No running query with id {}", id))
+    }
+}
+
+fn timeout(timeout_ms: Option<u64>) -> Option<Duration> {
+    timeout_ms.map(Duration::from_millis)
+}
+
 #[post("/columns")]
 async fn columns(
     data: web::Data<AppState>,
@@ -405,6 +433,11 @@ fn query_output_to_json_cols(result: QueryOutput) -> serde_json::Value {
             BasicTypeColumn::Float(xs) => json!(xs),
             BasicTypeColumn::String(xs) => json!(xs),
             BasicTypeColumn::Null(xs) => json!(xs),
+            BasicTypeColumn::Bool(xs) => json!(xs),
+            BasicTypeColumn::Timestamp(unit, xs) => json!(xs
+                .into_iter()
+                .map(|x| format_timestamp(x, unit))
+                .collect::<Vec<_>>()),
             BasicTypeColumn::Mixed(xs) => json!(xs
                 .into_iter()
                 .map(|val| match val {
@@ -412,6 +445,8 @@ fn query_output_to_json_cols(result: QueryOutput) -> serde_json::Value {
                     Value::Str(str) => json!(str),
                     Value::Null => json!(null),
                     Value::Float(f) => json!(f.0),
+                    Value::Bool(b) => json!(b),
+                    Value::Timestamp(..) => json!(val.to_string()),
                 })
                 .collect::<Vec<_>>()),
         };
@@ -460,6 +495,8 @@ pub fn run(
             .service(query_cols)
             .service(multi_query_cols)
             .service(columns)
+            .service(queries)
+            .service(cancel)
             .service(plot)
             .service(metrics)
             .route("/hey", web::get().to(manual_hello))
@@ -490,11 +527,13 @@ fn encode_column(col: BasicTypeColumn, encode_opts: &EncodingOpts) -> api::Colum
         }
         BasicTypeColumn::String(xs) => api::Column::String(xs),
         BasicTypeColumn::Null(xs) => api::Column::Null(xs),
+        BasicTypeColumn::Bool(xs) => api::Column::Int(xs.into_iter().map(|b| b as i64).collect()),
+        BasicTypeColumn::Timestamp(_, xs) => api::Column::Int(xs),
         BasicTypeColumn::Mixed(xs) => {
             let mut type_signature = 0u8;
             for val in &xs {
                 match val {
-                    Value::Int(_) => type_signature |= 1,
+                    Value::Int(_) | Value::Bool(_) | Value::Timestamp(..) => type_signature |= 1,
                     Value::Str(_) => type_signature |= 2,
                     Value::Null => type_signature |= 4,
                     Value::Float(_) => type_signature |= 8,
@@ -513,7 +552,8 @@ fn encode_column(col: BasicTypeColumn, encode_opts: &EncodingOpts) -> api::Colum
                 api::Column::Int(
                     xs.into_iter()
                         .map(|val| match val {
-                            Value::Int(int) => int,
+                            Value::Int(int) | Value::Timestamp(int, _) => int,
+                            Value::Bool(b) => b as i64,
                             _ => unreachable!(),
                         })
                         .collect(),
@@ -539,16 +579,7 @@ fn encode_column(col: BasicTypeColumn, encode_opts: &EncodingOpts) -> api::Colum
                     api::Column::Float(floats)
                 }
             } else {
-                api::Column::Mixed(
-                    xs.into_iter()
-                        .map(|val| match val {
-                            Value::Int(int) => api::AnyVal::Int(int),
-                            Value::Str(str) => api::AnyVal::Str(str),
-                            Value::Null => api::AnyVal::Null,
-                            Value::Float(float) => api::AnyVal::Float(float.0),
-                        })
-                        .collect(),
-                )
+                api::Column::Mixed(xs.into_iter().map(api::AnyVal::from).collect())
             }
         }
     }
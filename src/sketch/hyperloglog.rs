@@ -0,0 +1,147 @@
+use std::hash::{Hash, Hasher};
+
+use seahash::SeaHasher;
+
+/// Number of bits of the hash used to select a register.
+const PRECISION: u32 = 14;
+const REGISTERS: usize = 1 << PRECISION;
+/// Sketches are kept as an exact set of hashes until this many distinct hashes have been observed,
+/// which makes small cardinalities exact and keeps the memory footprint of small groups low.
+const MAX_SPARSE_LEN: usize = REGISTERS / 8;
+
+/// HyperLogLog cardinality estimator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HyperLogLog {
+    sparse: Vec<u64>,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    pub fn new() -> HyperLogLog {
+        HyperLogLog {
+            sparse: Vec::new(),
+            registers: Vec::new(),
+        }
+    }
+
+    pub fn insert<T: Hash>(&mut self, value: &T) {
+        let mut hasher = SeaHasher::new();
+        value.hash(&mut hasher);
+        self.insert_hash(hasher.finish());
+    }
+
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        if other.is_dense() {
+            if self.is_dense() {
+                for (register, &other) in self.registers.iter_mut().zip(&other.registers) {
+                    *register = (*register).max(other);
+                }
+            } else {
+                let sparse = std::mem::take(&mut self.sparse);
+                self.registers = other.registers.clone();
+                for hash in sparse {
+                    self.insert_dense(hash);
+                }
+            }
+        } else {
+            for &hash in &other.sparse {
+                self.insert_hash(hash);
+            }
+        }
+    }
+
+    pub fn estimate(&self) -> i64 {
+        if !self.is_dense() {
+            return self.sparse.len() as i64;
+        }
+        let m = REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let mut sum = 0.0;
+        let mut zeros = 0;
+        for &register in &self.registers {
+            sum += 1.0 / (1u64 << register) as f64;
+            if register == 0 {
+                zeros += 1;
+            }
+        }
+        let estimate = alpha * m * m / sum;
+        if estimate <= 2.5 * m && zeros > 0 {
+            // Linear counting is more accurate for small cardinalities
+            (m * (m / zeros as f64).ln()).round() as i64
+        } else {
+            estimate.round() as i64
+        }
+    }
+
+    fn is_dense(&self) -> bool {
+        !self.registers.is_empty()
+    }
+
+    fn insert_hash(&mut self, hash: u64) {
+        if self.is_dense() {
+            self.insert_dense(hash);
+        } else if let Err(index) = self.sparse.binary_search(&hash) {
+            self.sparse.insert(index, hash);
+            if self.sparse.len() > MAX_SPARSE_LEN {
+                self.registers = vec![0; REGISTERS];
+                for hash in std::mem::take(&mut self.sparse) {
+                    self.insert_dense(hash);
+                }
+            }
+        }
+    }
+
+    fn insert_dense(&mut self, hash: u64) {
+        let index = (hash >> (64 - PRECISION)) as usize;
+        let rank = ((hash << PRECISION).leading_zeros() + 1).min(64 - PRECISION + 1) as u8;
+        if self.registers[index] < rank {
+            self.registers[index] = rank;
+        }
+    }
+}
+
+impl Default for HyperLogLog {
+    fn default() -> HyperLogLog {
+        HyperLogLog::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate() {
+        let mut hll = HyperLogLog::new();
+        for i in 0..1000 {
+            hll.insert(&(i % 100));
+        }
+        assert_eq!(hll.estimate(), 100);
+
+        for i in 0..100_000 {
+            hll.insert(&i);
+        }
+        let error = (hll.estimate() - 100_000).abs() as f64 / 100_000.0;
+        assert!(error < 0.03, "estimate {} is off by {}", hll.estimate(), error);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut hll1 = HyperLogLog::new();
+        let mut hll2 = HyperLogLog::new();
+        let mut hll3 = HyperLogLog::new();
+        for i in 0..50_000 {
+            hll1.insert(&i);
+        }
+        for i in 25_000..75_000 {
+            hll2.insert(&i);
+        }
+        for i in 74_990..75_010 {
+            hll3.insert(&i);
+        }
+        hll3.merge(&hll1);
+        hll3.merge(&hll2);
+        let error = (hll3.estimate() - 75_010).abs() as f64 / 75_010.0;
+        assert!(error < 0.03, "estimate {} is off by {}", hll3.estimate(), error);
+    }
+}
@@ -0,0 +1,5 @@
+mod hyperloglog;
+mod tdigest;
+
+pub use self::hyperloglog::HyperLogLog;
+pub use self::tdigest::TDigest;
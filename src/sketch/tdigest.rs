@@ -0,0 +1,179 @@
+use std::f64::consts::PI;
+
+/// Controls the tradeoff between accuracy and size, digests retain at most ~`COMPRESSION` centroids.
+const COMPRESSION: f64 = 100.0;
+const MAX_UNMERGED: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+/// Merging t-digest (Dunning & Ertl) for estimating quantiles.
+/// Accuracy is highest for extreme quantiles, small inputs are represented exactly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TDigest {
+    centroids: Vec<Centroid>,
+    unmerged: Vec<Centroid>,
+    min: f64,
+    max: f64,
+}
+
+impl TDigest {
+    pub fn new() -> TDigest {
+        TDigest {
+            centroids: Vec::new(),
+            unmerged: Vec::new(),
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    pub fn insert(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.unmerged.push(Centroid {
+            mean: value,
+            weight: 1.0,
+        });
+        if self.unmerged.len() >= MAX_UNMERGED {
+            self.compress();
+        }
+    }
+
+    pub fn merge(&mut self, other: &TDigest) {
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.unmerged.extend_from_slice(&other.centroids);
+        self.unmerged.extend_from_slice(&other.unmerged);
+        if self.unmerged.len() >= MAX_UNMERGED {
+            self.compress();
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.centroids.is_empty() && self.unmerged.is_empty()
+    }
+
+    /// Estimates the value at quantile `q` (clamped to [0, 1]), returns `None` if the digest is empty.
+    pub fn quantile(&mut self, q: f64) -> Option<f64> {
+        self.compress();
+        let centroids = &self.centroids;
+        if centroids.is_empty() {
+            return None;
+        }
+        if centroids.len() == 1 {
+            return Some(centroids[0].mean);
+        }
+        let total = centroids.iter().map(|c| c.weight).sum::<f64>();
+        let target = q.clamp(0.0, 1.0) * total;
+
+        // Each centroid is assumed to be centered at the midpoint of the rank range it covers
+        let first = centroids[0];
+        if target < first.weight / 2.0 {
+            return Some(self.min + (first.mean - self.min) * target / (first.weight / 2.0));
+        }
+        let mut center = first.weight / 2.0;
+        for window in centroids.windows(2) {
+            let (left, right) = (window[0], window[1]);
+            let next_center = center + (left.weight + right.weight) / 2.0;
+            if target < next_center {
+                let fraction = (target - center) / (next_center - center);
+                return Some(left.mean + (right.mean - left.mean) * fraction);
+            }
+            center = next_center;
+        }
+        let last = centroids[centroids.len() - 1];
+        let fraction = ((target - center) / (last.weight / 2.0)).min(1.0);
+        Some(last.mean + (self.max - last.mean) * fraction)
+    }
+
+    fn compress(&mut self) {
+        if self.unmerged.is_empty() {
+            return;
+        }
+        let mut centroids = std::mem::take(&mut self.unmerged);
+        centroids.append(&mut self.centroids);
+        centroids.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+
+        let total = centroids.iter().map(|c| c.weight).sum::<f64>();
+        let mut merged = Vec::with_capacity(COMPRESSION as usize);
+        let mut current = centroids[0];
+        let mut weight_so_far = 0.0;
+        let mut weight_limit = total * k_inverse(k(0.0) + 1.0);
+        for &centroid in &centroids[1..] {
+            if weight_so_far + current.weight + centroid.weight <= weight_limit {
+                current.mean += (centroid.mean - current.mean) * centroid.weight
+                    / (current.weight + centroid.weight);
+                current.weight += centroid.weight;
+            } else {
+                weight_so_far += current.weight;
+                weight_limit = total * k_inverse(k(weight_so_far / total) + 1.0);
+                merged.push(current);
+                current = centroid;
+            }
+        }
+        merged.push(current);
+        self.centroids = merged;
+    }
+}
+
+impl Default for TDigest {
+    fn default() -> TDigest {
+        TDigest::new()
+    }
+}
+
+/// Scale function which limits the size of centroids, smaller close to the tails of the distribution.
+fn k(q: f64) -> f64 {
+    COMPRESSION / (2.0 * PI) * (2.0 * q - 1.0).asin()
+}
+
+fn k_inverse(k: f64) -> f64 {
+    if k >= COMPRESSION / 4.0 {
+        1.0
+    } else {
+        ((2.0 * PI * k / COMPRESSION).sin() + 1.0) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_inputs_exact() {
+        let mut digest = TDigest::new();
+        for x in [3.0, 1.0, 4.0, 1.0, 5.0] {
+            digest.insert(x);
+        }
+        assert_eq!(digest.quantile(0.0), Some(1.0));
+        assert_eq!(digest.quantile(0.5), Some(3.0));
+        assert_eq!(digest.quantile(1.0), Some(5.0));
+        assert_eq!(TDigest::new().quantile(0.5), None);
+    }
+
+    #[test]
+    fn test_quantiles() {
+        let mut digest1 = TDigest::new();
+        let mut digest2 = TDigest::new();
+        for i in 0..50_000 {
+            digest1.insert(i as f64);
+            digest2.insert((i + 50_000) as f64);
+        }
+        digest1.merge(&digest2);
+        for q in [0.001, 0.01, 0.1, 0.5, 0.9, 0.99, 0.999] {
+            let estimate = digest1.quantile(q).unwrap();
+            assert!(
+                (estimate - q * 100_000.0).abs() < 100_000.0 * 0.005,
+                "q={} estimate={}",
+                q,
+                estimate
+            );
+        }
+    }
+}
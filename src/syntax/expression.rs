@@ -1,14 +1,16 @@
 use self::Expr::*;
 use crate::engine::*;
 use crate::ingest::raw_val::RawVal;
-use std::collections::HashSet;
+use crate::{LogicalType, TimeUnit};
+use std::collections::{HashMap, HashSet};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Expr {
     ColName(String),
     Const(RawVal),
     Func1(Func1Type, Box<Expr>),
     Func2(Func2Type, Box<Expr>, Box<Expr>),
+    FuncN(FuncNType, Vec<Expr>),
     Aggregate(Aggregator, Box<Expr>),
 }
 
@@ -31,9 +33,15 @@ pub enum Func2Type {
     RegexMatch,
     Like,
     NotLike,
+    StartsWith,
+    Pow,
+    Greatest,
+    Least,
+    // Arguments of aggregates that take two arguments, e.g. CORR(x, y)
+    Pair,
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Func1Type {
     Negate,
     ToYear,
@@ -42,6 +50,59 @@ pub enum Func1Type {
     IsNotNull,
     Length,
     Floor,
+    ToMonth,
+    ToDayOfWeek,
+    ToHour,
+    DateTrunc(TimeGranularity),
+    TimeBucket(BucketWidth),
+    // Interprets integer as unix timestamp in seconds
+    FromUnixtime,
+    Lower,
+    Upper,
+    Trim,
+    TrimStart,
+    TrimEnd,
+    Abs,
+    Sign,
+    Ceil,
+    // Number of decimal places to round to, negative values round to powers of ten
+    Round(i64),
+    Sqrt,
+    Ln,
+    Log10,
+    Exp,
+    // Values that cannot be converted are an error
+    Cast(CastType),
+    // Values that cannot be converted are null
+    TryCast(CastType),
+}
+
+/// Functions with a variable number of arguments.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FuncNType {
+    Concat,
+    // substr(string, start[, length])
+    Substr,
+    // split_part(string, delimiter, index)
+    SplitPart,
+    // replace(string, from, to)
+    Replace,
+    // regexp_extract(string, regex[, group])
+    RegexpExtract,
+    // if(condition, then, else), also used for CASE and NULLIF
+    If,
+    Coalesce,
+    // x IN (a, b, ...), the first argument is the tested expression
+    In,
+}
+
+/// Width of `time_bucket` intervals.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BucketWidth {
+    // In the unit of the timestamp
+    Ticks(i64),
+    // Scaled to the unit of the timestamp
+    Seconds(i64),
 }
 
 impl Expr {
@@ -55,11 +116,58 @@ impl Expr {
                 expr2.add_colnames(result);
             }
             Func1(_, ref expr) => expr.add_colnames(result),
+            FuncN(_, ref exprs) => {
+                for expr in exprs {
+                    expr.add_colnames(result);
+                }
+            }
             Aggregate(_, ref expr) => expr.add_colnames(result),
             Const(_) => {}
         }
     }
 
+    /// Replaces every column name for which `rename` returns a new name.
+    pub fn rename_columns(&mut self, rename: &dyn Fn(&str) -> Option<String>) {
+        match *self {
+            ColName(ref mut name) => {
+                if let Some(new_name) = rename(name) {
+                    *name = new_name;
+                }
+            }
+            Func2(_, ref mut expr1, ref mut expr2) => {
+                expr1.rename_columns(rename);
+                expr2.rename_columns(rename);
+            }
+            Func1(_, ref mut expr) => expr.rename_columns(rename),
+            FuncN(_, ref mut exprs) => {
+                for expr in exprs {
+                    expr.rename_columns(rename);
+                }
+            }
+            Aggregate(_, ref mut expr) => expr.rename_columns(rename),
+            Const(_) => {}
+        }
+    }
+
+    /// Logical type of the values produced by the expression, given the logical types of the referenced columns.
+    pub fn logical_type(&self, column_types: &HashMap<String, LogicalType>) -> LogicalType {
+        match *self {
+            ColName(ref name) => column_types.get(name).copied().unwrap_or_default(),
+            Aggregate(
+                Aggregator::MaxI64 | Aggregator::MaxF64 | Aggregator::MinI64 | Aggregator::MinF64,
+                ref expr,
+            ) => expr.logical_type(column_types),
+            Func1(Func1Type::DateTrunc(_), ref expr) => match expr.logical_type(column_types) {
+                LogicalType::Timestamp(unit) => LogicalType::Timestamp(unit),
+                _ => LogicalType::Timestamp(TimeUnit::Second),
+            },
+            Func1(Func1Type::TimeBucket(_), ref expr) => expr.logical_type(column_types),
+            Func1(Func1Type::FromUnixtime, _) => LogicalType::Timestamp(TimeUnit::Second),
+            Func2(Func2Type::StartsWith, _, _) => LogicalType::Bool,
+            _ => LogicalType::Plain,
+        }
+    }
+
     pub fn func(ftype: Func2Type, expr1: Expr, expr2: Expr) -> Expr {
         Func2(ftype, Box::new(expr1), Box::new(expr2))
     }
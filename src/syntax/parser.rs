@@ -1,5 +1,7 @@
 extern crate sqlparser;
 
+use crate::engine::Join;
+use crate::engine::WindowFrame;
 use crate::engine::Query;
 use crate::engine::*;
 use crate::ingest::raw_val::RawVal;
@@ -10,9 +12,26 @@ use crate::QueryError;
 use sqlparser::ast::{Expr as ASTNode, *};
 use sqlparser::dialect::GenericDialect;
 use sqlparser::parser::{Parser, ParserError};
+use std::collections::HashMap;
+
+/// SELECT query or statement that removes data
+#[derive(Debug)]
+pub enum ParsedStatement {
+    Query(Box<Query>),
+    Mutation(Mutation),
+}
 
 // Convert sqlparser-rs `ASTNode` to LocustDB's `Query`
 pub fn parse_query(query: &str) -> Result<Query, QueryError> {
+    match parse_statement(query)? {
+        ParsedStatement::Query(query) => Ok(*query),
+        ParsedStatement::Mutation(_) => Err(QueryError::ParseError(
+            "Only SELECT queries are supported.".to_string(),
+        )),
+    }
+}
+
+pub fn parse_statement(query: &str) -> Result<ParsedStatement, QueryError> {
     let dialect = GenericDialect {};
     let mut ast = Parser::parse_sql(&dialect, query).map_err(|e| match e {
         ParserError::ParserError(e_str) => QueryError::ParseError(e_str),
@@ -25,37 +44,261 @@ pub fn parse_query(query: &str) -> Result<Query, QueryError> {
         )));
     }
 
-    let query = match ast.pop().unwrap() {
-        Statement::Query(query) => query,
+    let mutation = match ast.pop().unwrap() {
+        Statement::Query(query) => {
+            return Ok(ParsedStatement::Query(Box::new(convert_query(query, &HashMap::new())?)))
+        }
+        Statement::Drop {
+            object_type: ObjectType::Table,
+            if_exists,
+            names,
+            ..
+        } => Mutation::DropTable {
+            table: get_single_table_name(names, "DROP TABLE")?,
+            if_exists,
+        },
+        Statement::Truncate { table_names, .. } => Mutation::Truncate {
+            table: get_single_table_name(
+                table_names.into_iter().map(|t| t.name).collect(),
+                "TRUNCATE",
+            )?,
+        },
+        Statement::Delete(Delete {
+            tables,
+            from: FromTable::WithFromKeyword(mut from) | FromTable::WithoutKeyword(mut from),
+            using,
+            selection,
+            returning,
+            order_by,
+            limit,
+        }) => {
+            if !tables.is_empty() || from.len() != 1 || using.is_some() {
+                return Err(QueryError::NotImplemented(
+                    "DELETE from multiple tables".to_string(),
+                ));
+            }
+            if returning.is_some() || !order_by.is_empty() || limit.is_some() {
+                return Err(QueryError::NotImplemented(
+                    "DELETE with RETURNING, ORDER BY or LIMIT".to_string(),
+                ));
+            }
+            let TableWithJoins { relation, joins } = from.pop().unwrap();
+            if !joins.is_empty() {
+                return Err(QueryError::NotImplemented("DELETE with JOIN".to_string()));
+            }
+            Mutation::Delete {
+                table: get_table_name(Some(relation))?,
+                filter: match selection {
+                    Some(ref s) => *convert_to_native_expr(s)?,
+                    None => Expr::Const(RawVal::Int(1)),
+                },
+            }
+        }
         _ => {
             return Err(QueryError::ParseError(
-                "Only SELECT queries are supported.".to_string(),
+                "Only SELECT, DELETE, TRUNCATE and DROP TABLE statements are supported.".to_string(),
             ))
         }
     };
+    Ok(ParsedStatement::Mutation(mutation))
+}
+
+fn get_single_table_name(mut names: Vec<ObjectName>, statement: &str) -> Result<String, QueryError> {
+    if names.len() != 1 {
+        return Err(QueryError::NotImplemented(format!(
+            "{} with multiple tables",
+            statement
+        )));
+    }
+    Ok(strip_quotes(&format!("{}", names.pop().unwrap())))
+}
+
+// Converts a query or subquery, `ctes` are the common table expressions defined by enclosing queries
+fn convert_query(
+    mut query: Box<sqlparser::ast::Query>,
+    ctes: &HashMap<String, Query>,
+) -> Result<Query, QueryError> {
+    let mut ctes = ctes.clone();
+    if let Some(with) = query.with.take() {
+        if with.recursive {
+            return Err(QueryError::NotImplemented("WITH RECURSIVE".to_string()));
+        }
+        for cte in with.cte_tables {
+            if !cte.alias.columns.is_empty() {
+                return Err(QueryError::NotImplemented(format!(
+                    "Column names in common table expression {}",
+                    cte.alias
+                )));
+            }
+            let cte_query = convert_query(cte.query, &ctes)?;
+            ctes.insert(strip_quotes(&cte.alias.name.value), cte_query);
+        }
+    }
 
-    let (projection, relation, selection, order_by, limit, offset) = get_query_components(query)?;
-    let projection = get_projection(projection)?;
-    let table = get_table_name(relation)?;
+    if let SetExpr::SetOperation { .. } = *query.body {
+        return convert_union(query, &ctes);
+    }
+
+    let (projection, distinct, from, selection, group_by, having, order_by, limit, offset) =
+        get_query_components(query)?;
+    let (projection, downsample, windows) = get_projection(projection)?;
+    let (table, subqueries, join) = match from {
+        Some(TableWithJoins { relation, joins }) => {
+            let join = get_join(&relation, joins, &ctes)?;
+            let (table, subqueries) = get_table(relation, &ctes)?;
+            (table, subqueries, join)
+        }
+        None => (get_table_name(None)?, vec![], None),
+    };
     let filter = match selection {
         Some(ref s) => *convert_to_native_expr(s)?,
         None => Expr::Const(RawVal::Int(1)),
     };
+    let group_by = group_by
+        .iter()
+        .map(|e| convert_to_native_expr(e).map(|e| *e))
+        .collect::<Result<Vec<_>, _>>()?;
+    let having = match having {
+        Some(ref h) => Some(*convert_to_native_expr(h)?),
+        None => None,
+    };
     let order_by = get_order_by(order_by)?;
     let limit_clause = LimitClause {
         limit: get_limit(limit)?,
         offset: get_offset(offset)?,
     };
 
-    Ok(Query {
+    let mut query = Query {
         select: projection,
+        distinct,
         table,
+        subqueries,
         filter,
+        group_by,
+        having,
         order_by,
         limit: limit_clause,
+        downsample,
+        windows,
+        join: None,
+    };
+    // Columns of the queried table may be qualified by its name or alias when joining with another table
+    if let Some((join, qualifier)) = join {
+        query.join = Some(join);
+        query.rename_columns(&|name| {
+            name.strip_prefix(qualifier.as_str())?
+                .strip_prefix('.')
+                .map(str::to_string)
+        });
+    }
+    Ok(query)
+}
+
+// Converts `a UNION ALL b ...` into a query over the concatenated results of all branches, to which the ORDER BY and
+// LIMIT clauses of the union are applied. Columns are named after the columns of the first branch.
+fn convert_union(
+    query: Box<sqlparser::ast::Query>,
+    ctes: &HashMap<String, Query>,
+) -> Result<Query, QueryError> {
+    let sqlparser::ast::Query {
+        body,
+        order_by,
+        limit_clause,
+        ..
+    } = *query;
+    let mut branches = vec![];
+    get_union_branches(*body, &mut branches)?;
+    let subqueries = branches
+        .into_iter()
+        .map(|branch| match branch {
+            SetExpr::Query(query) => convert_query(query, ctes),
+            branch => convert_query(
+                Box::new(sqlparser::ast::Query {
+                    with: None,
+                    body: Box::new(branch),
+                    order_by: None,
+                    limit_clause: None,
+                    fetch: None,
+                    locks: vec![],
+                    for_clause: None,
+                    settings: None,
+                    format_clause: None,
+                }),
+                ctes,
+            ),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let select = if subqueries[0].is_select_star() {
+        subqueries[0].select.clone()
+    } else {
+        subqueries[0]
+            .select
+            .iter()
+            .map(|column| ColumnInfo {
+                expr: Expr::ColName(column.name.clone()),
+                name: column.name.clone(),
+            })
+            .collect()
+    };
+    let (limit, offset) = get_limit_offset(limit_clause);
+    Ok(Query {
+        select,
+        distinct: false,
+        table: "_union".to_string(),
+        subqueries,
+        filter: Expr::Const(RawVal::Int(1)),
+        group_by: vec![],
+        having: None,
+        order_by: get_order_by(get_order_by_exprs(order_by))?,
+        limit: LimitClause {
+            limit: get_limit(limit)?,
+            offset: get_offset(offset)?,
+        },
+        downsample: None,
+        windows: vec![],
+        join: None,
     })
 }
 
+fn get_union_branches(body: SetExpr, branches: &mut Vec<SetExpr>) -> Result<(), QueryError> {
+    match body {
+        SetExpr::SetOperation {
+            op: SetOperator::Union,
+            set_quantifier: SetQuantifier::All,
+            left,
+            right,
+        } => {
+            get_union_branches(*left, branches)?;
+            get_union_branches(*right, branches)
+        }
+        SetExpr::SetOperation {
+            op, set_quantifier, ..
+        } => Err(QueryError::NotImplemented(
+            format!("{} {}", op, set_quantifier).trim_end().to_string(),
+        )),
+        branch => {
+            branches.push(branch);
+            Ok(())
+        }
+    }
+}
+
+fn get_order_by_exprs(order_by: Option<OrderBy>) -> Option<Vec<OrderByExpr>> {
+    order_by.and_then(|o| match o.kind {
+        OrderByKind::Expressions(exprs) => Some(exprs),
+        _ => None,
+    })
+}
+
+fn get_limit_offset(
+    limit_clause: Option<sqlparser::ast::LimitClause>,
+) -> (Option<ASTNode>, Option<Offset>) {
+    match limit_clause {
+        Some(sqlparser::ast::LimitClause::LimitOffset { limit, offset, .. }) => (limit, offset),
+        _ => (None, None),
+    }
+}
+
 // TODO: use struct
 #[allow(clippy::type_complexity)]
 fn get_query_components(
@@ -63,7 +306,10 @@ fn get_query_components(
 ) -> Result<
     (
         Vec<SelectItem>,
-        Option<TableFactor>,
+        bool,
+        Option<TableWithJoins>,
+        Option<ASTNode>,
+        Vec<ASTNode>,
         Option<ASTNode>,
         Option<Vec<OrderByExpr>>,
         Option<ASTNode>,
@@ -89,35 +335,37 @@ fn get_query_components(
             // TODO: ensure other items not set
             ..
         }) => {
-            if let GroupByExpr::Expressions(exprs, with_mods) = group_by
-                && (!exprs.is_empty() || !with_mods.is_empty())
-            {
-                Err(QueryError::NotImplemented("Group By  (Hint: If your SELECT clause contains any aggregation expressions, results will implicitly grouped by all other expresssions.)".to_string()))
-            } else if having.is_some() {
-                Err(QueryError::NotImplemented("Having".to_string()))
-            } else if distinct.is_some() {
-                Err(QueryError::NotImplemented("DISTINCT".to_string()))
-            } else if from.len() > 1 {
+            // GROUP BY ALL is equivalent to the implicit grouping by all non-aggregate projections
+            let group_by = match group_by {
+                GroupByExpr::Expressions(exprs, with_mods) if with_mods.is_empty() => exprs,
+                GroupByExpr::All(with_mods) if with_mods.is_empty() => vec![],
+                _ => {
+                    return Err(QueryError::NotImplemented(
+                        "GROUP BY modifiers".to_string(),
+                    ))
+                }
+            };
+            let distinct = match distinct {
+                None => false,
+                Some(Distinct::Distinct) => true,
+                Some(Distinct::On(_)) => {
+                    return Err(QueryError::NotImplemented("DISTINCT ON".to_string()))
+                }
+            };
+            if from.len() > 1 {
                 Err(QueryError::NotImplemented(
                     "Selecting from multiple tables.".to_string(),
                 ))
-            } else if !from.is_empty() && !from[0].joins.is_empty() {
-                Err(QueryError::NotImplemented("JOIN".to_string()))
             } else {
-                let (limit, offset) = match limit_clause {
-                    Some(sqlparser::ast::LimitClause::LimitOffset { limit, offset, .. }) => {
-                        (limit, offset)
-                    }
-                    _ => (None, None),
-                };
+                let (limit, offset) = get_limit_offset(limit_clause);
                 Ok((
                     projection,
-                    from.pop().map(|t| t.relation),
+                    distinct,
+                    from.pop(),
                     selection,
-                    order_by.and_then(|o| match o.kind {
-                        OrderByKind::Expressions(exprs) => Some(exprs),
-                        _ => None,
-                    }),
+                    group_by,
+                    having,
+                    get_order_by_exprs(order_by),
                     limit,
                     offset,
                 ))
@@ -130,37 +378,252 @@ fn get_query_components(
     }
 }
 
-fn get_projection(projection: Vec<SelectItem>) -> Result<Vec<ColumnInfo>, QueryError> {
+#[allow(clippy::type_complexity)]
+fn get_projection(
+    projection: Vec<SelectItem>,
+) -> Result<(Vec<ColumnInfo>, Option<Downsample>, Vec<Window>), QueryError> {
     let mut result = Vec::<ColumnInfo>::new();
+    let mut downsample = None;
+    let mut windows = Vec::new();
     for elem in &projection {
-        match elem {
-            SelectItem::UnnamedExpr(e) => {
-                // sqlparser-rs provides string of the projection as entered by the user.
-                // Storing this string in Query.select corresponding to locustdb's Expr.
-                // These will later be used as colnames of query results.
+        let (expr, name) = match elem {
+            // sqlparser-rs provides string of the projection as entered by the user.
+            // Storing this string in Query.select corresponding to locustdb's Expr.
+            // These will later be used as colnames of query results.
+            SelectItem::UnnamedExpr(e) => (e, strip_quotes(&format!("{}", e))),
+            SelectItem::Wildcard(_) => {
                 result.push(ColumnInfo {
-                    expr: *convert_to_native_expr(e)?,
-                    name: strip_quotes(&format!("{}", e)),
-                })
-            }
-            SelectItem::Wildcard(_) => result.push(ColumnInfo {
-                expr: Expr::ColName('*'.to_string()),
-                name: "*".to_string(),
-            }),
-            SelectItem::ExprWithAlias { expr, alias } => result.push(ColumnInfo {
-                expr: *convert_to_native_expr(expr)?,
-                name: strip_quotes(&alias.to_string()),
-            }),
+                    expr: Expr::ColName('*'.to_string()),
+                    name: "*".to_string(),
+                });
+                continue;
+            }
+            SelectItem::ExprWithAlias { expr, alias } => (expr, strip_quotes(&alias.to_string())),
             _ => {
                 return Err(QueryError::NotImplemented(format!(
                     "Unsupported projection in SELECT: {}",
                     elem
                 )))
             }
+        };
+        if let Some((arg, window)) = get_window(expr, result.len())? {
+            windows.push(window);
+            result.push(ColumnInfo { expr: arg, name });
+            continue;
         }
+        let expr = match get_downsample(expr, result.len())? {
+            Some((y, d)) => {
+                if downsample.replace(d).is_some() {
+                    return Err(QueryError::ParseError(
+                        "At most one LTTB or MINMAX function per query".to_string(),
+                    ));
+                }
+                y
+            }
+            None => *convert_to_native_expr(expr)?,
+        };
+        result.push(ColumnInfo { expr, name });
     }
 
-    Ok(result)
+    Ok((result, downsample, windows))
+}
+
+/// Parses top-level window functions such as `SUM(x) OVER (PARTITION BY a ORDER BY b ROWS 10 PRECEDING)`, and returns
+/// the argument of the function which is evaluated by select column `column`.
+fn get_window(node: &ASTNode, column: usize) -> Result<Option<(Expr, Window)>, QueryError> {
+    let f = match node {
+        ASTNode::Function(f) => f,
+        _ => return Ok(None),
+    };
+    let spec = match &f.over {
+        None => return Ok(None),
+        Some(WindowType::WindowSpec(spec)) if spec.window_name.is_none() => spec,
+        Some(window) => return Err(QueryError::NotImplemented(format!("Named window {}", window))),
+    };
+    if let FunctionArguments::List(list) = &f.args {
+        if list.duplicate_treatment == Some(DuplicateTreatment::Distinct) {
+            return Err(QueryError::NotImplemented(format!("DISTINCT in window function {}", f.name)));
+        }
+    }
+    let name = format!("{}", f.name).to_uppercase();
+    let (function, arg) = match name.as_ref() {
+        "ROW_NUMBER" => {
+            function_args(f, &name, 0, 0)?;
+            (WindowFunction::RowNumber, Expr::Const(RawVal::Null))
+        }
+        "LAG" | "LEAD" => {
+            let mut args = function_args(f, &name, 1, 3)?.into_iter();
+            let arg = args.next().unwrap();
+            let offset = match args.next() {
+                Some(Expr::Const(RawVal::Int(offset))) if offset >= 0 => offset as usize,
+                Some(_) => {
+                    return Err(QueryError::ParseError(format!(
+                        "Offset in {} function must be a non-negative integer constant",
+                        name
+                    )))
+                }
+                None => 1,
+            };
+            let default = match args.next().map(constant) {
+                Some(Some(default)) => default,
+                Some(None) => {
+                    return Err(QueryError::ParseError(format!(
+                        "Default value in {} function must be a constant",
+                        name
+                    )))
+                }
+                None => RawVal::Null,
+            };
+            if name == "LAG" {
+                (WindowFunction::Lag(offset, default), arg)
+            } else {
+                (WindowFunction::Lead(offset, default), arg)
+            }
+        }
+        "COUNT" => match &f.args {
+            FunctionArguments::List(list)
+                if list.args.len() == 1
+                    && matches!(list.args[0], FunctionArg::Unnamed(FunctionArgExpr::Wildcard)) =>
+            {
+                (WindowFunction::CountRows, Expr::Const(RawVal::Null))
+            }
+            _ => (WindowFunction::Count, function_args(f, &name, 1, 1)?.remove(0)),
+        },
+        "SUM" => (WindowFunction::Sum, function_args(f, &name, 1, 1)?.remove(0)),
+        "AVG" => (WindowFunction::Avg, function_args(f, &name, 1, 1)?.remove(0)),
+        "MIN" => (WindowFunction::Min, function_args(f, &name, 1, 1)?.remove(0)),
+        "MAX" => (WindowFunction::Max, function_args(f, &name, 1, 1)?.remove(0)),
+        _ => return Err(QueryError::NotImplemented(format!("Window function {}", f.name))),
+    };
+    let partition_by = spec
+        .partition_by
+        .iter()
+        .map(|e| convert_to_native_expr(e).map(|e| *e))
+        .collect::<Result<Vec<_>, _>>()?;
+    let order_by = get_order_by(Some(spec.order_by.clone()))?;
+    let frame = match &spec.window_frame {
+        Some(frame) => get_window_frame(frame)?,
+        None => WindowFrame::implicit(!order_by.is_empty()),
+    };
+    let window = Window {
+        function,
+        column,
+        partition_by,
+        order_by,
+        frame,
+    };
+    Ok(Some((arg, window)))
+}
+
+/// Returns the value of constant expressions, including negative numbers.
+fn constant(expr: Expr) -> Option<RawVal> {
+    match expr {
+        Expr::Const(value) => Some(value),
+        Expr::Func1(Func1Type::Negate, box Expr::Const(RawVal::Int(i))) => Some(RawVal::Int(-i)),
+        Expr::Func1(Func1Type::Negate, box Expr::Const(RawVal::Float(f))) => Some(RawVal::Float(-f)),
+        _ => None,
+    }
+}
+
+fn get_window_frame(frame: &sqlparser::ast::WindowFrame) -> Result<WindowFrame, QueryError> {
+    let peers = match frame.units {
+        WindowFrameUnits::Rows => false,
+        WindowFrameUnits::Range => true,
+        WindowFrameUnits::Groups => {
+            return Err(QueryError::NotImplemented("GROUPS window frames".to_string()))
+        }
+    };
+    let bound = |bound: &WindowFrameBound| -> Result<FrameBound, QueryError> {
+        let offset = |expr: &ASTNode| match *convert_to_native_expr(expr)? {
+            _ if peers => Err(QueryError::NotImplemented(format!(
+                "RANGE window frame with offset {}",
+                expr
+            ))),
+            Expr::Const(RawVal::Int(n)) if n >= 0 => Ok(n as usize),
+            _ => Err(QueryError::ParseError(format!(
+                "Window frame offset must be a non-negative integer constant, found {}",
+                expr
+            ))),
+        };
+        Ok(match bound {
+            WindowFrameBound::CurrentRow => FrameBound::CurrentRow,
+            WindowFrameBound::Preceding(None) => FrameBound::UnboundedPreceding,
+            WindowFrameBound::Preceding(Some(n)) => FrameBound::Preceding(offset(n)?),
+            WindowFrameBound::Following(None) => FrameBound::UnboundedFollowing,
+            WindowFrameBound::Following(Some(n)) => FrameBound::Following(offset(n)?),
+        })
+    };
+    Ok(WindowFrame {
+        peers,
+        start: bound(&frame.start_bound)?,
+        end: match &frame.end_bound {
+            Some(end) => bound(end)?,
+            None => FrameBound::CurrentRow,
+        },
+    })
+}
+
+/// Parses top-level `LTTB(x, y, n)` and `MINMAX(x, y, n)` projections, which select `y` downsampled to `n` points.
+fn get_downsample(node: &ASTNode, column: usize) -> Result<Option<(Expr, Downsample)>, QueryError> {
+    let f = match node {
+        ASTNode::Function(f) => f,
+        _ => return Ok(None),
+    };
+    let method = match format!("{}", f.name).to_uppercase().as_ref() {
+        "LTTB" => DownsampleMethod::Lttb,
+        "MINMAX" => DownsampleMethod::MinMax,
+        _ => return Ok(None),
+    };
+    match &f.args {
+        FunctionArguments::List(list) if list.args.len() == 3 => {
+            let max_points = match *func_arg_to_native_expr(&list.args[2])? {
+                Expr::Const(RawVal::Int(n)) if n > 0 => n as usize,
+                _ => {
+                    return Err(QueryError::ParseError(format!(
+                        "Number of points in {} must be a positive integer constant",
+                        f.name
+                    )))
+                }
+            };
+            let downsample = Downsample {
+                method,
+                x: *func_arg_to_native_expr(&list.args[0])?,
+                column,
+                max_points,
+            };
+            Ok(Some((*func_arg_to_native_expr(&list.args[1])?, downsample)))
+        }
+        _ => Err(QueryError::ParseError(format!(
+            "Expected three arguments in {} function",
+            f.name
+        ))),
+    }
+}
+
+/// Returns the name of the queried table, and the query producing the table if it is a subquery or common table
+/// expression.
+fn get_table(
+    relation: TableFactor,
+    ctes: &HashMap<String, Query>,
+) -> Result<(String, Vec<Query>), QueryError> {
+    match relation {
+        TableFactor::Derived {
+            lateral: false,
+            subquery,
+            alias,
+        } => {
+            let name = match alias {
+                Some(alias) => strip_quotes(&alias.name.value),
+                None => "_subquery".to_string(),
+            };
+            Ok((name, vec![convert_query(subquery, ctes)?]))
+        }
+        relation => {
+            let name = get_table_name(Some(relation))?;
+            let cte = ctes.get(&name).cloned().into_iter().collect();
+            Ok((name, cte))
+        }
+    }
 }
 
 fn get_table_name(relation: Option<TableFactor>) -> Result<String, QueryError> {
@@ -175,6 +638,107 @@ fn get_table_name(relation: Option<TableFactor>) -> Result<String, QueryError> {
     }
 }
 
+/// Returns the JOIN clause, if any, and the name or alias that qualifies columns of the queried table.
+fn get_join(
+    relation: &TableFactor,
+    mut joins: Vec<sqlparser::ast::Join>,
+    ctes: &HashMap<String, Query>,
+) -> Result<Option<(Join, String)>, QueryError> {
+    let join = match joins.len() {
+        0 => return Ok(None),
+        1 => joins.pop().unwrap(),
+        _ => {
+            return Err(QueryError::NotImplemented(
+                "Joining more than two tables".to_string(),
+            ))
+        }
+    };
+    let (kind, constraint) = match join.join_operator {
+        JoinOperator::Join(c) | JoinOperator::Inner(c) => (JoinKind::Inner, c),
+        JoinOperator::Left(c) | JoinOperator::LeftOuter(c) => (JoinKind::Left, c),
+        _ => {
+            return Err(QueryError::NotImplemented(format!(
+                "Unsupported JOIN type: {}",
+                join
+            )))
+        }
+    };
+    let qualifier = get_table_alias(relation)?;
+    let table = get_table_name(Some(join.relation.clone()))?;
+    if ctes.contains_key(&table) {
+        return Err(QueryError::NotImplemented(format!(
+            "JOIN with common table expression {}",
+            table
+        )));
+    }
+    let alias = get_table_alias(&join.relation)?;
+    if alias == qualifier {
+        return Err(QueryError::ParseError(format!(
+            "Both joined tables are referred to as {}, use an alias to distinguish them",
+            alias
+        )));
+    }
+    let (left_key, right_key) = match constraint {
+        JoinConstraint::On(ASTNode::BinaryOp {
+            ref left,
+            op: BinaryOperator::Eq,
+            ref right,
+        }) => {
+            let left = *convert_to_native_expr(left)?;
+            let right = *convert_to_native_expr(right)?;
+            let joined_column = |expr: &Expr| match expr {
+                Expr::ColName(name) => name
+                    .strip_prefix(alias.as_str())
+                    .and_then(|name| name.strip_prefix('.'))
+                    .map(str::to_string),
+                _ => None,
+            };
+            match (joined_column(&left), joined_column(&right)) {
+                (None, Some(right_key)) => (left, right_key),
+                (Some(right_key), None) => (right, right_key),
+                _ => {
+                    return Err(QueryError::NotImplemented(format!(
+                        "JOIN condition must compare a column of {} with an expression over {}",
+                        alias, qualifier
+                    )))
+                }
+            }
+        }
+        JoinConstraint::Using(ref columns) if columns.len() == 1 => {
+            let column = strip_quotes(&format!("{}", columns[0]));
+            (Expr::ColName(column.clone()), column)
+        }
+        _ => {
+            return Err(QueryError::NotImplemented(
+                "JOIN condition other than a single equality or USING column".to_string(),
+            ))
+        }
+    };
+    Ok(Some((
+        Join {
+            kind,
+            table,
+            alias,
+            left_key,
+            right_key,
+            materialized: None,
+        },
+        qualifier,
+    )))
+}
+
+fn get_table_alias(relation: &TableFactor) -> Result<String, QueryError> {
+    match relation {
+        TableFactor::Table {
+            alias: Some(alias), ..
+        }
+        | TableFactor::Derived {
+            alias: Some(alias), ..
+        } => Ok(strip_quotes(&alias.name.value)),
+        _ => get_table_name(Some(relation.clone())),
+    }
+}
+
 fn get_order_by(order_by: Option<Vec<OrderByExpr>>) -> Result<Vec<(Expr, bool)>, QueryError> {
     let mut order = Vec::new();
     if let Some(sql_order_by_exprs) = order_by {
@@ -242,6 +806,20 @@ fn function_arg_to_expr(node: &FunctionArg) -> Result<&ASTNode, QueryError> {
 
 fn convert_to_native_expr(node: &ASTNode) -> Result<Box<Expr>, QueryError> {
     Ok(Box::new(match node {
+        ASTNode::BinaryOp {
+            ref left,
+            op: BinaryOperator::StringConcat,
+            ref right,
+        } => {
+            let mut args = vec![];
+            for arg in [left, right] {
+                match *convert_to_native_expr(arg)? {
+                    Expr::FuncN(FuncNType::Concat, concatenated) => args.extend(concatenated),
+                    expr => args.push(expr),
+                }
+            }
+            Expr::FuncN(FuncNType::Concat, args)
+        }
         ASTNode::BinaryOp {
             ref left,
             ref op,
@@ -261,7 +839,29 @@ fn convert_to_native_expr(node: &ASTNode) -> Result<Box<Expr>, QueryError> {
         ASTNode::Identifier(ref identifier) => {
             Expr::ColName(strip_quotes(identifier.value.as_ref()))
         }
+        ASTNode::CompoundIdentifier(ref identifiers) => Expr::ColName(
+            identifiers
+                .iter()
+                .map(|identifier| strip_quotes(identifier.value.as_ref()))
+                .collect::<Vec<_>>()
+                .join("."),
+        ),
         ASTNode::Nested(inner) => *convert_to_native_expr(inner)?,
+        ASTNode::Function(Function {
+            name,
+            args: FunctionArguments::List(list),
+            ..
+        }) if list.duplicate_treatment == Some(DuplicateTreatment::Distinct)
+            && !name.to_string().eq_ignore_ascii_case("COUNT") =>
+        {
+            return Err(QueryError::NotImplemented(format!("DISTINCT in function {}", name)));
+        }
+        ASTNode::Function(Function { name, over: Some(_), .. }) => {
+            return Err(QueryError::NotImplemented(format!(
+                "Window function {} that is not a top-level projection",
+                name
+            )));
+        }
         ASTNode::Function(f) => match format!("{}", f.name).to_uppercase().as_ref() {
             "TO_YEAR" => match &f.args {
                 FunctionArguments::List(list) if list.args.len() == 1 => Expr::Func1(
@@ -274,6 +874,85 @@ fn convert_to_native_expr(node: &ASTNode) -> Result<Box<Expr>, QueryError> {
                     ));
                 }
             },
+            name @ ("TO_MONTH" | "TO_DAY_OF_WEEK" | "TO_HOUR" | "FROM_UNIXTIME") => {
+                let ftype = match name {
+                    "TO_MONTH" => Func1Type::ToMonth,
+                    "TO_DAY_OF_WEEK" => Func1Type::ToDayOfWeek,
+                    "TO_HOUR" => Func1Type::ToHour,
+                    _ => Func1Type::FromUnixtime,
+                };
+                match &f.args {
+                    FunctionArguments::List(list) if list.args.len() == 1 => {
+                        Expr::Func1(ftype, func_arg_to_native_expr(&list.args[0])?)
+                    }
+                    _ => {
+                        return Err(QueryError::ParseError(format!(
+                            "Expected one argument in {} function",
+                            name
+                        )));
+                    }
+                }
+            }
+            "TIME_BUCKET" => match &f.args {
+                FunctionArguments::List(list) if list.args.len() == 2 => {
+                    let width = match *func_arg_to_native_expr(&list.args[0])? {
+                        Expr::Const(RawVal::Int(ticks)) => BucketWidth::Ticks(ticks),
+                        Expr::Const(RawVal::Str(ref interval)) => {
+                            BucketWidth::Seconds(parse_interval(interval).ok_or_else(|| {
+                                QueryError::ParseError(format!("Invalid interval '{}'", interval))
+                            })?)
+                        }
+                        _ => {
+                            return Err(QueryError::ParseError(
+                                "TIME_BUCKET width must be an integer or interval constant".to_string(),
+                            ));
+                        }
+                    };
+                    Expr::Func1(Func1Type::TimeBucket(width), func_arg_to_native_expr(&list.args[1])?)
+                }
+                _ => {
+                    return Err(QueryError::ParseError(
+                        "Expected two arguments in TIME_BUCKET function".to_string(),
+                    ));
+                }
+            },
+            name @ ("LTTB" | "MINMAX") => {
+                return Err(QueryError::ParseError(format!(
+                    "{} is only supported as top level expression in SELECT",
+                    name
+                )));
+            }
+            "DATE_TRUNC" => match &f.args {
+                FunctionArguments::List(list) if list.args.len() == 2 => {
+                    let granularity = match *func_arg_to_native_expr(&list.args[0])? {
+                        Expr::Const(RawVal::Str(ref granularity)) => TimeGranularity::parse(granularity)
+                            .ok_or_else(|| {
+                                QueryError::ParseError(format!("Unknown DATE_TRUNC granularity '{}'", granularity))
+                            })?,
+                        _ => {
+                            return Err(QueryError::ParseError(
+                                "DATE_TRUNC granularity must be a string constant".to_string(),
+                            ));
+                        }
+                    };
+                    Expr::Func1(Func1Type::DateTrunc(granularity), func_arg_to_native_expr(&list.args[1])?)
+                }
+                _ => {
+                    return Err(QueryError::ParseError(
+                        "Expected two arguments in DATE_TRUNC function".to_string(),
+                    ));
+                }
+            },
+            // Evaluated once per statement when parsing, so all partitions and subqueries see the same time
+            "NOW" => match &f.args {
+                FunctionArguments::None => now(),
+                FunctionArguments::List(list) if list.args.is_empty() => now(),
+                _ => {
+                    return Err(QueryError::ParseError(
+                        "Expected no arguments in NOW function".to_string(),
+                    ));
+                }
+            },
             "REGEX" => match &f.args {
                 FunctionArguments::List(list) if list.args.len() == 2 => Expr::Func2(
                     Func2Type::RegexMatch,
@@ -296,7 +975,90 @@ fn convert_to_native_expr(node: &ASTNode) -> Result<Box<Expr>, QueryError> {
                     ));
                 }
             },
+            name @ ("LOWER" | "UPPER" | "LTRIM" | "RTRIM") => {
+                let ftype = match name {
+                    "LOWER" => Func1Type::Lower,
+                    "UPPER" => Func1Type::Upper,
+                    "LTRIM" => Func1Type::TrimStart,
+                    _ => Func1Type::TrimEnd,
+                };
+                let mut args = function_args(f, name, 1, 1)?;
+                Expr::Func1(ftype, Box::new(args.remove(0)))
+            }
+            "STARTS_WITH" => {
+                let mut args = function_args(f, "STARTS_WITH", 2, 2)?;
+                let prefix = args.pop().unwrap();
+                Expr::Func2(Func2Type::StartsWith, Box::new(args.pop().unwrap()), Box::new(prefix))
+            }
+            "CONCAT" => Expr::FuncN(FuncNType::Concat, function_args(f, "CONCAT", 1, usize::MAX)?),
+            "SPLIT_PART" => Expr::FuncN(FuncNType::SplitPart, function_args(f, "SPLIT_PART", 3, 3)?),
+            "REPLACE" => Expr::FuncN(FuncNType::Replace, function_args(f, "REPLACE", 3, 3)?),
+            "REGEXP_EXTRACT" => Expr::FuncN(
+                FuncNType::RegexpExtract,
+                function_args(f, "REGEXP_EXTRACT", 2, 3)?,
+            ),
+            "IF" => Expr::FuncN(FuncNType::If, function_args(f, "IF", 3, 3)?),
+            "COALESCE" => Expr::FuncN(FuncNType::Coalesce, function_args(f, "COALESCE", 1, usize::MAX)?),
+            "NULLIF" => {
+                let mut args = function_args(f, "NULLIF", 2, 2)?;
+                let rhs = args.pop().unwrap();
+                let lhs = args.pop().unwrap();
+                Expr::FuncN(
+                    FuncNType::If,
+                    vec![Expr::func(Func2Type::Equals, lhs.clone(), rhs), Expr::Const(RawVal::Null), lhs],
+                )
+            }
+            name @ ("ABS" | "SIGN" | "CEILING" | "SQRT" | "LN" | "LOG10" | "EXP") => {
+                let ftype = match name {
+                    "ABS" => Func1Type::Abs,
+                    "SIGN" => Func1Type::Sign,
+                    "CEILING" => Func1Type::Ceil,
+                    "SQRT" => Func1Type::Sqrt,
+                    "LN" => Func1Type::Ln,
+                    "LOG10" => Func1Type::Log10,
+                    _ => Func1Type::Exp,
+                };
+                let mut args = function_args(f, name, 1, 1)?;
+                Expr::Func1(ftype, Box::new(args.remove(0)))
+            }
+            "ROUND" => {
+                let mut args = function_args(f, "ROUND", 1, 2)?;
+                let digits = match args.get(1) {
+                    None => 0,
+                    Some(Expr::Const(RawVal::Int(digits))) => *digits,
+                    Some(Expr::Func1(Func1Type::Negate, box Expr::Const(RawVal::Int(digits)))) => -digits,
+                    Some(_) => {
+                        return Err(QueryError::ParseError(
+                            "ROUND precision must be an integer constant".to_string(),
+                        ));
+                    }
+                };
+                Expr::Func1(Func1Type::Round(digits), Box::new(args.remove(0)))
+            }
+            name @ ("POW" | "POWER") => {
+                let mut args = function_args(f, name, 2, 2)?;
+                let exponent = args.pop().unwrap();
+                Expr::func(Func2Type::Pow, args.pop().unwrap(), exponent)
+            }
+            name @ ("GREATEST" | "LEAST") => {
+                let ftype = if name == "GREATEST" { Func2Type::Greatest } else { Func2Type::Least };
+                let mut args = function_args(f, name, 1, usize::MAX)?.into_iter();
+                let first = args.next().unwrap();
+                args.fold(first, |lhs, rhs| Expr::func(ftype, lhs, rhs))
+            }
             "COUNT" => match &f.args {
+                FunctionArguments::List(list)
+                    if list.args.len() == 1
+                        && list.duplicate_treatment == Some(DuplicateTreatment::Distinct) =>
+                {
+                    Expr::Aggregate(Aggregator::CountDistinct, func_arg_to_native_expr(&list.args[0])?)
+                }
+                FunctionArguments::List(list)
+                    if list.args.len() == 1
+                        && matches!(list.args[0], FunctionArg::Unnamed(FunctionArgExpr::Wildcard)) =>
+                {
+                    Expr::Aggregate(Aggregator::Count, Box::new(Expr::Const(RawVal::Int(1))))
+                }
                 FunctionArguments::List(list) if list.args.len() == 1 => {
                     Expr::Aggregate(Aggregator::Count, func_arg_to_native_expr(&list.args[0])?)
                 }
@@ -354,6 +1116,93 @@ fn convert_to_native_expr(node: &ASTNode) -> Result<Box<Expr>, QueryError> {
                     ));
                 }
             },
+            "APPROX_COUNT_DISTINCT" => match &f.args {
+                FunctionArguments::List(list) if list.args.len() == 1 => Expr::Aggregate(
+                    Aggregator::ApproxCountDistinct,
+                    func_arg_to_native_expr(&list.args[0])?,
+                ),
+                _ => {
+                    return Err(QueryError::ParseError(
+                        "Expected one argument in APPROX_COUNT_DISTINCT function".to_string(),
+                    ));
+                }
+            },
+            "APPROX_QUANTILE" => match &f.args {
+                FunctionArguments::List(list) if list.args.len() == 2 => Expr::Aggregate(
+                    Aggregator::ApproxQuantile(get_fraction(&list.args[1], "Quantile")?),
+                    func_arg_to_native_expr(&list.args[0])?,
+                ),
+                _ => {
+                    return Err(QueryError::ParseError(
+                        "Expected two arguments in APPROX_QUANTILE function".to_string(),
+                    ));
+                }
+            },
+            name @ ("VARIANCE" | "VAR_SAMP" | "VAR_POP" | "STDDEV" | "STDDEV_SAMP" | "STDDEV_POP") => {
+                let aggregator = match name {
+                    "VARIANCE" | "VAR_SAMP" => Aggregator::Variance,
+                    "VAR_POP" => Aggregator::VariancePop,
+                    "STDDEV" | "STDDEV_SAMP" => Aggregator::StdDev,
+                    _ => Aggregator::StdDevPop,
+                };
+                match &f.args {
+                    FunctionArguments::List(list) if list.args.len() == 1 => {
+                        Expr::Aggregate(aggregator, func_arg_to_native_expr(&list.args[0])?)
+                    }
+                    _ => {
+                        return Err(QueryError::ParseError(format!(
+                            "Expected one argument in {} function",
+                            name
+                        )));
+                    }
+                }
+            }
+            "CORR" => match &f.args {
+                FunctionArguments::List(list) if list.args.len() == 2 => Expr::Aggregate(
+                    Aggregator::Corr,
+                    Box::new(Expr::Func2(
+                        Func2Type::Pair,
+                        func_arg_to_native_expr(&list.args[0])?,
+                        func_arg_to_native_expr(&list.args[1])?,
+                    )),
+                ),
+                _ => {
+                    return Err(QueryError::ParseError(
+                        "Expected two arguments in CORR function".to_string(),
+                    ));
+                }
+            },
+            "MEDIAN" => match &f.args {
+                FunctionArguments::List(list) if list.args.len() == 1 => Expr::Aggregate(
+                    Aggregator::PercentileCont(ordered_float::OrderedFloat(0.5)),
+                    func_arg_to_native_expr(&list.args[0])?,
+                ),
+                _ => {
+                    return Err(QueryError::ParseError(
+                        "Expected one argument in MEDIAN function".to_string(),
+                    ));
+                }
+            },
+            "PERCENTILE_CONT" => match (&f.args, &f.within_group[..]) {
+                (FunctionArguments::List(list), [order_by]) if list.args.len() == 1 => {
+                    let p = get_fraction(&list.args[0], "Percentile")?;
+                    // Percentiles of descending order are the complementary percentiles of ascending order
+                    let p = if order_by.options.asc == Some(false) {
+                        ordered_float::OrderedFloat(1.0 - p.0)
+                    } else {
+                        p
+                    };
+                    Expr::Aggregate(
+                        Aggregator::PercentileCont(p),
+                        convert_to_native_expr(&order_by.expr)?,
+                    )
+                }
+                _ => {
+                    return Err(QueryError::ParseError(
+                        "Expected PERCENTILE_CONT(p) WITHIN GROUP (ORDER BY x)".to_string(),
+                    ));
+                }
+            },
             _ => return Err(QueryError::NotImplemented(format!("Function {:?}", f.name))),
         },
         ASTNode::IsNull(ref node) => Expr::Func1(Func1Type::IsNull, convert_to_native_expr(node)?),
@@ -382,7 +1231,110 @@ fn convert_to_native_expr(node: &ASTNode) -> Result<Box<Expr>, QueryError> {
                 convert_to_native_expr(pattern)?,
             )
         }
+        ASTNode::Substring {
+            expr,
+            substring_from,
+            substring_for,
+            ..
+        } => {
+            let mut args = vec![*convert_to_native_expr(expr)?];
+            match substring_from {
+                Some(from) => args.push(*convert_to_native_expr(from)?),
+                None => args.push(Expr::Const(RawVal::Int(1))),
+            }
+            if let Some(length) = substring_for {
+                args.push(*convert_to_native_expr(length)?);
+            }
+            Expr::FuncN(FuncNType::Substr, args)
+        }
+        ASTNode::Trim {
+            expr,
+            trim_where,
+            trim_what,
+            trim_characters,
+        } => {
+            if trim_what.is_some() || trim_characters.is_some() {
+                return Err(QueryError::NotImplemented(
+                    "TRIM of characters other than whitespace".to_string(),
+                ));
+            }
+            let ftype = match trim_where {
+                Some(TrimWhereField::Leading) => Func1Type::TrimStart,
+                Some(TrimWhereField::Trailing) => Func1Type::TrimEnd,
+                Some(TrimWhereField::Both) | None => Func1Type::Trim,
+            };
+            Expr::Func1(ftype, convert_to_native_expr(expr)?)
+        }
+        ASTNode::Case {
+            operand,
+            conditions,
+            else_result,
+        } => {
+            let mut result = match else_result {
+                Some(else_result) => *convert_to_native_expr(else_result)?,
+                None => Expr::Const(RawVal::Null),
+            };
+            for CaseWhen { condition, result: then } in conditions.iter().rev() {
+                let mut condition = *convert_to_native_expr(condition)?;
+                if let Some(operand) = operand {
+                    condition = Expr::func(Func2Type::Equals, *convert_to_native_expr(operand)?, condition);
+                }
+                result = Expr::FuncN(FuncNType::If, vec![condition, *convert_to_native_expr(then)?, result]);
+            }
+            result
+        }
+        ASTNode::InList {
+            expr,
+            list,
+            negated,
+        } => {
+            let mut args = vec![*convert_to_native_expr(expr)?];
+            for item in list {
+                args.push(*convert_to_native_expr(item)?);
+            }
+            let in_list = Expr::FuncN(FuncNType::In, args);
+            if *negated {
+                Expr::func1(Func1Type::Not, in_list)
+            } else {
+                in_list
+            }
+        }
+        ASTNode::Between {
+            expr,
+            negated,
+            low,
+            high,
+        } => {
+            let expr = *convert_to_native_expr(expr)?;
+            let between = Expr::func(
+                Func2Type::And,
+                Expr::func(Func2Type::GTE, expr.clone(), *convert_to_native_expr(low)?),
+                Expr::func(Func2Type::LTE, expr, *convert_to_native_expr(high)?),
+            );
+            if *negated {
+                Expr::func1(Func1Type::Not, between)
+            } else {
+                between
+            }
+        }
+        ASTNode::Cast {
+            kind,
+            expr,
+            data_type,
+            format,
+        } => {
+            if format.is_some() {
+                return Err(QueryError::NotImplemented("CAST with FORMAT".to_string()));
+            }
+            let cast_type = get_cast_type(data_type)?;
+            let ftype = match kind {
+                CastKind::Cast | CastKind::DoubleColon => Func1Type::Cast(cast_type),
+                CastKind::TryCast | CastKind::SafeCast => Func1Type::TryCast(cast_type),
+            };
+            Expr::Func1(ftype, convert_to_native_expr(expr)?)
+        }
         ASTNode::Floor { expr, .. } => Expr::Func1(Func1Type::Floor, convert_to_native_expr(expr)?),
+        ASTNode::Ceil { expr, .. } => Expr::Func1(Func1Type::Ceil, convert_to_native_expr(expr)?),
         _ => {
             return Err(QueryError::NotImplemented(format!(
                 "Parsing for this ASTNode not implemented: {:?}",
@@ -396,6 +1348,87 @@ fn func_arg_to_native_expr(node: &FunctionArg) -> Result<Box<Expr>, QueryError>
     convert_to_native_expr(function_arg_to_expr(node)?)
 }
 
+// Converts the arguments of function `name`, which takes between `min` and `max` arguments.
+fn function_args(f: &Function, name: &str, min: usize, max: usize) -> Result<Vec<Expr>, QueryError> {
+    let args: &[FunctionArg] = match &f.args {
+        FunctionArguments::List(list) => &list.args,
+        _ => &[],
+    };
+    if args.len() < min || args.len() > max {
+        let expected = if min == max {
+            format!("{}", min)
+        } else if max == usize::MAX {
+            format!("at least {}", min)
+        } else {
+            format!("{} to {}", min, max)
+        };
+        return Err(QueryError::ParseError(format!(
+            "Expected {} arguments in {} function, found {}",
+            expected,
+            name,
+            args.len()
+        )));
+    }
+    args.iter().map(|arg| func_arg_to_native_expr(arg).map(|expr| *expr)).collect()
+}
+
+// Parses the quantile/percentile argument of an aggregate function, which must be a constant in [0, 1].
+fn get_fraction(node: &FunctionArg, name: &str) -> Result<ordered_float::OrderedFloat<f64>, QueryError> {
+    let q = match *func_arg_to_native_expr(node)? {
+        Expr::Const(RawVal::Int(i)) => i as f64,
+        Expr::Const(RawVal::Float(f)) => f.0,
+        _ => return Err(QueryError::ParseError(format!("{} must be a numeric constant", name))),
+    };
+    if !(0.0..=1.0).contains(&q) {
+        return Err(QueryError::ParseError(format!("{} {} is not in [0, 1]", name, q)));
+    }
+    Ok(ordered_float::OrderedFloat(q))
+}
+
+fn get_cast_type(data_type: &DataType) -> Result<CastType, QueryError> {
+    Ok(match data_type {
+        DataType::Int(_)
+        | DataType::Integer(_)
+        | DataType::BigInt(_)
+        | DataType::SmallInt(_)
+        | DataType::TinyInt(_)
+        | DataType::Int8(_)
+        | DataType::Int64 => CastType::Integer,
+        DataType::Float(_)
+        | DataType::Double(_)
+        | DataType::DoublePrecision
+        | DataType::Real
+        | DataType::Float64
+        | DataType::Float8 => CastType::Float,
+        DataType::String(_) | DataType::Text | DataType::Varchar(_) | DataType::Char(_) => CastType::String,
+        _ => return Err(QueryError::NotImplemented(format!("CAST to {}", data_type))),
+    })
+}
+
+/// Parses intervals such as `30s`, `5 minutes` or `1h` into seconds.
+fn parse_interval(interval: &str) -> Option<i64> {
+    let interval = interval.trim();
+    let split = interval.find(|c: char| !c.is_ascii_digit())?;
+    let (count, unit) = interval.split_at(split);
+    let seconds = match unit.trim().to_lowercase().as_ref() {
+        "s" | "sec" | "second" | "seconds" => 1,
+        "m" | "min" | "minute" | "minutes" => 60,
+        "h" | "hour" | "hours" => 60 * 60,
+        "d" | "day" | "days" => 24 * 60 * 60,
+        "w" | "week" | "weeks" => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+    count.parse::<i64>().ok()?.checked_mul(seconds)
+}
+
+/// The current time as a constant. Statements are parsed on every run, so repeated queries see the current time.
+fn now() -> Expr {
+    Expr::Func1(
+        Func1Type::FromUnixtime,
+        Box::new(Expr::Const(RawVal::Int(chrono::Utc::now().timestamp()))),
+    )
+}
+
 fn strip_quotes(ident: &str) -> String {
     if ident.starts_with('`') || ident.starts_with('"') {
         ident[1..ident.len() - 1].to_string()
@@ -449,6 +1482,8 @@ fn get_raw_val(constant: &Value) -> Result<RawVal, QueryError> {
             }
         }
         Value::SingleQuotedString(string) => Ok(RawVal::Str(string.to_string())),
+        // Booleans are represented as integers, which makes them comparable to boolean columns
+        Value::Boolean(b) => Ok(RawVal::Int(*b as i64)),
         Value::Null => Ok(RawVal::Null),
         _ => Err(QueryError::NotImplemented(format!("{:?}", constant))),
     }
@@ -462,20 +1497,151 @@ mod tests {
     fn test_select_star() {
         assert_eq!(
             format!("{:?}", parse_query("select * from default limit 100")),
-            "Ok(Query { select: [ColumnInfo { expr: ColName(\"*\"), name: \"*\" }], table: \"default\", filter: Const(Int(1)), order_by: [], limit: LimitClause { limit: 100, offset: 0 } })");
+            "Ok(Query { select: [ColumnInfo { expr: ColName(\"*\"), name: \"*\" }], distinct: false, table: \"default\", subqueries: [], filter: Const(Int(1)), group_by: [], having: None, order_by: [], limit: LimitClause { limit: 100, offset: 0 }, downsample: None, windows: [], join: None })");
     }
 
     #[test]
     fn test_alias() {
         assert_eq!(
             format!("{:?}", parse_query("select trip_id as id from default limit 100")),
-            "Ok(Query { select: [ColumnInfo { expr: ColName(\"trip_id\"), name: \"id\" }], table: \"default\", filter: Const(Int(1)), order_by: [], limit: LimitClause { limit: 100, offset: 0 } })");
+            "Ok(Query { select: [ColumnInfo { expr: ColName(\"trip_id\"), name: \"id\" }], distinct: false, table: \"default\", subqueries: [], filter: Const(Int(1)), group_by: [], having: None, order_by: [], limit: LimitClause { limit: 100, offset: 0 }, downsample: None, windows: [], join: None })");
     }
 
     #[test]
     fn test_to_year() {
         assert_eq!(
             format!("{:?}", parse_query("select to_year(ts) from default limit 100")),
-            "Ok(Query { select: [ColumnInfo { expr: Func1(ToYear, ColName(\"ts\")), name: \"to_year(ts)\" }], table: \"default\", filter: Const(Int(1)), order_by: [], limit: LimitClause { limit: 100, offset: 0 } })");
+            "Ok(Query { select: [ColumnInfo { expr: Func1(ToYear, ColName(\"ts\")), name: \"to_year(ts)\" }], distinct: false, table: \"default\", subqueries: [], filter: Const(Int(1)), group_by: [], having: None, order_by: [], limit: LimitClause { limit: 100, offset: 0 }, downsample: None, windows: [], join: None })");
+    }
+
+    #[test]
+    fn test_date_trunc() {
+        assert_eq!(
+            format!("{:?}", parse_query("select date_trunc('Hour', ts) from default limit 100")),
+            "Ok(Query { select: [ColumnInfo { expr: Func1(DateTrunc(Hour), ColName(\"ts\")), name: \"date_trunc('Hour', ts)\" }], distinct: false, table: \"default\", subqueries: [], filter: Const(Int(1)), group_by: [], having: None, order_by: [], limit: LimitClause { limit: 100, offset: 0 }, downsample: None, windows: [], join: None })");
+        assert!(parse_query("select date_trunc('fortnight', ts) from default").is_err());
+    }
+
+    #[test]
+    fn test_time_bucket() {
+        assert_eq!(
+            format!("{:?}", parse_query("select time_bucket('5 minutes', ts), lttb(ts, value, 500) from default")),
+            "Ok(Query { select: [ColumnInfo { expr: Func1(TimeBucket(Seconds(300)), ColName(\"ts\")), name: \"time_bucket('5 minutes', ts)\" }, ColumnInfo { expr: ColName(\"value\"), name: \"lttb(ts, value, 500)\" }], distinct: false, table: \"default\", subqueries: [], filter: Const(Int(1)), group_by: [], having: None, order_by: [], limit: LimitClause { limit: 18446744073709551615, offset: 0 }, downsample: Some(Downsample { method: Lttb, x: ColName(\"ts\"), column: 1, max_points: 500 }), windows: [], join: None })");
+        assert_eq!(parse_interval("1h"), Some(3600));
+        assert_eq!(parse_interval("2 days"), Some(172800));
+        assert_eq!(parse_interval("5 fortnights"), None);
+        assert!(parse_query("select sum(lttb(ts, value, 500)) from default").is_err());
+    }
+
+    #[test]
+    fn test_string_functions() {
+        assert_eq!(
+            format!("{:?}", parse_query("select a || '-' || b, substr(c, 2), trim(leading d) from default")),
+            "Ok(Query { select: [ColumnInfo { expr: FuncN(Concat, [ColName(\"a\"), Const(Str(\"-\")), ColName(\"b\")]), name: \"a || '-' || b\" }, ColumnInfo { expr: FuncN(Substr, [ColName(\"c\"), Const(Int(2))]), name: \"SUBSTR(c, 2)\" }, ColumnInfo { expr: Func1(TrimStart, ColName(\"d\")), name: \"TRIM(LEADING d)\" }], distinct: false, table: \"default\", subqueries: [], filter: Const(Int(1)), group_by: [], having: None, order_by: [], limit: LimitClause { limit: 18446744073709551615, offset: 0 }, downsample: None, windows: [], join: None })");
+        assert!(parse_query("select split_part(a, '.') from default").is_err());
+    }
+
+    #[test]
+    fn test_math_functions() {
+        assert_eq!(
+            format!("{:?}", parse_query("select round(a, -2), ceil(b), greatest(a, b, 3), power(a, 2) from default")),
+            "Ok(Query { select: [ColumnInfo { expr: Func1(Round(-2), ColName(\"a\")), name: \"round(a, -2)\" }, ColumnInfo { expr: Func1(Ceil, ColName(\"b\")), name: \"CEIL(b)\" }, ColumnInfo { expr: Func2(Greatest, Func2(Greatest, ColName(\"a\"), ColName(\"b\")), Const(Int(3))), name: \"greatest(a, b, 3)\" }, ColumnInfo { expr: Func2(Pow, ColName(\"a\"), Const(Int(2))), name: \"power(a, 2)\" }], distinct: false, table: \"default\", subqueries: [], filter: Const(Int(1)), group_by: [], having: None, order_by: [], limit: LimitClause { limit: 18446744073709551615, offset: 0 }, downsample: None, windows: [], join: None })");
+        assert!(parse_query("select round(a, b) from default").is_err());
+        assert!(parse_query("select pow(a) from default").is_err());
+    }
+
+    #[test]
+    fn test_conditionals() {
+        assert_eq!(
+            format!("{:?}", parse_query("select case when a > 1 then 'x' when b then 'y' end, case a when 1 then 2 else 3 end, nullif(a, 0), coalesce(a, b, 0) from default")),
+            "Ok(Query { select: [ColumnInfo { expr: FuncN(If, [Func2(GT, ColName(\"a\"), Const(Int(1))), Const(Str(\"x\")), FuncN(If, [ColName(\"b\"), Const(Str(\"y\")), Const(Null)])]), name: \"CASE WHEN a > 1 THEN 'x' WHEN b THEN 'y' END\" }, ColumnInfo { expr: FuncN(If, [Func2(Equals, ColName(\"a\"), Const(Int(1))), Const(Int(2)), Const(Int(3))]), name: \"CASE a WHEN 1 THEN 2 ELSE 3 END\" }, ColumnInfo { expr: FuncN(If, [Func2(Equals, ColName(\"a\"), Const(Int(0))), Const(Null), ColName(\"a\")]), name: \"nullif(a, 0)\" }, ColumnInfo { expr: FuncN(Coalesce, [ColName(\"a\"), ColName(\"b\"), Const(Int(0))]), name: \"coalesce(a, b, 0)\" }], distinct: false, table: \"default\", subqueries: [], filter: Const(Int(1)), group_by: [], having: None, order_by: [], limit: LimitClause { limit: 18446744073709551615, offset: 0 }, downsample: None, windows: [], join: None })");
+        assert!(parse_query("select if(a, b) from default").is_err());
+    }
+
+    #[test]
+    fn test_in_between() {
+        assert_eq!(
+            format!("{:?}", parse_query("select a from default where a not in ('x', 'y') and b between 1 and 3")),
+            "Ok(Query { select: [ColumnInfo { expr: ColName(\"a\"), name: \"a\" }], distinct: false, table: \"default\", subqueries: [], filter: Func2(And, Func1(Not, FuncN(In, [ColName(\"a\"), Const(Str(\"x\")), Const(Str(\"y\"))])), Func2(And, Func2(GTE, ColName(\"b\"), Const(Int(1))), Func2(LTE, ColName(\"b\"), Const(Int(3))))), group_by: [], having: None, order_by: [], limit: LimitClause { limit: 18446744073709551615, offset: 0 }, downsample: None, windows: [], join: None })");
+    }
+
+    #[test]
+    fn test_cast() {
+        assert_eq!(
+            format!("{:?}", parse_query("select cast(a as float), b::text, try_cast(c as bigint) from default")),
+            "Ok(Query { select: [ColumnInfo { expr: Func1(Cast(Float), ColName(\"a\")), name: \"CAST(a AS FLOAT)\" }, ColumnInfo { expr: Func1(Cast(String), ColName(\"b\")), name: \"b::TEXT\" }, ColumnInfo { expr: Func1(TryCast(Integer), ColName(\"c\")), name: \"TRY_CAST(c AS BIGINT)\" }], distinct: false, table: \"default\", subqueries: [], filter: Const(Int(1)), group_by: [], having: None, order_by: [], limit: LimitClause { limit: 18446744073709551615, offset: 0 }, downsample: None, windows: [], join: None })");
+        assert!(parse_query("select cast(a as date) from default").is_err());
+    }
+
+    #[test]
+    fn test_join() {
+        assert_eq!(
+            format!("{:?}", parse_query("select e.a, r.b from events e left join runs r on e.run_id = r.id")),
+            "Ok(Query { select: [ColumnInfo { expr: ColName(\"a\"), name: \"e.a\" }, ColumnInfo { expr: ColName(\"r.b\"), name: \"r.b\" }], distinct: false, table: \"events\", subqueries: [], filter: Const(Int(1)), group_by: [], having: None, order_by: [], limit: LimitClause { limit: 18446744073709551615, offset: 0 }, downsample: None, windows: [], join: Some(Join { kind: Left, table: \"runs\", alias: \"r\", left_key: ColName(\"run_id\"), right_key: \"id\", materialized: None }) })");
+        assert_eq!(
+            format!("{:?}", parse_query("select name from events join runs using (run_id)")),
+            "Ok(Query { select: [ColumnInfo { expr: ColName(\"name\"), name: \"name\" }], distinct: false, table: \"events\", subqueries: [], filter: Const(Int(1)), group_by: [], having: None, order_by: [], limit: LimitClause { limit: 18446744073709551615, offset: 0 }, downsample: None, windows: [], join: Some(Join { kind: Inner, table: \"runs\", alias: \"runs\", left_key: ColName(\"run_id\"), right_key: \"run_id\", materialized: None }) })");
+        assert!(parse_query("select a from events e right join runs r on e.run_id = r.id").is_err());
+        assert!(parse_query("select a from events e join runs r on e.run_id > r.id").is_err());
+        assert!(parse_query("select a from events join events on a = b").is_err());
+    }
+
+    #[test]
+    fn test_subquery() {
+        assert_eq!(
+            format!("{:?}", parse_query("select max(m) from (select a, max(b) as m from default group by a) t")),
+            "Ok(Query { select: [ColumnInfo { expr: Aggregate(MaxI64, ColName(\"m\")), name: \"max(m)\" }], distinct: false, table: \"t\", subqueries: [Query { select: [ColumnInfo { expr: ColName(\"a\"), name: \"a\" }, ColumnInfo { expr: Aggregate(MaxI64, ColName(\"b\")), name: \"m\" }], distinct: false, table: \"default\", subqueries: [], filter: Const(Int(1)), group_by: [ColName(\"a\")], having: None, order_by: [], limit: LimitClause { limit: 18446744073709551615, offset: 0 }, downsample: None, windows: [], join: None }], filter: Const(Int(1)), group_by: [], having: None, order_by: [], limit: LimitClause { limit: 18446744073709551615, offset: 0 }, downsample: None, windows: [], join: None })");
+        assert_eq!(
+            format!("{:?}", parse_query("with t as (select a from default) select a from t")),
+            "Ok(Query { select: [ColumnInfo { expr: ColName(\"a\"), name: \"a\" }], distinct: false, table: \"t\", subqueries: [Query { select: [ColumnInfo { expr: ColName(\"a\"), name: \"a\" }], distinct: false, table: \"default\", subqueries: [], filter: Const(Int(1)), group_by: [], having: None, order_by: [], limit: LimitClause { limit: 18446744073709551615, offset: 0 }, downsample: None, windows: [], join: None }], filter: Const(Int(1)), group_by: [], having: None, order_by: [], limit: LimitClause { limit: 18446744073709551615, offset: 0 }, downsample: None, windows: [], join: None })");
+        assert!(parse_query("with recursive t as (select a from default) select a from t").is_err());
+        assert!(parse_query("with t as (select a from default) select a from default join t using (a)").is_err());
+    }
+
+    #[test]
+    fn test_union_all() {
+        assert_eq!(
+            format!("{:?}", parse_query("select a from t1 union all select b from t2 order by a limit 5")),
+            "Ok(Query { select: [ColumnInfo { expr: ColName(\"a\"), name: \"a\" }], distinct: false, table: \"_union\", subqueries: [Query { select: [ColumnInfo { expr: ColName(\"a\"), name: \"a\" }], distinct: false, table: \"t1\", subqueries: [], filter: Const(Int(1)), group_by: [], having: None, order_by: [], limit: LimitClause { limit: 18446744073709551615, offset: 0 }, downsample: None, windows: [], join: None }, Query { select: [ColumnInfo { expr: ColName(\"b\"), name: \"b\" }], distinct: false, table: \"t2\", subqueries: [], filter: Const(Int(1)), group_by: [], having: None, order_by: [], limit: LimitClause { limit: 18446744073709551615, offset: 0 }, downsample: None, windows: [], join: None }], filter: Const(Int(1)), group_by: [], having: None, order_by: [(ColName(\"a\"), false)], limit: LimitClause { limit: 5, offset: 0 }, downsample: None, windows: [], join: None })");
+        assert!(parse_query("select a from t1 union select b from t2").is_err());
+        assert!(parse_query("select a from t1 except select b from t2").is_err());
+    }
+
+    #[test]
+    fn test_group_by_having() {
+        assert_eq!(
+            format!("{:?}", parse_query("select a, count(*) from default group by a, b having count(*) > 10")),
+            "Ok(Query { select: [ColumnInfo { expr: ColName(\"a\"), name: \"a\" }, ColumnInfo { expr: Aggregate(Count, Const(Int(1))), name: \"count(*)\" }], distinct: false, table: \"default\", subqueries: [], filter: Const(Int(1)), group_by: [ColName(\"a\"), ColName(\"b\")], having: Some(Func2(GT, Aggregate(Count, Const(Int(1))), Const(Int(10)))), order_by: [], limit: LimitClause { limit: 18446744073709551615, offset: 0 }, downsample: None, windows: [], join: None })");
+    }
+
+    #[test]
+    fn test_distinct() {
+        assert_eq!(
+            format!("{:?}", parse_query("select distinct a, count(distinct b) from default")),
+            "Ok(Query { select: [ColumnInfo { expr: ColName(\"a\"), name: \"a\" }, ColumnInfo { expr: Aggregate(CountDistinct, ColName(\"b\")), name: \"count(DISTINCT b)\" }], distinct: true, table: \"default\", subqueries: [], filter: Const(Int(1)), group_by: [], having: None, order_by: [], limit: LimitClause { limit: 18446744073709551615, offset: 0 }, downsample: None, windows: [], join: None })");
+    }
+
+    #[test]
+    fn test_percentile_cont() {
+        assert_eq!(
+            format!("{:?}", parse_query("select percentile_cont(0.75) within group (order by x desc) from default")),
+            "Ok(Query { select: [ColumnInfo { expr: Aggregate(PercentileCont(0.25), ColName(\"x\")), name: \"percentile_cont(0.75) WITHIN GROUP (ORDER BY x DESC)\" }], distinct: false, table: \"default\", subqueries: [], filter: Const(Int(1)), group_by: [], having: None, order_by: [], limit: LimitClause { limit: 18446744073709551615, offset: 0 }, downsample: None, windows: [], join: None })");
+    }
+
+    #[test]
+    fn test_mutations() {
+        assert_eq!(
+            format!("{:?}", parse_statement("drop table if exists \"runs/1\"")),
+            "Ok(Mutation(DropTable { table: \"runs/1\", if_exists: true }))");
+        assert_eq!(
+            format!("{:?}", parse_statement("truncate table default")),
+            "Ok(Mutation(Truncate { table: \"default\" }))");
+        assert_eq!(
+            format!("{:?}", parse_statement("delete from default where a > 10")),
+            "Ok(Mutation(Delete { table: \"default\", filter: Func2(GT, ColName(\"a\"), Const(Int(10))) }))");
+        assert!(parse_statement("drop table t1, t2").is_err());
+        assert!(parse_statement("delete from default where a > 10 limit 5").is_err());
+        assert!(parse_statement("insert into default values (1)").is_err());
+        assert!(parse_query("delete from default").is_err());
     }
 }
@@ -490,3 +490,242 @@ async fn test_many_concurrent_requests() {
     log::info!("Stopping server");
     _handle.stop(true).await;
 }
+
+#[test]
+fn test_persist_logical_types() {
+    let _ = env_logger::try_init();
+    let db_path: PathBuf = tempdir().unwrap().path().into();
+    let opts = locustdb::Options {
+        db_path: Some(db_path),
+        threads: 1,
+        ..locustdb::Options::default()
+    };
+    let db = LocustDB::new(&opts);
+    futures::executor::block_on(
+        db.load_csv(
+            locustdb::LoadOptions::new("test_data/typed.csv", "typed")
+                .with_schema("int,bool,ts")
+                .allow_nulls_all_columns(),
+        ),
+    )
+    .unwrap();
+    db.force_flush();
+    drop(db);
+
+    let db = LocustDB::new(&opts);
+    let query = "SELECT flag, time FROM typed WHERE id = 3";
+    let result = futures::executor::block_on(db.run_query(query, false, true, vec![])).unwrap();
+    assert_eq!(
+        result.rows.unwrap(),
+        [[
+            Bool(true),
+            Timestamp(1707955200, locustdb::TimeUnit::Second)
+        ]],
+    );
+}
+
+#[test]
+fn test_load_malformed_logical_types() {
+    let _ = env_logger::try_init();
+    let dir = tempdir().unwrap();
+    let db = LocustDB::new(&locustdb::Options::default());
+    for (contents, error) in [
+        (
+            "1,true,2024-01-01\n2,maybe,2024-01-02\n",
+            "\"maybe\" as boolean (column flag, row 1)",
+        ),
+        (
+            "1,true,2024-01-01\n2,false,yesterday\n",
+            "\"yesterday\" as timestamp (column time, row 1)",
+        ),
+        (
+            "1,true,2024-01-01\n2,false,\n",
+            "\"\" as timestamp (column time, row 1)",
+        ),
+    ] {
+        let path = dir.path().join("malformed.csv");
+        std::fs::write(&path, contents).unwrap();
+        let result = futures::executor::block_on(db.load_csv(
+            locustdb::LoadOptions::new(&path, "malformed").with_schema("id:int,flag:bool,time:ts"),
+        ));
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains(error), "{}", message);
+    }
+}
+
+#[test]
+fn test_persist_column_ranges() {
+    let _ = env_logger::try_init();
+    let db_path: PathBuf = tempdir().unwrap().path().into();
+    let opts = locustdb::Options {
+        db_path: Some(db_path),
+        threads: 1,
+        partition_combine_factor: 999,
+        ..locustdb::Options::default()
+    };
+    let db = LocustDB::new(&opts);
+    futures::executor::block_on(
+        db.load_csv(locustdb::LoadOptions::new("test_data/tiny.csv", "default").with_partition_size(40)),
+    )
+    .unwrap();
+    db.force_flush();
+    drop(db);
+
+    let db = LocustDB::new(&opts);
+    let query = "SELECT COUNT(0) FROM default WHERE ts > 1486388008";
+    let result = futures::executor::block_on(db.run_query(query, false, true, vec![])).unwrap();
+    assert_eq!(result.rows.unwrap(), [[Int(3)]]);
+    assert_eq!(result.stats.rows_scanned, 40);
+    assert_eq!(result.stats.files_opened, 1);
+}
+
+#[test]
+fn test_persist_retention_policy() {
+    let _ = env_logger::try_init();
+    let db_path: PathBuf = tempdir().unwrap().path().into();
+    let opts = locustdb::Options {
+        db_path: Some(db_path.clone()),
+        threads: 1,
+        partition_combine_factor: 999,
+        ..locustdb::Options::default()
+    };
+    let db = LocustDB::new(&opts);
+    futures::executor::block_on(
+        db.load_csv(locustdb::LoadOptions::new("test_data/tiny.csv", "default").with_partition_size(40)),
+    )
+    .unwrap();
+    db.force_flush();
+    let table_files = || std::fs::read_dir(db_path.join("tables").join("default")).unwrap().count();
+    let files_before = table_files();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let policy = locustdb::RetentionPolicy {
+        max_age: Some(locustdb::MaxAge {
+            timestamp_column: "ts".to_string(),
+            unit: locustdb::TimeUnit::Second,
+            max_age: Duration::from_secs(now - 1487000000),
+        }),
+        max_size_bytes: Some(1 << 30),
+    };
+    db.set_retention_policy("default", Some(policy.clone()));
+    drop(db);
+
+    // Only the first partition is older than the cutoff
+    let db = LocustDB::new(&opts);
+    assert_eq!(db.retention_policies(), HashMap::from([("default".to_string(), policy)]));
+    assert_eq!(db.apply_retention_policies(), 1);
+    assert!(table_files() < files_before);
+    drop(db);
+
+    let db = LocustDB::new(&opts);
+    let query = "SELECT COUNT(0) FROM default";
+    let result = futures::executor::block_on(db.run_query(query, false, true, vec![])).unwrap();
+    assert_eq!(result.rows.unwrap(), [[Int(60)]]);
+}
+
+#[test]
+fn test_persist_delete() {
+    let _ = env_logger::try_init();
+    let db_path: PathBuf = tempdir().unwrap().path().into();
+    let opts = locustdb::Options {
+        db_path: Some(db_path.clone()),
+        threads: 1,
+        partition_combine_factor: 999,
+        ..locustdb::Options::default()
+    };
+    let db = LocustDB::new(&opts);
+    futures::executor::block_on(
+        db.load_csv(locustdb::LoadOptions::new("test_data/tiny.csv", "default").with_partition_size(40)),
+    )
+    .unwrap();
+    db.force_flush();
+    let run = |db: &LocustDB, query: &str| {
+        futures::executor::block_on(db.run_query(query, false, true, vec![])).unwrap().rows.unwrap()
+    };
+    assert_eq!(run(&db, "DELETE FROM default WHERE num > 1"), [[Int(43)]]);
+    drop(db);
+
+    let db = LocustDB::new(&opts);
+    assert_eq!(run(&db, "SELECT COUNT(0), SUM(num) FROM default"), [[Int(57), Int(49)]]);
+    assert_eq!(run(&db, "DROP TABLE default"), [[Int(57)]]);
+    drop(db);
+
+    let db = LocustDB::new(&opts);
+    futures::executor::block_on(db.run_query("SELECT COUNT(0) FROM default", false, true, vec![])).unwrap_err();
+    assert!(std::fs::read_dir(db_path.join("tables").join("default")).map_or(true, |files| files.count() == 0));
+}
+
+/// Stores blobs in memory, keyed by path
+#[derive(Default)]
+struct MemoryBlobWriter {
+    blobs: std::sync::Mutex<std::collections::BTreeMap<PathBuf, Vec<u8>>>,
+}
+
+type BlobResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync + 'static>>;
+
+impl locustdb::BlobWriter for MemoryBlobWriter {
+    fn store(&self, path: &std::path::Path, data: &[u8]) -> BlobResult<()> {
+        self.blobs.lock().unwrap().insert(path.to_owned(), data.to_vec());
+        Ok(())
+    }
+
+    fn load(&self, path: &std::path::Path) -> BlobResult<Vec<u8>> {
+        let blobs = self.blobs.lock().unwrap();
+        Ok(blobs.get(path).ok_or_else(|| format!("{:?} not found", path))?.clone())
+    }
+
+    fn delete(&self, path: &std::path::Path) -> BlobResult<()> {
+        self.blobs.lock().unwrap().remove(path);
+        Ok(())
+    }
+
+    fn list(&self, path: &std::path::Path) -> BlobResult<Vec<PathBuf>> {
+        let blobs = self.blobs.lock().unwrap();
+        Ok(blobs.keys().filter(|p| p.parent() == Some(path)).cloned().collect())
+    }
+
+    fn exists(&self, path: &std::path::Path) -> BlobResult<bool> {
+        Ok(self.blobs.lock().unwrap().contains_key(path))
+    }
+}
+
+#[test]
+fn test_custom_blob_writer() {
+    let _ = env_logger::try_init();
+    let blob_writer = Arc::new(MemoryBlobWriter::default());
+    let opts = locustdb::Options {
+        db_path: Some(PathBuf::from("memory-db")),
+        blob_writer: Some(blob_writer.clone()),
+        threads: 1,
+        ..locustdb::Options::default()
+    };
+    let db = LocustDB::new(&opts);
+    futures::executor::block_on(
+        db.load_csv(locustdb::LoadOptions::new("test_data/tiny.csv", "default").with_partition_size(40)),
+    )
+    .unwrap();
+    db.force_flush();
+    drop(db);
+
+    assert!(!PathBuf::from("memory-db").exists());
+    let blobs = blob_writer.blobs.lock().unwrap().keys().cloned().collect::<Vec<_>>();
+    assert!(blobs.contains(&PathBuf::from("memory-db/meta")));
+    assert!(blobs.iter().any(|path| path.starts_with("memory-db/tables/default")));
+
+    let db = LocustDB::new(&opts);
+    let query = "SELECT COUNT(0), SUM(num) FROM default";
+    let result = futures::executor::block_on(db.run_query(query, false, true, vec![])).unwrap();
+    assert_eq!(result.rows.unwrap(), [[Int(100), Int(168)]]);
+}
+
+#[test]
+#[should_panic(expected = "blob_writer requires db_path")]
+fn test_custom_blob_writer_without_db_path() {
+    let opts = locustdb::Options {
+        blob_writer: Some(Arc::new(MemoryBlobWriter::default())),
+        ..locustdb::Options::default()
+    };
+    LocustDB::new(&opts);
+}
@@ -29,6 +29,11 @@ fn test_query(query: &str, expected_rows: &[Vec<Value>]) {
 }
 
 fn test_query_ec(query: &str, expected_rows: &[Vec<Value>]) {
+    test_query_ec_with_tables(query, expected_rows, &[])
+}
+
+// Also loads each `(path, table)` in `tables`
+fn test_query_ec_with_tables(query: &str, expected_rows: &[Vec<Value>], tables: &[(&str, &str)]) {
     let _ = env_logger::try_init();
     let optss = [
         Options::default(),
@@ -61,6 +66,10 @@ fn test_query_ec(query: &str, expected_rows: &[Vec<Value>]) {
                     .allow_nulls_all_columns(),
             ),
         );
+        for &(path, table) in tables {
+            block_on(locustdb.load_csv(LoadOptions::new(path, table).allow_nulls_all_columns()))
+                .unwrap();
+        }
 
         let show = if env::var("DEBUG_TESTS").is_ok() {
             vec![0, 1, 2, 3]
@@ -132,6 +141,37 @@ fn test_query_nyc(query: &str, expected_rows: &[Vec<Value>]) {
     );
 }
 
+fn test_query_typed(query: &str, expected_rows: &[Vec<Value>]) {
+    let _ = env_logger::try_init();
+    for max_partition_length in [2, 1024] {
+        let mut opts = Options {
+            max_partition_length,
+            ..Options::default()
+        };
+        if env::var("DEBUG_TESTS").is_ok() {
+            opts.threads = 1;
+        }
+        let locustdb = LocustDB::new(&opts);
+        block_on(
+            locustdb.load_csv(
+                LoadOptions::new("test_data/typed.csv", "default")
+                    .with_schema("int,bool,ts")
+                    .with_partition_size(max_partition_length)
+                    .allow_nulls_all_columns(),
+            ),
+        )
+        .unwrap();
+        let result1 = block_on(locustdb.run_query(query, false, true, vec![])).unwrap();
+        locustdb.force_flush();
+        let result2 = block_on(locustdb.run_query(query, false, true, vec![])).unwrap();
+        assert_eq!(
+            result1.rows, result2.rows,
+            "Query results differ after flush"
+        );
+        assert_eq!(result1.rows.unwrap(), expected_rows);
+    }
+}
+
 fn test_query_colnames(query: &str, expected_result: Vec<String>) {
     let _ = env_logger::try_init();
     #[allow(unused_mut)]
@@ -1947,6 +1987,178 @@ fn test_floor3() {
     );
 }
 
+#[test]
+fn test_math_functions() {
+    test_query_ec(
+        "SELECT abs(negative), sign(negative), round(negative, -2), abs(float), sqrt(id * id) FROM default ORDER BY id LIMIT 4",
+        &[
+            vec![Int(199), Int(-1), Int(-200), Float(0.123412), Float(0.0)],
+            vec![Int(39), Int(1), Int(0), Float(0.0003), Float(1.0)],
+            vec![Int(100), Int(-1), Int(-100), Float(124.0), Float(2.0)],
+            vec![Int(34), Int(1), Int(0), Float(3.15159), Float(3.0)],
+        ],
+    );
+    test_query_ec(
+        "SELECT ceil(float), round(float), round(float, 2), log10(pow(10, id)), exp(id * 0) FROM default ORDER BY id LIMIT 4",
+        &[
+            vec![Int(1), Int(0), Float(0.12), Float(0.0), Float(1.0)],
+            vec![Int(1), Int(0), Float(0.0), Float(1.0), Float(1.0)],
+            vec![Int(-124), Int(-124), Float(-124.0), Float(2.0), Float(1.0)],
+            vec![Int(4), Int(3), Float(3.15), Float(3.0), Float(1.0)],
+        ],
+    );
+    test_query_ec(
+        "SELECT id + abs(-3), id + round(2.5), id * greatest(1, 2.5) FROM default ORDER BY id LIMIT 2",
+        &[vec![Int(3), Int(3), Float(0.0)], vec![Int(4), Int(4), Float(2.5)]],
+    );
+}
+
+#[test]
+fn test_math_functions_nullable() {
+    test_query_ec(
+        "SELECT abs(nullable_int), greatest(nullable_int, nullable_int2), least(id, float), greatest(id, 2, 1) FROM default ORDER BY id LIMIT 4",
+        &[
+            vec![Int(1), Null, Float(0.0), Int(2)],
+            vec![Int(40), Int(-40), Float(0.0003), Int(2)],
+            vec![Null, Null, Float(-124.0), Int(2)],
+            vec![Null, Null, Float(3.0), Int(3)],
+        ],
+    );
+}
+
+#[test]
+fn test_conditionals() {
+    test_query_ec(
+        "SELECT CASE WHEN negative > 0 THEN 'pos' ELSE 'neg' END, coalesce(nullable_int, nullable_int2, 0), nullif(enum, 'aa'), if(id > 1, float, id) FROM default ORDER BY id LIMIT 4",
+        &[
+            vec![Str("neg"), Int(-1), Null, Float(0.0)],
+            vec![Str("pos"), Int(-40), Null, Float(1.0)],
+            vec![Str("neg"), Int(0), Null, Float(-124.0)],
+            vec![Str("pos"), Int(0), Str("bb"), Float(3.15159)],
+        ],
+    );
+    test_query_ec(
+        "SELECT CASE country WHEN 'USA' THEN 1 WHEN 'Germany' THEN 2 END, CASE WHEN nullable_int < -10 THEN 'low' ELSE 'high' END FROM default ORDER BY id LIMIT 4",
+        &[
+            vec![Int(2), Str("high")],
+            vec![Int(1), Str("low")],
+            vec![Null, Str("high")],
+            vec![Null, Str("high")],
+        ],
+    );
+}
+
+#[test]
+fn test_conditionals_filter() {
+    test_query_ec(
+        "SELECT id FROM default WHERE coalesce(nullable_int2, 1) = 0",
+        &[vec![Int(3)]],
+    );
+    test_query_ec(
+        "SELECT id FROM default WHERE if(id < 5, negative > 0, nullable_int > 10) ORDER BY id",
+        &[vec![Int(1)], vec![Int(3)], vec![Int(4)], vec![Int(7)], vec![Int(9)]],
+    );
+}
+
+#[test]
+fn test_in_list() {
+    test_query_ec(
+        "SELECT id FROM default WHERE enum IN ('aa', 'cc') ORDER BY id",
+        &[vec![Int(0)], vec![Int(1)], vec![Int(2)], vec![Int(5)], vec![Int(6)], vec![Int(7)], vec![Int(8)]],
+    );
+    test_query_ec(
+        "SELECT id FROM default WHERE string_packed NOT IN ('xyz', 'abc', 't') ORDER BY id",
+        &[vec![Int(2)], vec![Int(3)], vec![Int(4)], vec![Int(5)], vec![Int(6)], vec![Int(7)], vec![Int(9)]],
+    );
+    test_query_ec(
+        "SELECT id FROM default WHERE u8_offset_encoded IN (256, 500, 511) ORDER BY id",
+        &[vec![Int(0)], vec![Int(5)], vec![Int(8)], vec![Int(9)]],
+    );
+    test_query_ec(
+        "SELECT id FROM default WHERE id IN (3, 1, 2) ORDER BY id",
+        &[vec![Int(1)], vec![Int(2)], vec![Int(3)]],
+    );
+    test_query_ec(
+        "SELECT id FROM default WHERE float IN (3.15159, -124.0) ORDER BY id",
+        &[vec![Int(2)], vec![Int(3)]],
+    );
+}
+
+#[test]
+fn test_in_list_nullable() {
+    test_query_ec(
+        "SELECT id FROM default WHERE nullable_int IN (-1, 13, 20) ORDER BY id",
+        &[vec![Int(0)], vec![Int(7)], vec![Int(9)]],
+    );
+    test_query_ec(
+        "SELECT id FROM default WHERE nullable_int NOT IN (-1, 13) ORDER BY id",
+        &[vec![Int(1)], vec![Int(4)], vec![Int(7)]],
+    );
+    test_query_ec(
+        "SELECT id FROM default WHERE country IN ('USA', 'Turkey') ORDER BY id",
+        &[vec![Int(1)], vec![Int(6)]],
+    );
+    test_query_ec(
+        "SELECT id FROM default WHERE country NOT IN ('Germany') ORDER BY id",
+        &[vec![Int(1)], vec![Int(2)], vec![Int(4)], vec![Int(6)]],
+    );
+}
+
+#[test]
+fn test_between() {
+    test_query_ec(
+        "SELECT id FROM default WHERE negative BETWEEN -130 AND 39 ORDER BY id",
+        &[vec![Int(1)], vec![Int(2)], vec![Int(3)], vec![Int(5)], vec![Int(6)], vec![Int(7)], vec![Int(9)]],
+    );
+    test_query_ec(
+        "SELECT id FROM default WHERE negative NOT BETWEEN -130 AND 39 ORDER BY id",
+        &[vec![Int(0)], vec![Int(4)], vec![Int(8)]],
+    );
+}
+
+#[test]
+fn test_cast() {
+    test_query_ec(
+        "SELECT CAST(id AS FLOAT), TRY_CAST(float AS INT), CAST(negative AS STRING), nullable_int::varchar FROM default ORDER BY id LIMIT 3",
+        &[
+            vec![Float(0.0), Int(0), Str("-199"), Str("-1")],
+            vec![Float(1.0), Int(0), Str("39"), Str("-40")],
+            vec![Float(2.0), Int(-124), Str("-100"), Null],
+        ],
+    );
+    test_query_ec(
+        "SELECT id, CAST(mixed_float_int_null AS FLOAT), CAST(mixed_float_int_null AS INTEGER) FROM default ORDER BY id LIMIT 6",
+        &[
+            vec![Int(0), Float(1.0), Int(1)],
+            vec![Int(1), Float(10.0), Int(10)],
+            vec![Int(2), Float(3.0), Int(3)],
+            vec![Int(3), Float(0.21), Int(0)],
+            vec![Int(4), Float(0.12), Int(0)],
+            vec![Int(5), Null, Null],
+        ],
+    );
+    test_query_ec(
+        "SELECT id FROM default WHERE id = CAST('3' AS INT) OR float > CAST('1e6' AS FLOAT) ORDER BY id",
+        &[vec![Int(3)], vec![Int(4)], vec![Int(9)]],
+    );
+}
+
+#[test]
+fn test_cast_strings() {
+    test_query_ec(
+        "SELECT id FROM default WHERE CAST(CAST(u8_offset_encoded AS TEXT) AS BIGINT) = 500 ORDER BY id",
+        &[vec![Int(5)], vec![Int(9)]],
+    );
+    test_query_ec(
+        "SELECT id, TRY_CAST(string_packed AS INT) FROM default WHERE id < 2 ORDER BY id",
+        &[vec![Int(0), Null], vec![Int(1), Null]],
+    );
+    test_query_ec_err(
+        "SELECT CAST(string_packed AS INT) FROM default",
+        QueryError::TypeError("Cannot cast \"xyz\" to Integer".to_string()),
+    );
+}
+
 #[test]
 fn test_negative_constant() {
     test_query_ec(
@@ -2014,3 +2226,1003 @@ fn test_meta_column_names() {
         ],
     );
 }
+
+#[test]
+fn test_explicit_group_by() {
+    test_query_ec(
+        "SELECT enum, COUNT(*) FROM default GROUP BY enum;",
+        &[
+            vec![Str("aa"), Int(5)],
+            vec![Str("bb"), Int(3)],
+            vec![Str("cc"), Int(2)],
+        ],
+    );
+    test_query_ec(
+        "SELECT enum, COUNT(*) FROM default GROUP BY 1;",
+        &[
+            vec![Str("aa"), Int(5)],
+            vec![Str("bb"), Int(3)],
+            vec![Str("cc"), Int(2)],
+        ],
+    );
+    test_query_ec(
+        "SELECT enum FROM default GROUP BY enum;",
+        &[vec![Str("aa")], vec![Str("bb")], vec![Str("cc")]],
+    );
+}
+
+#[test]
+fn test_group_by_key_not_in_projection() {
+    test_query_ec(
+        "SELECT COUNT(*) FROM default GROUP BY enum ORDER BY COUNT(*);",
+        &[vec![Int(2)], vec![Int(3)], vec![Int(5)]],
+    );
+}
+
+#[test]
+fn test_group_by_expression() {
+    test_query_ec(
+        "SELECT negative / 100 + 1, COUNT(*) FROM default GROUP BY negative / 100;",
+        &[
+            vec![Int(0), Int(4)],
+            vec![Int(1), Int(4)],
+            vec![Int(41), Int(2)],
+        ],
+    );
+}
+
+#[test]
+fn test_having() {
+    test_query_ec(
+        "SELECT enum, COUNT(*) FROM default GROUP BY enum HAVING COUNT(*) > 2;",
+        &[vec![Str("aa"), Int(5)], vec![Str("bb"), Int(3)]],
+    );
+    test_query_ec(
+        "SELECT country FROM default GROUP BY country HAVING SUM(id) > 5 ORDER BY country;",
+        &[
+            vec![Str("France")],
+            vec![Str("Germany")],
+            vec![Str("Turkey")],
+            vec![Null],
+        ],
+    );
+    test_query_ec(
+        "SELECT COUNT(*) FROM default HAVING COUNT(*) > 100;",
+        &[],
+    );
+}
+
+#[test]
+fn test_group_by_ungrouped_column() {
+    test_query_ec_err(
+        "SELECT id, COUNT(*) FROM default GROUP BY enum;",
+        QueryError::TypeError(String::new()),
+    );
+    // Columns named like generated aggregate columns are not treated as aggregates
+    test_query_ec_err(
+        "SELECT enum, COUNT(*), _ca0 FROM (SELECT enum, id AS _ca0 FROM default) GROUP BY enum;",
+        QueryError::TypeError(String::new()),
+    );
+}
+
+#[test]
+fn test_select_distinct() {
+    test_query_ec(
+        "SELECT DISTINCT enum FROM default;",
+        &[vec![Str("aa")], vec![Str("bb")], vec![Str("cc")]],
+    );
+    test_query_ec(
+        "SELECT DISTINCT non_dense_ints FROM default ORDER BY non_dense_ints DESC;",
+        &[vec![Int(4)], vec![Int(3)], vec![Int(2)], vec![Int(1)], vec![Int(0)]],
+    );
+    test_query_ec(
+        "SELECT DISTINCT enum, non_dense_ints FROM default;",
+        &[
+            vec![Str("aa"), Int(0)],
+            vec![Str("aa"), Int(1)],
+            vec![Str("aa"), Int(2)],
+            vec![Str("aa"), Int(3)],
+            vec![Str("bb"), Int(1)],
+            vec![Str("bb"), Int(3)],
+            vec![Str("bb"), Int(4)],
+            vec![Str("cc"), Int(2)],
+        ],
+    );
+}
+
+#[test]
+fn test_count_distinct() {
+    test_query_ec("SELECT COUNT(DISTINCT country) FROM default;", &[vec![Int(4)]]);
+    test_query_ec("SELECT COUNT(DISTINCT nullable_int) FROM default;", &[vec![Int(5)]]);
+    test_query_ec(
+        "SELECT enum, COUNT(DISTINCT non_dense_ints), COUNT(*) FROM default;",
+        &[
+            vec![Str("aa"), Int(4), Int(5)],
+            vec![Str("bb"), Int(3), Int(3)],
+            vec![Str("cc"), Int(1), Int(2)],
+        ],
+    );
+    test_query_ec(
+        "SELECT enum, COUNT(DISTINCT country) FROM default ORDER BY COUNT(DISTINCT country) DESC, enum LIMIT 2;",
+        &[vec![Str("aa"), Int(3)], vec![Str("bb"), Int(2)]],
+    );
+}
+
+#[test]
+fn test_approx_count_distinct() {
+    test_query_ec("SELECT APPROX_COUNT_DISTINCT(country) FROM default;", &[vec![Int(4)]]);
+    test_query_ec(
+        "SELECT enum, APPROX_COUNT_DISTINCT(non_dense_ints) FROM default;",
+        &[
+            vec![Str("aa"), Int(4)],
+            vec![Str("bb"), Int(3)],
+            vec![Str("cc"), Int(1)],
+        ],
+    );
+    test_query_nyc(
+        "SELECT APPROX_COUNT_DISTINCT(pickup_ntaname), APPROX_COUNT_DISTINCT(passenger_count) FROM default;",
+        &[vec![Int(145), Int(7)]],
+    );
+}
+
+#[test]
+fn test_approx_quantile() {
+    test_query_ec(
+        "SELECT APPROX_QUANTILE(non_dense_ints, 0), APPROX_QUANTILE(non_dense_ints, 0.5), APPROX_QUANTILE(non_dense_ints, 0.9) FROM default;",
+        &[vec![Float(0.0), Float(2.0), Float(3.5)]],
+    );
+    test_query_ec(
+        "SELECT enum, APPROX_QUANTILE(non_dense_ints, 1) FROM default;",
+        &[
+            vec![Str("aa"), Float(3.0)],
+            vec![Str("bb"), Float(4.0)],
+            vec![Str("cc"), Float(2.0)],
+        ],
+    );
+    test_query_ec_err(
+        "SELECT APPROX_QUANTILE(non_dense_ints, 1.5) FROM default;",
+        QueryError::ParseError("Quantile 1.5 is not in [0, 1]".to_string()),
+    );
+    test_query_ec_err(
+        "SELECT APPROX_QUANTILE(country, 0.5) FROM default;",
+        QueryError::TypeError("APPROX_QUANTILE is not defined for strings".to_string()),
+    );
+}
+
+#[test]
+fn test_percentile_cont() {
+    test_query_ec(
+        "SELECT MEDIAN(non_dense_ints), PERCENTILE_CONT(0.75) WITHIN GROUP (ORDER BY non_dense_ints), PERCENTILE_CONT(0.75) WITHIN GROUP (ORDER BY non_dense_ints DESC) FROM default;",
+        &[vec![Float(2.0), Float(2.75), Float(1.0)]],
+    );
+    test_query_ec(
+        "SELECT enum, MEDIAN(non_dense_ints) FROM default;",
+        &[
+            vec![Str("aa"), Float(1.0)],
+            vec![Str("bb"), Float(3.0)],
+            vec![Str("cc"), Float(2.0)],
+        ],
+    );
+    test_query_ec("SELECT MEDIAN(nullable_int) FROM default;", &[vec![Float(10.0)]]);
+    test_query_ec_err(
+        "SELECT PERCENTILE_CONT(0.5) FROM default;",
+        QueryError::ParseError("Expected PERCENTILE_CONT(p) WITHIN GROUP (ORDER BY x)".to_string()),
+    );
+    test_query_ec_err(
+        "SELECT MEDIAN(country) FROM default;",
+        QueryError::TypeError("PERCENTILE_CONT is not defined for strings".to_string()),
+    );
+}
+
+#[test]
+fn test_percentile_cont_nyc() {
+    test_query_nyc(
+        "SELECT MEDIAN(total_amount), PERCENTILE_CONT(0.99) WITHIN GROUP (ORDER BY passenger_count) FROM default;",
+        &[vec![Float(1100.0), Float(6.0)]],
+    );
+}
+
+#[test]
+fn test_variance() {
+    test_query_ec(
+        "SELECT VAR_POP(id), VARIANCE(id), STDDEV_POP(id), STDDEV(id) FROM default;",
+        &[vec![Float(8.25), Float(82.5 / 9.0), Float(8.25f64.sqrt()), Float((82.5f64 / 9.0).sqrt())]],
+    );
+    test_query_ec(
+        "SELECT enum, VARIANCE(non_dense_ints) FROM default;",
+        &[
+            vec![Str("aa"), Float(1.7)],
+            vec![Str("bb"), Float(7.0 / 3.0)],
+            vec![Str("cc"), Float(0.0)],
+        ],
+    );
+    test_query_ec(
+        "SELECT VARIANCE(nullable_int), STDDEV(float01) FROM default;",
+        &[vec![Float(567.3), Float(0.4031280169685418)]],
+    );
+}
+
+#[test]
+fn test_corr() {
+    test_query_ec(
+        "SELECT CORR(id, id), CORR(id, 2 * id + 1), CORR(id, 0 - id), CORR(id, constant0) FROM default;",
+        &[vec![Float(1.0), Float(1.0), Float(-1.0), Null]],
+    );
+    test_query_ec(
+        "SELECT CORR(nullable_int, 0 - nullable_int), CORR(id, nullable_int - nullable_int + id) FROM default;",
+        &[vec![Float(-1.0), Float(1.0)]],
+    );
+    test_query_ec_err(
+        "SELECT CORR(id) FROM default;",
+        QueryError::ParseError("Expected two arguments in CORR function".to_string()),
+    );
+}
+
+#[test]
+fn test_count_distinct_nyc() {
+    test_query_nyc(
+        "SELECT COUNT(DISTINCT pickup_ntaname), COUNT(DISTINCT trip_id) FROM default;",
+        &[vec![Int(145), Int(10_000)]],
+    );
+    test_query_nyc(
+        "SELECT pickup_ntaname, COUNT(DISTINCT passenger_count) FROM default ORDER BY pickup_ntaname LIMIT 3;",
+        &[
+            vec![Str(""), Int(7)],
+            vec![Str("Airport"), Int(3)],
+            vec![Str("Allerton-Pelham Gardens"), Int(1)],
+        ],
+    );
+}
+
+#[test]
+fn test_bool_timestamp_columns() {
+    use TimeUnit::Second;
+    test_query_typed(
+        "SELECT id, flag, time FROM default ORDER BY id;",
+        &[
+            vec![Int(1), Bool(true), Timestamp(1704067200, Second)],
+            vec![Int(2), Bool(false), Timestamp(1704112200, Second)],
+            vec![Int(3), Bool(true), Timestamp(1707955200, Second)],
+            vec![Int(4), Bool(false), Timestamp(1700000000, Second)],
+            vec![Int(5), Bool(true), Timestamp(1709276400, Second)],
+            vec![Int(6), Null, Null],
+        ],
+    );
+}
+
+#[test]
+fn test_timestamp_literal_comparison() {
+    test_query_typed(
+        "SELECT id FROM default WHERE time > '2024-01-15' ORDER BY id;",
+        &[vec![Int(3)], vec![Int(5)]],
+    );
+    test_query_typed(
+        "SELECT id FROM default WHERE time = '2024-01-01T12:30:00Z';",
+        &[vec![Int(2)]],
+    );
+}
+
+#[test]
+fn test_bool_filter() {
+    test_query_typed(
+        "SELECT id FROM default WHERE flag = true ORDER BY id;",
+        &[vec![Int(1)], vec![Int(3)], vec![Int(5)]],
+    );
+}
+
+#[test]
+fn test_timestamp_max() {
+    test_query_typed(
+        "SELECT MAX(time), MIN(time) FROM default;",
+        &[vec![
+            Timestamp(1709276400, TimeUnit::Second),
+            Timestamp(1700000000, TimeUnit::Second),
+        ]],
+    );
+}
+
+#[test]
+fn test_date_parts() {
+    test_query_typed(
+        "SELECT id, to_month(time), to_day_of_week(time), to_hour(time) FROM default ORDER BY id;",
+        &[
+            vec![Int(1), Int(1), Int(1), Int(0)],
+            vec![Int(2), Int(1), Int(1), Int(12)],
+            vec![Int(3), Int(2), Int(4), Int(0)],
+            vec![Int(4), Int(11), Int(2), Int(22)],
+            vec![Int(5), Int(3), Int(5), Int(7)],
+            vec![Int(6), Null, Null, Null],
+        ],
+    );
+}
+
+#[test]
+fn test_date_trunc() {
+    use TimeUnit::Second;
+    test_query_typed(
+        "SELECT date_trunc('month', time), COUNT(0) FROM default WHERE time IS NOT NULL;",
+        &[
+            vec![Timestamp(1698796800, Second), Int(1)],
+            vec![Timestamp(1704067200, Second), Int(2)],
+            vec![Timestamp(1706745600, Second), Int(1)],
+            vec![Timestamp(1709251200, Second), Int(1)],
+        ],
+    );
+    test_query_typed(
+        "SELECT id, date_trunc('week', time), date_trunc('hour', time) FROM default WHERE id = 4;",
+        &[vec![
+            Int(4),
+            Timestamp(1699833600, Second),
+            Timestamp(1699999200, Second),
+        ]],
+    );
+}
+
+#[test]
+fn test_now() {
+    test_query_typed(
+        "SELECT COUNT(0) FROM default WHERE time < now();",
+        &[vec![Int(5)]],
+    );
+}
+
+#[test]
+fn test_now_per_statement() {
+    use std::thread;
+    use std::time::Duration;
+    let locustdb = LocustDB::new(&Options::default());
+    block_on(locustdb.load_csv(LoadOptions::new("test_data/countries.csv", "countries"))).unwrap();
+    let query = "SELECT t * 0 + now(), t FROM (SELECT calling_code * 0 + now() AS t FROM countries) LIMIT 1;";
+    let run = || {
+        let rows = block_on(locustdb.run_query(query, false, true, vec![])).unwrap().rows.unwrap();
+        assert_eq!(rows[0][0], rows[0][1]);
+        match rows[0][0] {
+            Int(time) => time,
+            ref other => panic!("expected integer, got {:?}", other),
+        }
+    };
+    let first = run();
+    thread::sleep(Duration::from_millis(1100));
+    assert!(run() > first);
+}
+
+#[test]
+fn test_from_unixtime() {
+    test_query(
+        "SELECT from_unixtime(ts), to_hour(ts) FROM default WHERE ts < '2016-09-02' AND ts >= '2016-09-01';",
+        &[vec![Timestamp(1472763607, TimeUnit::Second), Int(21)]],
+    );
+}
+
+#[test]
+fn test_time_bucket() {
+    use TimeUnit::Second;
+    test_query_typed(
+        "SELECT time_bucket('1 day', time), COUNT(0) FROM default WHERE time IS NOT NULL;",
+        &[
+            vec![Timestamp(1699920000, Second), Int(1)],
+            vec![Timestamp(1704067200, Second), Int(2)],
+            vec![Timestamp(1707955200, Second), Int(1)],
+            vec![Timestamp(1709251200, Second), Int(1)],
+        ],
+    );
+    test_query_typed(
+        "SELECT time_bucket(3600, time) FROM default WHERE id = 4;",
+        &[vec![Timestamp(1699999200, Second)]],
+    );
+}
+
+#[test]
+fn test_lttb() {
+    test_query(
+        "SELECT ts, LTTB(ts, num, 6) FROM default;",
+        &[
+            vec![Int(1456591230), Int(0)],
+            vec![Int(1462342697), Int(4)],
+            vec![Int(1463484153), Int(1)],
+            vec![Int(1471953776), Int(5)],
+            vec![Int(1483322265), Int(8)],
+            vec![Int(1487173444), Int(1)],
+        ],
+    );
+    // Limit applies after downsampling, x values don't have to be selected
+    test_query(
+        "SELECT LTTB(ts, num, 6) FROM default LIMIT 2 OFFSET 1;",
+        &[vec![Int(4)], vec![Int(1)]],
+    );
+}
+
+#[test]
+fn test_minmax_downsample() {
+    test_query(
+        "SELECT ts, MINMAX(ts, num, 6) FROM default;",
+        &[
+            vec![Int(1456591230), Int(0)],
+            vec![Int(1462842942), Int(4)],
+            vec![Int(1471953776), Int(5)],
+            vec![Int(1475053542), Int(0)],
+            vec![Int(1477592772), Int(0)],
+            vec![Int(1483322265), Int(8)],
+        ],
+    );
+}
+
+#[test]
+fn test_string_functions() {
+    test_query(
+        "SELECT lower(first_name), upper(tld), trim(concat('  ', tld, ' ')), length(concat(first_name, ' ', last_name)) FROM default WHERE ts = 1472763607;",
+        &[vec![Str("victor"), Str("MIL"), Str("mil"), Int(14)]],
+    );
+    test_query(
+        "SELECT substr(guid, 1, 8), substring(guid FROM 25), split_part(version, '.', 2), split_part(version, '.', -1) FROM default WHERE ts = 1477514391;",
+        &[vec![Str("b925766a"), Str("6381bd73a1f2"), Str("9"), Str("8")]],
+    );
+    test_query(
+        "SELECT first_name || ' ' || last_name, replace(tld, 'o', '0'), regexp_extract(guid, '^([0-9a-f]+)-', 1), starts_with(guid, '06e8'), starts_with(guid, 'f') FROM default WHERE ts = 1459120197;",
+        &[vec![Str("Nicole Garza"), Str("c0m"), Str("06e8f511"), Bool(true), Bool(false)]],
+    );
+}
+
+#[test]
+fn test_string_functions_group_by() {
+    test_query(
+        "SELECT upper(tld), COUNT(0) FROM default WHERE starts_with(first_name, 'C');",
+        &[
+            vec![Str(""), Int(2)],
+            vec![Str("BIZ"), Int(1)],
+            vec![Str("GOV"), Int(1)],
+            vec![Str("MIL"), Int(1)],
+            vec![Str("ORG"), Int(2)],
+        ],
+    );
+    test_query(
+        "SELECT concat('<', first_name, '>') FROM default ORDER BY ts LIMIT 3;",
+        &[vec![Str("<Charles>")], vec![Str("<Paula>")], vec![Str("<Cynthia>")]],
+    );
+}
+
+#[test]
+fn test_string_functions_nullable() {
+    test_query_ec(
+        "SELECT upper(country), concat(enum, '-', country), substr(string_packed, 2) FROM default ORDER BY id;",
+        &[
+            vec![Str("GERMANY"), Str("aa-Germany"), Str("yz")],
+            vec![Str("USA"), Str("aa-USA"), Str("bc")],
+            vec![Str("FRANCE"), Str("aa-France"), Str("xz")],
+            vec![Null, Null, Str("XY")],
+            vec![Str("FRANCE"), Str("bb-France"), Str("zy")],
+            vec![Null, Null, Str("sss")],
+            vec![Str("TURKEY"), Str("cc-Turkey"), Str("sd")],
+            vec![Null, Null, Str("f")],
+            vec![Null, Null, Str("")],
+            vec![Str("GERMANY"), Str("bb-Germany"), Str("")],
+        ],
+    );
+}
+
+#[test]
+fn test_join() {
+    let countries = [("test_data/countries.csv", "countries")];
+    test_query_ec_with_tables(
+        "SELECT id, c.continent FROM default d JOIN countries c ON d.country = c.country ORDER BY id;",
+        &[
+            vec![Int(0), Str("Europe")],
+            vec![Int(1), Str("North America")],
+            vec![Int(2), Str("Europe")],
+            vec![Int(4), Str("Europe")],
+            vec![Int(9), Str("Europe")],
+        ],
+        &countries,
+    );
+    test_query_ec_with_tables(
+        "SELECT id, continent, calling_code FROM default LEFT JOIN countries USING (country) ORDER BY id LIMIT 4;",
+        &[
+            vec![Int(0), Str("Europe"), Int(49)],
+            vec![Int(1), Str("North America"), Int(1)],
+            vec![Int(2), Str("Europe"), Int(33)],
+            vec![Int(3), Null, Null],
+        ],
+        &countries,
+    );
+    test_query_ec_with_tables(
+        "SELECT continent, COUNT(0) FROM default LEFT JOIN countries ON country = countries.country GROUP BY continent;",
+        &[
+            vec![Str("Europe"), Int(4)],
+            vec![Str("North America"), Int(1)],
+            vec![Null, Int(5)],
+        ],
+        &countries,
+    );
+    test_query_ec_with_tables(
+        "SELECT id FROM default JOIN countries c ON country = c.country WHERE c.calling_code > 40 AND enum = 'aa' ORDER BY id;",
+        &[vec![Int(0)]],
+        &countries,
+    );
+}
+
+#[test]
+fn test_subquery() {
+    test_query_ec(
+        "SELECT AVG(m) FROM (SELECT enum, MAX(id) AS m FROM default GROUP BY enum);",
+        &[vec![Int(8)]],
+    );
+    test_query_ec(
+        "SELECT * FROM (SELECT id, enum FROM default WHERE id < 2) t ORDER BY id;",
+        &[vec![Int(0), Str("aa")], vec![Int(1), Str("aa")]],
+    );
+    test_query_ec(
+        "SELECT COUNT(0) FROM (SELECT id FROM default WHERE id > 100);",
+        &[],
+    );
+
+    let locustdb = LocustDB::memory_only();
+    block_on(locustdb.load_csv(LoadOptions::new("test_data/edge_cases.csv", "default").allow_nulls_all_columns()))
+        .unwrap();
+    let query = "SELECT * FROM (SELECT id AS x, enum AS x FROM default);";
+    let err = block_on(locustdb.run_query(query, false, true, vec![])).unwrap_err();
+    assert!(format!("{:?}", err).contains("multiple columns named x"), "{:?}", err);
+}
+
+#[test]
+fn test_common_table_expression() {
+    test_query_ec(
+        "WITH maxima AS (SELECT enum, MAX(negative) AS m FROM default GROUP BY enum) \
+         SELECT enum, m FROM maxima WHERE m > 100 ORDER BY m DESC;",
+        &[vec![Str("bb"), Int(4031)], vec![Str("cc"), Int(4010)]],
+    );
+    test_query_ec(
+        "WITH a AS (SELECT enum, id FROM default WHERE id > 3), b AS (SELECT enum, COUNT(0) AS c FROM a GROUP BY enum) \
+         SELECT SUM(c) FROM b;",
+        &[vec![Int(6)]],
+    );
+}
+
+#[test]
+fn test_union_all() {
+    let countries = [("test_data/countries.csv", "countries")];
+    test_query_ec_with_tables(
+        "SELECT id AS x FROM default WHERE id < 2 UNION ALL SELECT calling_code FROM countries ORDER BY x;",
+        &[
+            vec![Int(0)],
+            vec![Int(1)],
+            vec![Int(1)],
+            vec![Int(33)],
+            vec![Int(49)],
+            vec![Int(81)],
+        ],
+        &countries,
+    );
+    test_query_ec_with_tables(
+        "SELECT country, COUNT(0) AS count FROM default GROUP BY country \
+         UNION ALL SELECT country, calling_code FROM countries \
+         UNION ALL (SELECT country, id * 100 FROM default ORDER BY id DESC LIMIT 1) \
+         ORDER BY count DESC LIMIT 3;",
+        &[
+            vec![Str("Germany"), Int(900)],
+            vec![Str("Japan"), Int(81)],
+            vec![Str("Germany"), Int(49)],
+        ],
+        &countries,
+    );
+}
+
+#[test]
+fn test_table_pattern() {
+    let runs = [
+        ("test_data/edge_cases.csv", "runs/a"),
+        ("test_data/countries.csv", "runs/b"),
+    ];
+    test_query_ec_with_tables(
+        "SELECT _table, COUNT(0) FROM \"runs/*\" GROUP BY _table;",
+        &[vec![Str("runs/a"), Int(10)], vec![Str("runs/b"), Int(4)]],
+        &runs,
+    );
+    test_query_ec_with_tables(
+        "SELECT _table, country FROM \"runs/?\" WHERE country = 'USA' ORDER BY _table;",
+        &[vec![Str("runs/a"), Str("USA")], vec![Str("runs/b"), Str("USA")]],
+        &runs,
+    );
+    test_query_ec_with_tables(
+        "SELECT country, continent FROM \"runs/*\" WHERE id = 1 OR calling_code = 81 ORDER BY country;",
+        &[vec![Str("Japan"), Str("Asia")], vec![Str("USA"), Null]],
+        &runs,
+    );
+    test_query_ec_with_tables(
+        "SELECT * FROM \"runs/b*\" ORDER BY calling_code LIMIT 1;",
+        &[vec![Int(1), Str("North America"), Str("USA")]],
+        &runs,
+    );
+}
+
+#[test]
+fn test_join_errors() {
+    let locustdb = LocustDB::new(&Options::default());
+    block_on(locustdb.load_csv(
+        LoadOptions::new("test_data/edge_cases.csv", "default").allow_nulls_all_columns(),
+    ))
+    .unwrap();
+    block_on(locustdb.load_csv(
+        LoadOptions::new("test_data/countries.csv", "countries").allow_nulls_all_columns(),
+    ))
+    .unwrap();
+    // Only UNION ALL is supported
+    assert!(block_on(locustdb.run_query(
+        "SELECT country FROM default UNION SELECT country FROM countries;",
+        false,
+        true,
+        vec![],
+    ))
+    .is_err());
+    assert!(block_on(locustdb.run_query(
+        "SELECT country, id FROM default UNION ALL SELECT country FROM countries;",
+        false,
+        true,
+        vec![],
+    ))
+    .is_err());
+    assert!(matches!(
+        block_on(locustdb.run_query(
+            "SELECT country FROM default UNION ALL SELECT calling_code FROM countries;",
+            false,
+            true,
+            vec![],
+        )),
+        Err(QueryError::TypeError(_))
+    ));
+    assert!(block_on(locustdb.run_query("SELECT * FROM \"runs/*\";", false, true, vec![])).is_err());
+    // `country` exists in both tables
+    assert!(block_on(locustdb.run_query(
+        "SELECT country FROM default d JOIN countries c ON d.id = c.calling_code;",
+        false,
+        true,
+        vec![],
+    ))
+    .is_err());
+    // Key of the joined table must be unique
+    assert!(block_on(locustdb.run_query(
+        "SELECT id FROM countries JOIN default d ON continent = d.enum;",
+        false,
+        true,
+        vec![],
+    ))
+    .is_err());
+}
+
+#[test]
+fn test_window_functions() {
+    test_query_ec(
+        "SELECT id, SUM(negative) OVER (ORDER BY id ROWS 1 PRECEDING) FROM default ORDER BY id LIMIT 4;",
+        &[
+            vec![Int(0), Int(-199)],
+            vec![Int(1), Int(-160)],
+            vec![Int(2), Int(-61)],
+            vec![Int(3), Int(-66)],
+        ],
+    );
+    test_query_ec(
+        "SELECT id, ROW_NUMBER() OVER (PARTITION BY enum ORDER BY id DESC) FROM default ORDER BY id;",
+        &[
+            vec![Int(0), Int(5)],
+            vec![Int(1), Int(4)],
+            vec![Int(2), Int(3)],
+            vec![Int(3), Int(3)],
+            vec![Int(4), Int(2)],
+            vec![Int(5), Int(2)],
+            vec![Int(6), Int(2)],
+            vec![Int(7), Int(1)],
+            vec![Int(8), Int(1)],
+            vec![Int(9), Int(1)],
+        ],
+    );
+    test_query_ec(
+        "SELECT id, LAG(negative) OVER (ORDER BY id), LEAD(id, 2, -1) OVER (ORDER BY id) FROM default WHERE id < 4 ORDER BY id;",
+        &[
+            vec![Int(0), Null, Int(2)],
+            vec![Int(1), Int(-199), Int(3)],
+            vec![Int(2), Int(39), Int(-1)],
+            vec![Int(3), Int(-100), Int(-1)],
+        ],
+    );
+    test_query_ec(
+        "SELECT id, AVG(id) OVER (ORDER BY id ROWS BETWEEN 1 PRECEDING AND 1 FOLLOWING), MAX(negative) OVER () \
+         FROM default ORDER BY id DESC LIMIT 2;",
+        &[vec![Int(9), Int(8), Int(4031)], vec![Int(8), Int(8), Int(4031)]],
+    );
+    test_query_ec(
+        "SELECT enum, id, COUNT(*) OVER (ORDER BY enum), MIN(negative) OVER (PARTITION BY enum) FROM default ORDER BY id LIMIT 4;",
+        &[
+            vec![Str("aa"), Int(0), Int(5), Int(-199)],
+            vec![Str("aa"), Int(1), Int(5), Int(-199)],
+            vec![Str("aa"), Int(2), Int(5), Int(-199)],
+            vec![Str("bb"), Int(3), Int(8), Int(-40)],
+        ],
+    );
+}
+
+#[test]
+fn test_window_functions_over_groups() {
+    test_query_ec(
+        "SELECT enum, COUNT(0), SUM(COUNT(0)) OVER (ORDER BY enum) FROM default GROUP BY enum ORDER BY enum;",
+        &[
+            vec![Str("aa"), Int(5), Int(5)],
+            vec![Str("bb"), Int(3), Int(8)],
+            vec![Str("cc"), Int(2), Int(10)],
+        ],
+    );
+    test_query_ec(
+        "SELECT enum, ROW_NUMBER() OVER (ORDER BY MAX(negative) DESC) FROM default ORDER BY enum;",
+        &[
+            vec![Str("aa"), Int(3)],
+            vec![Str("bb"), Int(1)],
+            vec![Str("cc"), Int(2)],
+        ],
+    );
+}
+
+#[test]
+fn test_query_timeout() {
+    use crate::Value::*;
+    use std::time::Duration;
+    let _ = env_logger::try_init();
+    let opts = Options {
+        query_timeout: Some(Duration::ZERO),
+        ..Options::default()
+    };
+    let locustdb = LocustDB::new(&opts);
+    let _ = block_on(
+        locustdb
+            .load_csv(LoadOptions::new("test_data/tiny.csv", "default").with_partition_size(40)),
+    );
+    let query = "SELECT COUNT(0) FROM default";
+    let result = block_on(locustdb.run_query(query, false, true, vec![]));
+    assert!(
+        matches!(&result, Err(QueryError::Aborted(msg)) if msg.contains("exceeded timeout")),
+        "{:?}",
+        result
+    );
+
+    let timeout = Some(Duration::from_secs(600));
+    let result = block_on(locustdb.run_query_with_timeout(query, false, true, vec![], timeout));
+    assert_eq!(result.unwrap().rows.unwrap(), vec![vec![Int(100)]]);
+    // Queries are unregistered once they complete
+    assert!(!locustdb.cancel_query(0));
+}
+
+#[test]
+fn test_cancel_query_during_partition() {
+    use std::thread;
+    use std::time::Duration;
+    let _ = env_logger::try_init();
+    let locustdb = LocustDB::memory_only();
+    block_on(locustdb.gen_table(locustdb::colgen::GenTable {
+        name: "test".to_string(),
+        partitions: 1,
+        partition_size: 1 << 22,
+        columns: vec![("x".to_string(), locustdb::colgen::int_uniform(0, 1 << 20))],
+    }))
+    .unwrap();
+    let query = "SELECT x, COUNT(0) FROM test";
+    let result = thread::scope(|s| {
+        // Cancel the query once it has started executing its only partition
+        s.spawn(|| loop {
+            let started = locustdb
+                .queries()
+                .into_iter()
+                .find(|q| q.query == query && q.partitions_scanned == 1);
+            if let Some(q) = started {
+                assert!(locustdb.cancel_query(q.id));
+                break;
+            }
+            thread::sleep(Duration::from_millis(1));
+        });
+        block_on(locustdb.run_query(query, false, true, vec![]))
+    });
+    assert!(
+        matches!(&result, Err(QueryError::Aborted(msg)) if msg.contains("canceled by request")),
+        "{:?}",
+        result
+    );
+}
+
+#[test]
+fn test_partition_pruning() {
+    use crate::Value::*;
+    let _ = env_logger::try_init();
+    let locustdb = LocustDB::memory_only();
+    let _ = block_on(
+        locustdb
+            .load_csv(LoadOptions::new("test_data/tiny.csv", "default").with_partition_size(40)),
+    );
+    let run = |query: &str| block_on(locustdb.run_query(query, false, true, vec![])).unwrap();
+
+    // Only the second partition contains timestamps larger than the largest timestamp in the first partition
+    let result = run("SELECT COUNT(0) FROM default WHERE ts > 1486388008");
+    assert_eq!(result.rows.unwrap(), vec![vec![Int(3)]]);
+    assert_eq!(result.stats.rows_scanned, 40);
+
+    let result = run("SELECT ts FROM default WHERE ts < 1456591230 OR first_name < 'A'");
+    assert_eq!(result.rows.unwrap(), Vec::<Vec<Value>>::new());
+    assert_eq!(result.stats.rows_scanned, 0);
+
+    let result = run("SELECT COUNT(0) FROM default WHERE ts >= 1456591230");
+    assert_eq!(result.rows.unwrap(), vec![vec![Int(100)]]);
+    assert_eq!(result.stats.rows_scanned, 100);
+
+    // String literals are converted to the unit of timestamp columns, millisecond values equal to the seconds above
+    block_on(
+        locustdb.load_csv(
+            LoadOptions::new("test_data/tiny.csv", "typed")
+                .with_schema("ts_ms,s,s,s,s,s,s,int,s")
+                .with_partition_size(40),
+        ),
+    )
+    .unwrap();
+    let result = run("SELECT COUNT(0) FROM typed WHERE ts > '1970-01-18T04:53:08.008Z'");
+    assert_eq!(result.rows.unwrap(), vec![vec![Int(3)]]);
+    assert_eq!(result.stats.rows_scanned, 40);
+}
+
+#[test]
+fn test_retention_policy() {
+    use crate::Value::*;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    let _ = env_logger::try_init();
+    let locustdb = LocustDB::memory_only();
+    let load = || {
+        block_on(
+            locustdb.load_csv(
+                LoadOptions::new("test_data/tiny.csv", "default").with_partition_size(40),
+            ),
+        )
+        .unwrap()
+    };
+    load();
+    let run = |query: &str| block_on(locustdb.run_query(query, false, true, vec![])).unwrap();
+
+    // Only the first partition is older than the cutoff, the last partition is also older but retained since
+    // partitions are deleted in order
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let policy = RetentionPolicy {
+        max_age: Some(MaxAge {
+            timestamp_column: "ts".to_string(),
+            unit: TimeUnit::Second,
+            max_age: Duration::from_secs(now - 1487000000),
+        }),
+        max_size_bytes: None,
+    };
+    locustdb.set_retention_policy("default", Some(policy.clone()));
+    assert_eq!(locustdb.retention_policies()["default"], policy);
+    assert_eq!(locustdb.apply_retention_policies(), 1);
+    assert_eq!(locustdb.apply_retention_policies(), 0);
+    let result = run("SELECT COUNT(0) FROM default");
+    assert_eq!(result.rows.unwrap(), vec![vec![Int(60)]]);
+
+    // New data is still appended after partitions have been deleted
+    load();
+    let result = run("SELECT COUNT(0), SUM(num) FROM default");
+    assert_eq!(result.rows.unwrap(), vec![vec![Int(160), Int(273)]]);
+
+    locustdb.set_retention_policy(
+        "default",
+        Some(RetentionPolicy {
+            max_age: None,
+            max_size_bytes: Some(1),
+        }),
+    );
+    assert!(locustdb.apply_retention_policies() > 0);
+    let result = run("SELECT ts FROM default");
+    assert_eq!(result.rows.unwrap(), Vec::<Vec<Value>>::new());
+
+    locustdb.set_retention_policy("default", None);
+    assert!(locustdb.retention_policies().is_empty());
+}
+
+#[test]
+fn test_mutations() {
+    use crate::Value::*;
+    let _ = env_logger::try_init();
+    let locustdb = LocustDB::memory_only();
+    let load = || {
+        block_on(
+            locustdb.load_csv(
+                LoadOptions::new("test_data/tiny.csv", "default").with_partition_size(40),
+            ),
+        )
+        .unwrap()
+    };
+    load();
+    let run = |query: &str| block_on(locustdb.run_query(query, false, true, vec![]));
+
+    // Removes rows from all partitions
+    let result = run("DELETE FROM default WHERE num > 1").unwrap();
+    assert_eq!(result.colnames, vec!["rows_deleted".to_string()]);
+    assert_eq!(result.rows.unwrap(), vec![vec![Int(43)]]);
+    let result = run("SELECT COUNT(0), SUM(num) FROM default").unwrap();
+    assert_eq!(result.rows.unwrap(), vec![vec![Int(57), Int(49)]]);
+    let result = run("DELETE FROM default WHERE num > 1").unwrap();
+    assert_eq!(result.rows.unwrap(), vec![vec![Int(0)]]);
+
+    let result = run("TRUNCATE default").unwrap();
+    assert_eq!(result.rows.unwrap(), vec![vec![Int(57)]]);
+    let result = run("SELECT COUNT(0) FROM default").unwrap();
+    assert_eq!(result.rows.unwrap(), Vec::<Vec<Value>>::new());
+
+    // Table can be dropped and recreated
+    load();
+    let result = run("DROP TABLE default").unwrap();
+    assert_eq!(result.rows.unwrap(), vec![vec![Int(100)]]);
+    run("SELECT COUNT(0) FROM default").unwrap_err();
+    run("DROP TABLE default").unwrap_err();
+    run("DROP TABLE IF EXISTS default").unwrap();
+    let result = run("SELECT COUNT(0) FROM _meta_tables WHERE name = 'default'").unwrap();
+    assert_eq!(result.rows.unwrap(), Vec::<Vec<Value>>::new());
+    load();
+    let result = run("SELECT COUNT(0), SUM(num) FROM default").unwrap();
+    assert_eq!(result.rows.unwrap(), vec![vec![Int(100), Int(168)]]);
+
+    run("DELETE FROM _meta_tables WHERE name = 'default'").unwrap_err();
+}
+
+#[test]
+fn test_queries_table() {
+    use crate::Value::*;
+    let _ = env_logger::try_init();
+    let locustdb = LocustDB::memory_only();
+    let _ = block_on(
+        locustdb
+            .load_csv(LoadOptions::new("test_data/tiny.csv", "default").with_partition_size(40)),
+    );
+    block_on(locustdb.run_query("SELECT COUNT(0) FROM default", false, true, vec![])).unwrap();
+    block_on(locustdb.run_query("SELECT COUNT(0) FROM missing", false, true, vec![])).unwrap_err();
+    let query = "SELECT id, query, status, rows_scanned, error FROM _queries ORDER BY id";
+    let result = block_on(locustdb.run_query(query, false, true, vec![])).unwrap();
+    assert_eq!(
+        result.rows.unwrap(),
+        vec![
+            vec![Int(0), Str("SELECT COUNT(0) FROM default".to_string()), Str("completed".to_string()), Int(100), Null],
+            vec![
+                Int(1),
+                Str("SELECT COUNT(0) FROM missing".to_string()),
+                Str("failed".to_string()),
+                Int(0),
+                Str("Not implemented: Table missing does not exist!".to_string()),
+            ],
+            vec![Int(2), Str(query.to_string()), Str("running".to_string()), Int(0), Null],
+        ]
+    );
+    assert_eq!(locustdb.queries().len(), 3);
+}
+
+#[test]
+fn test_query_log() {
+    use crate::Value::*;
+    let _ = env_logger::try_init();
+    let opts = Options {
+        query_log_table_name: Some("_query_log".to_string()),
+        ..Options::default()
+    };
+    let locustdb = LocustDB::new(&opts);
+    let _ = block_on(
+        locustdb
+            .load_csv(LoadOptions::new("test_data/tiny.csv", "default").with_partition_size(40)),
+    );
+    block_on(locustdb.run_query("SELECT COUNT(0) FROM default", false, true, vec![])).unwrap();
+    block_on(locustdb.run_query("SELECT COUNT(0) FROM missing", false, true, vec![])).unwrap_err();
+    block_on(locustdb.run_query("SELECT COUNT(0) FROM _queries", false, true, vec![])).unwrap();
+    let query = "SELECT query, status, rows_scanned, error FROM _query_log ORDER BY timestamp, query";
+    // Queries of the query log and of `_queries` are not logged
+    block_on(locustdb.run_query(query, false, true, vec![])).unwrap();
+    let result = block_on(locustdb.run_query(query, false, true, vec![])).unwrap();
+    assert_eq!(
+        result.rows.unwrap(),
+        vec![
+            vec![Str("SELECT COUNT(0) FROM default".to_string()), Str("completed".to_string()), Int(100), Null],
+            vec![
+                Str("SELECT COUNT(0) FROM missing".to_string()),
+                Str("failed".to_string()),
+                Int(0),
+                Str("Not implemented: Table missing does not exist!".to_string()),
+            ],
+        ]
+    );
+}